@@ -0,0 +1,133 @@
+use crate::*;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Default, Clone, Debug, PartialEq)]
+pub struct AcceptCounterOfferParams {
+    pub proposal_id: String,
+}
+
+// Define the context, passed in parameters when trigger from the proposal owner.
+#[derive(Accounts)]
+#[instruction(params: AcceptCounterOfferParams)]
+pub struct AcceptCounterOfferContext<'info> {
+    // The proposal owner pays for the space of the accepted option
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    #[account(
+        seeds = [PLATFORM_SEED],
+        bump = swap_registry.bump,
+    )]
+    pub swap_registry: Account<'info, SwapPlatformRegistry>,
+
+    #[account(
+        mut,
+        constraint = swap_proposal.is_proposal_address(swap_proposal.key(), &params.proposal_id) @ SwapError::InvalidProposal,
+        constraint = swap_proposal.owner == signer.key() @ SwapError::OnlyOwner,
+    )]
+    pub swap_proposal: Account<'info, SwapProposal>,
+
+    // The counter offer is closed and the rent goes back to the taker
+    #[account(
+        mut,
+        seeds = [COUNTER_OFFER_SEED, swap_proposal.key().as_ref(), taker.key().as_ref()],
+        bump = counter_offer.bump,
+        close = taker
+    )]
+    pub counter_offer: Account<'info, CounterOffer>,
+
+    #[account(mut)]
+    pub taker: SystemAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [TREASURY_SEED],
+        bump,
+    )]
+    pub swap_treasury: SystemAccount<'info>,
+
+    #[account(address = system_program::ID)]
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> AcceptCounterOfferContext<'info> {
+    pub fn execute(&mut self, _params: AcceptCounterOfferParams) -> Result<()> {
+        if !self.counter_offer.is_counter_offer_still_in_time_window() {
+            return Err(SwapError::CounterOfferExpired.into());
+        }
+
+        // the counter offer can only be accepted while no other participant reserved the proposal
+        if !self.swap_proposal.is_proposal_open_for_participants() {
+            return Err(SwapError::FulfillingIsNotAvailable.into());
+        }
+
+        let swap_option = self.counter_offer.swap_option.clone();
+        let taker = self.counter_offer.taker.clone();
+
+        // the option id must not collide with the options of the proposal
+        if self.swap_proposal.swap_options.iter().any(|option| option.id == swap_option.id) {
+            return Err(SwapError::InvalidValue.into());
+        }
+
+        // the accepted option must fit within the registry limits
+        if self.swap_proposal.swap_options.len() >= self.swap_registry.max_allowed_options as usize {
+            return Err(SwapError::InvalidValue.into());
+        }
+
+        // the counter offer becomes the fulfilled option, its asking items are already deposited
+        let swap_proposal = &mut self.swap_proposal;
+        swap_proposal.swap_options.push(swap_option.clone());
+        swap_proposal.fulfilled_by = taker;
+        swap_proposal.fulfilled_with_option_id = swap_option.id.clone();
+        swap_proposal.reserved_at = Clock::get()?.unix_timestamp as u64;
+        swap_proposal.status = SwapProposalStatus::Fulfilled;
+        swap_proposal.open_counter_offers = swap_proposal.open_counter_offers.saturating_sub(1);
+
+        // grow the account to hold the accepted option
+        let new_space = self.swap_proposal.space().max(self.swap_proposal.to_account_info().data_len());
+        resize_proposal(
+            self.swap_proposal.to_account_info(),
+            self.signer.to_account_info(),
+            self.system_program.to_account_info(),
+            new_space,
+        )?;
+
+        // Collect the fulfillment fee held by the counter offer
+        let fulfillment_fee = self.counter_offer.fulfillment_fee;
+        if fulfillment_fee > 0 {
+            **self.counter_offer.to_account_info().try_borrow_mut_lamports()? -= fulfillment_fee;
+            **self.swap_treasury.to_account_info().try_borrow_mut_lamports()? += fulfillment_fee;
+
+            swap_emit!(
+                FeeCollected {
+                    actor: taker,
+                    proposal_key: self.swap_proposal.key().clone(),
+                    mint_account: Pubkey::default(),
+                    amount: fulfillment_fee,
+                }
+            );
+        }
+
+        swap_emit!(
+            ProposalFulfilled {
+                status: SwapProposalStatus::Fulfilled,
+                actor: taker,
+                proposal_key: self.swap_proposal.key().clone(),
+                id: self.swap_proposal.id.clone()
+            }
+        );
+
+        // emit event
+        swap_emit!(
+            CounterOfferAccepted {
+                actor: self.signer.key().clone(),
+                proposal_key: self.swap_proposal.key().clone(),
+                id: self.swap_proposal.id.clone(),
+                counter_offer_key: self.counter_offer.key().clone(),
+                taker,
+                option_id: swap_option.id,
+            }
+        );
+
+        return Ok(());
+    }
+}
@@ -0,0 +1,49 @@
+use crate::*;
+
+// Define the context, passed in parameters when trigger from the pending owner.
+#[derive(Accounts)]
+pub struct AcceptOwnershipContext<'info> {
+    // The pending owner must sign to accept the ownership
+    #[account(
+        mut,
+        address = swap_registry.pending_owner @ SwapError::OnlyPendingOwner
+    )]
+    pub new_owner: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [PLATFORM_SEED],
+        bump = swap_registry.bump,
+    )]
+    pub swap_registry: Account<'info, SwapPlatformRegistry>,
+
+    #[account(address = system_program::ID)]
+    pub system_program: Program<'info, System>,
+}
+
+// implement the handler
+impl<'info> AcceptOwnershipContext<'info> {
+    pub fn execute(&mut self) -> Result<()> {
+        // A revoked transfer cannot be accepted
+        if self.swap_registry.pending_owner == Pubkey::default() {
+            return Err(SwapError::OnlyPendingOwner.into());
+        }
+
+        // Assigning values
+        let swap_registry = &mut self.swap_registry;
+        let previous_owner = swap_registry.owner.clone();
+        swap_registry.owner = self.new_owner.key().clone();
+        swap_registry.pending_owner = Pubkey::default();
+
+        // emit event
+        swap_emit!(
+            OwnershipTransferred {
+                actor: self.new_owner.key().clone(),
+                previous_owner,
+                new_owner: self.new_owner.key().clone(),
+            }
+        );
+
+        Ok(())
+    }
+}
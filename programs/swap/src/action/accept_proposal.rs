@@ -0,0 +1,300 @@
+use crate::*;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Default, Clone, Debug, PartialEq)]
+pub struct AcceptProposalParams {
+    pub proposal_id: String,
+    pub option_id: String,
+    // The vault bumps of the asking items, following the order of the option asking items
+    pub asking_vault_bumps: Vec<u8>,
+    // The vault bumps of the offered items, following the order of the offered items
+    pub offered_vault_bumps: Vec<u8>,
+    // Whether the asking items are pushed to the proposal owner right away instead of being redeemed later
+    pub settle_to_owner: bool,
+}
+
+// Define the context, the item accounts are passed through the remaining accounts as
+// [mint_account, token_account, swap_token_vault] in the following order:
+// - every asking item of the option not deposited yet, from the signer token accounts
+// - every offered item, to the signer token accounts
+// - every asking item of the option when settling to the owner, to the owner token accounts
+// followed by the platform vaults receiving the fees of per-proposal vaults,
+// the other token program when items of both token programs are mixed, the transfer-hook extra accounts
+// and the Token Metadata transfer accounts of programmable NFTs if any.
+// Native SOL items are passed as [system_program, wallet, native_vault] instead.
+#[derive(Accounts)]
+#[instruction(params: AcceptProposalParams)]
+pub struct AcceptProposalContext<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    #[account(
+        mut,
+        constraint = swap_proposal.is_proposal_address(swap_proposal.key(), &params.proposal_id) @ SwapError::InvalidProposal,
+    )]
+    pub swap_proposal: Account<'info, SwapProposal>,
+
+    #[account(
+        mut,
+        seeds = [PLATFORM_SEED],
+        bump = swap_registry.bump,
+    )]
+    pub swap_registry: Account<'info, SwapPlatformRegistry>,
+
+    #[account(
+        mut,
+        seeds = [TREASURY_SEED],
+        bump,
+    )]
+    pub swap_treasury: SystemAccount<'info>,
+
+    #[account(address = system_program::ID)]
+    pub system_program: Program<'info, System>,
+
+    /// CHECK: the token program or the Token-2022 program
+    #[account(constraint = token_interface::is_token_program(token_program.key) @ SwapError::InvalidTokenProgram)]
+    pub token_program: AccountInfo<'info>,
+}
+
+impl<'info> AcceptProposalContext<'info> {
+    pub fn execute(&mut self, params: AcceptProposalParams, remaining_accounts: &[AccountInfo<'info>]) -> Result<()> {
+        let mut cursor = 0;
+        let extra_accounts = extra_accounts(remaining_accounts, self.count_item_accounts(&params));
+        let transfer = ItemTransfer {
+            signer: self.signer.to_account_info(),
+            token_program: self.token_program.to_account_info(),
+            system_program: self.system_program.to_account_info(),
+            extra_accounts,
+        };
+
+        self.fulfill(&params, remaining_accounts, &transfer, &mut cursor)?;
+        self.redeem_offered_items(&params, remaining_accounts, &transfer, &mut cursor)?;
+
+        if params.settle_to_owner {
+            self.redeem_asking_items(&params, remaining_accounts, &transfer, &mut cursor)?;
+        }
+
+        // Check and update the final status of the proposal
+        self.swap_proposal.update_redeemed_status()?;
+
+        return Ok(());
+    }
+
+    // Count the swap items whose accounts are passed through the remaining accounts
+    fn count_item_accounts(&self, params: &AcceptProposalParams) -> usize {
+        let asking_items = self.swap_proposal.swap_options
+            .iter()
+            .find(|x| x.id == params.option_id)
+            .map(|option| option.asking_items.clone())
+            .unwrap_or_default();
+
+        let mut count = asking_items.iter().filter(|item| item.status == SwapItemStatus::Created).count()
+            + self.swap_proposal.offered_items.len();
+
+        if params.settle_to_owner {
+            count += asking_items.len();
+        }
+
+        return count;
+    }
+
+    fn fulfill(&mut self, params: &AcceptProposalParams, remaining_accounts: &[AccountInfo<'info>], transfer: &ItemTransfer<'_, 'info>, cursor: &mut usize) -> Result<()> {
+        let swap_proposal_key = self.swap_proposal.key().clone();
+
+        // check whether the proposal is still open for fulfilling
+        if !self.swap_proposal.is_proposal_open_for_fulfilling(
+            params.option_id.clone(),
+            self.signer.key().clone()
+        ) {
+            return Err(SwapError::FulfillingIsNotAvailable.into());
+        }
+
+        // private proposals can only be fulfilled by the allowed participants
+        if !self.swap_proposal.is_allowed_participant(self.signer.key().clone()) {
+            return Err(SwapError::OnlyAllowedParticipant.into());
+        }
+
+        // reserve the proposal
+        if self.swap_proposal.fulfilled_by == Pubkey::default() {
+            self.swap_proposal.reserved_at = Clock::get()?.unix_timestamp as u64;
+        }
+        self.swap_proposal.fulfilled_with_option_id = params.option_id.clone();
+        self.swap_proposal.fulfilled_by = self.signer.key().clone();
+
+        // find the option id
+        let option_index = self.swap_proposal.swap_options
+            .iter()
+            .position(|x| x.id == params.option_id)
+            .ok_or(SwapError::SwapOptionNotFound)?;
+
+        if self.swap_proposal.swap_options[option_index].asking_items.len() != params.asking_vault_bumps.len() {
+            return Err(SwapError::InvalidValue.into());
+        }
+
+        for index in 0..params.asking_vault_bumps.len() {
+            // skip the items deposited already
+            if self.swap_proposal.swap_options[option_index].asking_items[index].status != SwapItemStatus::Created {
+                continue;
+            }
+
+            // collection asks are fulfilled with the mint named by the participant
+            let item_accounts = next_item_accounts(remaining_accounts, cursor)?;
+            resolve_collection_item(
+                &self.swap_registry,
+                &mut self.swap_proposal.swap_options[option_index].asking_items[index],
+                &item_accounts[0],
+                transfer.extra_accounts,
+            )?;
+            let item = self.swap_proposal.swap_options[option_index].asking_items[index].clone();
+
+            let received_amount = deposit_item(
+                &self.swap_registry,
+                &self.swap_proposal,
+                &item,
+                ItemAccounts {
+                    accounts: item_accounts,
+                    vault_bump: params.asking_vault_bumps[index],
+                },
+                transfer,
+            )?;
+
+            // update the item status
+            let asking_item = &mut self.swap_proposal.swap_options[option_index].asking_items[index];
+            asking_item.status = SwapItemStatus::Deposited;
+            asking_item.amount = received_amount;
+            asking_item.owner = self.signer.key().clone();
+
+            swap_emit!(
+                ItemDeposited {
+                    id: item.id.clone(),
+                    status: SwapItemStatus::Deposited,
+                    proposal_key: swap_proposal_key,
+                    actor: self.signer.key().clone(),
+                }
+            );
+        }
+
+        // every asking item of the option is escrowed now
+        self.swap_proposal.status = SwapProposalStatus::Fulfilled;
+
+        // emit event
+        swap_emit!(
+            ProposalFulfilled {
+                status: SwapProposalStatus::Fulfilled,
+                actor: self.signer.key().clone(),
+                proposal_key: swap_proposal_key,
+                id: self.swap_proposal.id.clone()
+            }
+        );
+
+        // Charge the fulfillment fee to the participant
+        let fulfillment_fee = self.swap_registry.fee_config.fulfillment_fee;
+        if fulfillment_fee > 0 {
+            collect_platform_fee(
+                self.signer.to_account_info(),
+                self.swap_treasury.to_account_info(),
+                self.system_program.to_account_info(),
+                fulfillment_fee,
+            )?;
+
+            swap_emit!(
+                FeeCollected {
+                    actor: self.signer.key().clone(),
+                    proposal_key: swap_proposal_key,
+                    mint_account: Pubkey::default(),
+                    amount: fulfillment_fee,
+                }
+            );
+        }
+
+        return Ok(());
+    }
+
+    fn redeem_offered_items(&mut self, params: &AcceptProposalParams, remaining_accounts: &[AccountInfo<'info>], transfer: &ItemTransfer<'_, 'info>, cursor: &mut usize) -> Result<()> {
+        if self.swap_proposal.offered_items.len() != params.offered_vault_bumps.len() {
+            return Err(SwapError::InvalidValue.into());
+        }
+
+        for index in 0..self.swap_proposal.offered_items.len() {
+            let item = self.swap_proposal.offered_items[index].clone();
+
+            // Redeem is not available
+            if item.status != SwapItemStatus::Deposited {
+                return Err(SwapError::TransferTokenFromVaultIsNotAvailable.into());
+            }
+
+            release_item_to(
+                &mut self.swap_registry,
+                &self.swap_proposal,
+                &item,
+                SwapItemStatus::Redeemed,
+                self.signer.key(),
+                ItemAccounts {
+                    accounts: next_item_accounts(remaining_accounts, cursor)?,
+                    vault_bump: params.offered_vault_bumps[index],
+                },
+                transfer,
+            )?;
+
+            // update the item status
+            self.swap_proposal.offered_items[index].status = SwapItemStatus::Redeemed;
+
+            swap_emit!(
+                ItemRedeemed {
+                    id: item.id.clone(),
+                    proposal_key: self.swap_proposal.key().clone(),
+                    status: SwapItemStatus::Redeemed,
+                    actor: self.signer.key().clone()
+                }
+            );
+        }
+
+        return Ok(());
+    }
+
+    fn redeem_asking_items(&mut self, params: &AcceptProposalParams, remaining_accounts: &[AccountInfo<'info>], transfer: &ItemTransfer<'_, 'info>, cursor: &mut usize) -> Result<()> {
+        let option_id = self.swap_proposal.fulfilled_with_option_id.clone();
+        let proposal_owner = self.swap_proposal.owner.clone();
+
+        // find the option id
+        let option_index = self.swap_proposal.swap_options
+            .iter()
+            .position(|x| x.id == option_id)
+            .ok_or(SwapError::SwapOptionNotFound)?;
+
+        for index in 0..self.swap_proposal.swap_options[option_index].asking_items.len() {
+            let item = self.swap_proposal.swap_options[option_index].asking_items[index].clone();
+
+            // Redeem is not available
+            if item.status != SwapItemStatus::Deposited {
+                return Err(SwapError::TransferTokenFromVaultIsNotAvailable.into());
+            }
+
+            release_item_to(
+                &mut self.swap_registry,
+                &self.swap_proposal,
+                &item,
+                SwapItemStatus::Redeemed,
+                proposal_owner,
+                ItemAccounts {
+                    accounts: next_item_accounts(remaining_accounts, cursor)?,
+                    vault_bump: params.asking_vault_bumps[index],
+                },
+                transfer,
+            )?;
+
+            // update the item status
+            self.swap_proposal.swap_options[option_index].asking_items[index].status = SwapItemStatus::Redeemed;
+
+            swap_emit!(
+                ItemRedeemed {
+                    id: item.id.clone(),
+                    proposal_key: self.swap_proposal.key().clone(),
+                    status: SwapItemStatus::Redeemed,
+                    actor: self.signer.key().clone()
+                }
+            );
+        }
+
+        return Ok(());
+    }
+}
@@ -15,8 +15,7 @@ pub struct CancelProposalContext<'info> {
 
     #[account(
         mut,
-        seeds = [PROPOSAL_SEED, params.id.as_bytes().as_ref()],
-        bump = swap_proposal.bump,
+        constraint = swap_proposal.is_proposal_address(swap_proposal.key(), &params.id) @ SwapError::InvalidProposal,
     )]
     pub swap_proposal: Account<'info, SwapProposal>,
 
@@ -0,0 +1,47 @@
+use crate::*;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Default, Clone, Debug, PartialEq)]
+pub struct CloseProposalParams {
+    id: String
+}
+
+// Define the context, passed in parameters when trigger from the proposal owner.
+#[derive(Accounts)]
+#[instruction(params: CloseProposalParams)]
+pub struct CloseProposalContext<'info> {
+    // The proposal owner receives the rent back
+    #[account(mut)]
+    pub proposal_owner: Signer<'info>,
+
+    #[account(
+        mut,
+        constraint = swap_proposal.is_proposal_address(swap_proposal.key(), &params.id) @ SwapError::InvalidProposal,
+        constraint = swap_proposal.owner == proposal_owner.key() @ SwapError::OnlyOwner,
+        close = proposal_owner
+    )]
+    pub swap_proposal: Account<'info, SwapProposal>,
+
+    #[account(address = system_program::ID)]
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> CloseProposalContext<'info> {
+    pub fn execute(&mut self, _params: CloseProposalParams) -> Result<()> {
+        // Only settled proposals can be closed
+        if !self.swap_proposal.is_proposal_closable() {
+            return Err(SwapError::ProposalCannotBeClosed.into());
+        }
+
+        // emit event
+        swap_emit!(
+            ProposalClosed {
+                actor: self.proposal_owner.key().clone(),
+                status: self.swap_proposal.status,
+                id: self.swap_proposal.id.clone(),
+                proposal_key: self.swap_proposal.key().clone(),
+            }
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,262 @@
+use crate::*;
+use anchor_spl::associated_token::AssociatedToken;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Default, Clone, Debug, PartialEq)]
+pub struct CrankSettleParams {
+    pub proposal_id: String,
+    // The vault bumps of the deposited items, following the order of the items
+    pub swap_token_vault_bumps: Vec<u8>,
+}
+
+// Define the context, public to anyone. The item accounts are passed through the remaining accounts
+// as [mint_account, associated_token_account, swap_token_vault] for every deposited item:
+// the offered items first, then the asking items of the fulfilled option, then the pending refunds,
+// followed by the wallets owning the pending refunds whose associated token account must be created,
+// the platform vaults receiving the fees of per-proposal vaults,
+// the other token program when items of both token programs are mixed and the transfer-hook extra accounts if any.
+// Native SOL items are passed as [system_program, wallet, native_vault] instead.
+#[derive(Accounts)]
+#[instruction(params: CrankSettleParams)]
+pub struct CrankSettleContext<'info> {
+    // The crank caller pays for the associated token accounts and receives the bounty
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [PLATFORM_SEED],
+        bump = swap_registry.bump,
+    )]
+    pub swap_registry: Account<'info, SwapPlatformRegistry>,
+
+    #[account(
+        mut,
+        constraint = swap_proposal.is_proposal_address(swap_proposal.key(), &params.proposal_id) @ SwapError::InvalidProposal,
+    )]
+    pub swap_proposal: Account<'info, SwapProposal>,
+
+    #[account(mut, address = swap_proposal.owner @ SwapError::OnlyOwner)]
+    /// CHECK: the address is checked against the proposal owner, it receives the items and the proposal rent.
+    pub proposal_owner: AccountInfo<'info>,
+
+    #[account(address = swap_proposal.fulfilled_by @ SwapError::OnlyParticipant)]
+    /// CHECK: the address is checked against the fulfilled participant, it receives the items.
+    /// Without a taker the address is the default key, which cannot be writable, the handler checks the writability otherwise.
+    pub participant: AccountInfo<'info>,
+
+    #[account(address = system_program::ID)]
+    pub system_program: Program<'info, System>,
+
+    /// CHECK: the token program or the Token-2022 program
+    #[account(constraint = token_interface::is_token_program(token_program.key) @ SwapError::InvalidTokenProgram)]
+    pub token_program: AccountInfo<'info>,
+
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
+
+impl<'info> CrankSettleContext<'info> {
+    pub fn execute(&mut self, params: CrankSettleParams, remaining_accounts: &[AccountInfo<'info>]) -> Result<()> {
+        // fulfilled proposals are redeemed, canceled and expired proposals are withdrawn
+        let is_settleable = self.swap_proposal.is_proposal_redeemable() || self.swap_proposal.is_proposal_withdrawable();
+        let status = if self.swap_proposal.is_proposal_redeemable() {
+            SwapItemStatus::Redeemed
+        } else {
+            SwapItemStatus::Withdrawn
+        };
+
+        // the items of released reservations are pushed back to their owners at any time
+        if !is_settleable && !self.swap_proposal.has_pending_refunds() {
+            return Err(SwapError::ProposalCannotBeSettled.into());
+        }
+
+        // the participant receives the items once a taker reserved an option
+        if is_settleable && self.swap_proposal.fulfilled_by != Pubkey::default() && !self.participant.is_writable {
+            return Err(ErrorCode::ConstraintMut.into());
+        }
+
+        // find the option id
+        let option_index = self.swap_proposal.swap_options
+            .iter()
+            .position(|x| x.id == self.swap_proposal.fulfilled_with_option_id);
+
+        // collect the deposited items with their rightful owner
+        let mut deposited_items: Vec<(ItemLocation, Pubkey)> = vec![];
+        if is_settleable {
+            for (index, item) in self.swap_proposal.offered_items.iter().enumerate() {
+                if item.status == SwapItemStatus::Deposited {
+                    let recipient = match status {
+                        SwapItemStatus::Redeemed => self.participant.key(),
+                        _ => self.proposal_owner.key(),
+                    };
+                    deposited_items.push((ItemLocation::Offered(index), recipient));
+                }
+            }
+
+            if let Some(option_index) = option_index {
+                for (index, item) in self.swap_proposal.swap_options[option_index].asking_items.iter().enumerate() {
+                    if item.status == SwapItemStatus::Deposited {
+                        let recipient = match status {
+                            SwapItemStatus::Redeemed => self.proposal_owner.key(),
+                            _ => self.participant.key(),
+                        };
+                        deposited_items.push((ItemLocation::Asking(option_index, index), recipient));
+                    }
+                }
+            }
+        }
+
+        for (index, item) in self.swap_proposal.pending_refunds.iter().enumerate() {
+            if item.status == SwapItemStatus::Deposited {
+                deposited_items.push((ItemLocation::PendingRefund(index), item.owner));
+            }
+        }
+
+        if deposited_items.len() != params.swap_token_vault_bumps.len() {
+            return Err(SwapError::InvalidValue.into());
+        }
+
+        let mut cursor = 0;
+        let extra_accounts = extra_accounts(remaining_accounts, deposited_items.len());
+        let transfer = ItemTransfer {
+            signer: self.signer.to_account_info(),
+            token_program: self.token_program.to_account_info(),
+            system_program: self.system_program.to_account_info(),
+            extra_accounts,
+        };
+        for (position, (location, recipient)) in deposited_items.into_iter().enumerate() {
+            let (item, item_status) = match location {
+                ItemLocation::Offered(index) => (self.swap_proposal.offered_items[index].clone(), status),
+                ItemLocation::Asking(option_index, index) => (self.swap_proposal.swap_options[option_index].asking_items[index].clone(), status),
+                ItemLocation::PendingRefund(index) => (self.swap_proposal.pending_refunds[index].clone(), SwapItemStatus::Withdrawn),
+            };
+            let accounts = next_item_accounts(remaining_accounts, &mut cursor)?;
+
+            // The items are pushed to the associated token account of the rightful owner, native SOL goes to the wallet
+            if item.item_type != SwapItemType::NativeSol {
+                let token_program = token_interface::token_program_of(
+                    &accounts[0],
+                    &self.token_program.to_account_info(),
+                    extra_accounts,
+                )?;
+
+                if accounts[1].key() != token_interface::get_associated_token_address_with_program_id(
+                    &recipient,
+                    &item.mint_account,
+                    &token_program.key(),
+                ) {
+                    return Err(SwapError::InvalidTokenAccount.into());
+                }
+
+                if accounts[1].data_is_empty() {
+                    token_interface::create_associated_token_account(
+                        self.signer.to_account_info(),
+                        accounts[1].clone(),
+                        self.recipient_account(recipient, extra_accounts)?,
+                        accounts[0].clone(),
+                        self.system_program.to_account_info(),
+                        token_program,
+                        self.associated_token_program.to_account_info(),
+                    )?;
+                }
+            }
+
+            release_item_to(
+                &mut self.swap_registry,
+                &self.swap_proposal,
+                &item,
+                item_status,
+                recipient,
+                ItemAccounts {
+                    accounts,
+                    vault_bump: params.swap_token_vault_bumps[position],
+                },
+                &transfer,
+            )?;
+
+            // update the item status
+            match location {
+                ItemLocation::Offered(index) => self.swap_proposal.offered_items[index].status = item_status,
+                ItemLocation::Asking(option_index, index) => self.swap_proposal.swap_options[option_index].asking_items[index].status = item_status,
+                ItemLocation::PendingRefund(index) => self.swap_proposal.pending_refunds[index].status = item_status,
+            };
+
+            if item_status == SwapItemStatus::Redeemed {
+                swap_emit!(
+                    ItemRedeemed {
+                        id: item.id.clone(),
+                        proposal_key: self.swap_proposal.key().clone(),
+                        status: item_status,
+                        actor: self.signer.key().clone()
+                    }
+                );
+            } else {
+                swap_emit!(
+                    ItemWithdrawn {
+                        id: item.id.clone(),
+                        proposal_key: self.swap_proposal.key().clone(),
+                        status: item_status,
+                        actor: self.signer.key().clone()
+                    }
+                );
+            }
+        }
+
+        // Check and update the final status of the proposal
+        if is_settleable && status == SwapItemStatus::Redeemed {
+            self.swap_proposal.update_redeemed_status()?;
+        } else if is_settleable {
+            self.swap_proposal.update_withdrawn_status()?;
+        }
+
+        // Settled proposals are closed, the crank caller is paid out of the rent
+        let mut bounty = 0;
+        if self.swap_proposal.is_proposal_closable() {
+            bounty = self.swap_registry.crank_bounty.min(self.swap_proposal.to_account_info().lamports());
+            if bounty > 0 {
+                **self.swap_proposal.to_account_info().try_borrow_mut_lamports()? -= bounty;
+                **self.signer.to_account_info().try_borrow_mut_lamports()? += bounty;
+            }
+
+            self.swap_proposal.close(self.proposal_owner.to_account_info())?;
+
+            swap_emit!(
+                ProposalClosed {
+                    actor: self.signer.key().clone(),
+                    status: self.swap_proposal.status,
+                    id: self.swap_proposal.id.clone(),
+                    proposal_key: self.swap_proposal.key().clone(),
+                }
+            );
+        }
+
+        swap_emit!(
+            ProposalSettled {
+                actor: self.signer.key().clone(),
+                proposal_key: self.swap_proposal.key().clone(),
+                id: self.swap_proposal.id.clone(),
+                status: self.swap_proposal.status,
+                bounty,
+            }
+        );
+
+        return Ok(());
+    }
+
+    // Find the wallet owning the associated token account of an item, the owners of the pending refunds
+    // other than the proposal owner and the participant are looked up in the extra accounts.
+    fn recipient_account(&self, recipient: Pubkey, extra_accounts: &[AccountInfo<'info>]) -> Result<AccountInfo<'info>> {
+        if recipient == self.proposal_owner.key() {
+            return Ok(self.proposal_owner.to_account_info());
+        }
+
+        if recipient == self.participant.key() {
+            return Ok(self.participant.to_account_info());
+        }
+
+        return extra_accounts
+            .iter()
+            .find(|account| account.key() == recipient)
+            .cloned()
+            .ok_or(SwapError::InvalidTokenAccountOwner.into());
+    }
+}
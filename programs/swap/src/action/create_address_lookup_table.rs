@@ -20,6 +20,9 @@ pub struct CreateAddressLookupTableContext<'info> {
         mut,
         seeds = [LOOKUP_TABLE_SEED, signer.key().as_ref()],
         bump = lookup_table_registry.bump,
+        realloc = lookup_table_registry.space_with(1, lookup_table_registry.to_account_info().data_len()),
+        realloc::payer = signer,
+        realloc::zero = false,
     )]
     pub lookup_table_registry: Account<'info, LookupTableRegistry>,
 
@@ -39,16 +42,16 @@ pub struct CreateAddressLookupTableContext<'info> {
 impl<'info> CreateAddressLookupTableContext<'info> {
     pub fn execute(&mut self, params: CreateAddressLookupTableParams) -> Result<()> {
         let slot = params.slot;
-        self.create_lookup_table(slot).unwrap();
+        self.create_lookup_table(slot)?;
 
         return Ok(());
     }
 
     fn create_lookup_table(&mut self, slot: u64) -> Result<()> {
-        let lookup_table_registry = self.lookup_table_registry.borrow_mut();
+        let lookup_table_registry = &mut self.lookup_table_registry;
 
         let (create_ix, table_pk) =
-            instruction::create_lookup_table(
+            solana_address_lookup_table_program::instruction::create_lookup_table(
                 self.signer.key(),
                 self.signer.key(),
                 slot,
@@ -61,7 +64,7 @@ impl<'info> CreateAddressLookupTableContext<'info> {
                 self.signer.to_account_info(),
                 self.signer.to_account_info(),
             ]
-        ).unwrap();
+        )?;
 
         lookup_table_registry.lookup_table_addresses.push(table_pk);
 
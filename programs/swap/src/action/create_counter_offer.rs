@@ -0,0 +1,178 @@
+use crate::*;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Default, Clone, Debug, PartialEq)]
+pub struct CreateCounterOfferParams {
+    pub proposal_id: String,
+
+    // define the option proposed by the taker
+    pub swap_option: SwapItemOptionInfo,
+
+    // define expiry date of the counter offer
+    pub expired_at: u64,
+
+    // The vault bumps of the asking items, following the order of the proposed asking items
+    pub swap_token_vault_bumps: Vec<u8>,
+}
+
+impl CreateCounterOfferParams {
+    // Compute the counter offer account size from the proposed option
+    pub fn space(&self) -> usize {
+        return CounterOffer::space_for(self.swap_option.space());
+    }
+}
+
+// Define the context, the item accounts are passed through the remaining accounts
+// as [mint_account, signer_token_account, swap_token_vault] for every proposed asking item,
+// or as [system_program, signer, native_vault] for native SOL items,
+// followed by the other token program when items of both token programs are mixed,
+// the transfer-hook extra accounts and the Token Metadata transfer accounts of programmable NFTs if any.
+#[derive(Accounts)]
+#[instruction(params: CreateCounterOfferParams)]
+pub struct CreateCounterOfferContext<'info> {
+    // The taker pays for the counter offer account
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    #[account(
+        seeds = [PLATFORM_SEED],
+        bump = swap_registry.bump,
+    )]
+    pub swap_registry: Account<'info, SwapPlatformRegistry>,
+
+    #[account(
+        mut,
+        constraint = swap_proposal.is_proposal_address(swap_proposal.key(), &params.proposal_id) @ SwapError::InvalidProposal,
+    )]
+    pub swap_proposal: Account<'info, SwapProposal>,
+
+    #[account(
+        init,
+        seeds = [COUNTER_OFFER_SEED, swap_proposal.key().as_ref(), signer.key().as_ref()],
+        payer = signer,
+        space = params.space(),
+        bump
+    )]
+    pub counter_offer: Account<'info, CounterOffer>,
+
+    #[account(address = system_program::ID)]
+    pub system_program: Program<'info, System>,
+
+    /// CHECK: the token program or the Token-2022 program
+    #[account(constraint = token_interface::is_token_program(token_program.key) @ SwapError::InvalidTokenProgram)]
+    pub token_program: AccountInfo<'info>,
+}
+
+impl<'info> CreateCounterOfferContext<'info> {
+    pub fn execute(&mut self, params: CreateCounterOfferParams, bump: u8, remaining_accounts: &[AccountInfo<'info>]) -> Result<()> {
+        // Counter offers can only be made while the proposal is open for participants
+        if !self.swap_proposal.is_proposal_open_for_participants() {
+            return Err(SwapError::FulfillingIsNotAvailable.into());
+        }
+
+        // the proposal owner cannot counter its own proposal
+        if self.swap_proposal.is_proposal_owner(self.signer.key().clone()) {
+            return Err(SwapError::OnlyParticipant.into());
+        }
+
+        // private proposals can only be countered by the allowed participants
+        if !self.swap_proposal.is_allowed_participant(self.signer.key().clone()) {
+            return Err(SwapError::OnlyAllowedParticipant.into());
+        }
+
+        if params.expired_at <= Clock::get()?.unix_timestamp as u64 {
+            return Err(SwapError::InvalidValue.into());
+        }
+
+        let swap_option = params.swap_option.to_swap_option(self.signer.key().clone());
+
+        // the option id must not collide with the options of the proposal
+        if swap_option.id == String::default()
+            || swap_option.asking_items.len() < 1
+            || self.swap_proposal.swap_options.iter().any(|option| option.id == swap_option.id)
+        {
+            return Err(SwapError::InvalidValue.into());
+        }
+
+        // Check if user want to offer un-allowed mint tokens
+        self.swap_registry.validate_swap_items(&swap_option.asking_items)?;
+
+        if swap_option.asking_items.len() != params.swap_token_vault_bumps.len() {
+            return Err(SwapError::InvalidValue.into());
+        }
+
+        let counter_offer = &mut self.counter_offer;
+        counter_offer.bump = bump;
+        counter_offer.proposal = self.swap_proposal.key().clone();
+        counter_offer.taker = self.signer.key().clone();
+        counter_offer.expired_at = params.expired_at;
+        counter_offer.swap_option = swap_option;
+
+        // escrow the proposed asking items up front
+        let mut cursor = 0;
+        let extra_accounts = extra_accounts(remaining_accounts, self.counter_offer.swap_option.asking_items.len());
+        let transfer = ItemTransfer {
+            signer: self.signer.to_account_info(),
+            token_program: self.token_program.to_account_info(),
+            system_program: self.system_program.to_account_info(),
+            extra_accounts,
+        };
+        for index in 0..self.counter_offer.swap_option.asking_items.len() {
+            let item = self.counter_offer.swap_option.asking_items[index].clone();
+
+            let received_amount = deposit_item(
+                &self.swap_registry,
+                &self.swap_proposal,
+                &item,
+                ItemAccounts {
+                    accounts: next_item_accounts(remaining_accounts, &mut cursor)?,
+                    vault_bump: params.swap_token_vault_bumps[index],
+                },
+                &transfer,
+            )?;
+
+            // update the item status
+            self.counter_offer.swap_option.asking_items[index].status = SwapItemStatus::Deposited;
+            self.counter_offer.swap_option.asking_items[index].amount = received_amount;
+
+            swap_emit!(
+                ItemDeposited {
+                    id: item.id.clone(),
+                    status: SwapItemStatus::Deposited,
+                    proposal_key: self.swap_proposal.key().clone(),
+                    actor: self.signer.key().clone(),
+                }
+            );
+        }
+
+        // Hold the fulfillment fee in the counter offer, it is collected once the counter offer is accepted
+        let fulfillment_fee = self.swap_registry.fee_config.fulfillment_fee;
+        if fulfillment_fee > 0 {
+            collect_platform_fee(
+                self.signer.to_account_info(),
+                self.counter_offer.to_account_info(),
+                self.system_program.to_account_info(),
+                fulfillment_fee,
+            )?;
+        }
+        self.counter_offer.fulfillment_fee = fulfillment_fee;
+
+        // the proposal cannot be closed while the counter offer holds escrowed items
+        self.swap_proposal.open_counter_offers = self.swap_proposal.open_counter_offers
+            .checked_add(1)
+            .ok_or(SwapError::InvalidValue)?;
+
+        // emit event
+        swap_emit!(
+            CounterOfferCreated {
+                actor: self.signer.key().clone(),
+                proposal_key: self.swap_proposal.key().clone(),
+                id: self.swap_proposal.id.clone(),
+                counter_offer_key: self.counter_offer.key().clone(),
+                option_id: self.counter_offer.swap_option.id.clone(),
+                expired_at: self.counter_offer.expired_at,
+            }
+        );
+
+        return Ok(());
+    }
+}
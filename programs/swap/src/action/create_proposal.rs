@@ -8,10 +8,54 @@ pub struct SwapItemInfo {
     pub item_type: SwapItemType
 }
 
+impl SwapItemInfo {
+    // Convert the submitted item into a swap item held by the owner
+    pub fn to_swap_item(&self, owner: Pubkey) -> SwapItem {
+        let mut swap_item = SwapItem::default();
+        swap_item.amount = self.amount;
+        swap_item.mint_account = self.mint_account;
+        swap_item.id = self.id.clone();
+        swap_item.item_type = self.item_type;
+        swap_item.owner = owner;
+
+        return swap_item;
+    }
+}
+
 #[derive(AnchorSerialize, AnchorDeserialize, Default, Clone, Debug, PartialEq)]
 pub struct SwapItemOptionInfo {
-    id: String,
-    asking_items: Vec<SwapItemInfo>,
+    pub id: String,
+    pub asking_items: Vec<SwapItemInfo>,
+}
+
+impl SwapItemOptionInfo {
+    // Convert the submitted option into a swap option whose asking items are held by the owner
+    pub fn to_swap_option(&self, owner: Pubkey) -> SwapOption {
+        let mut swap_option = SwapOption::default();
+
+        swap_option.id = self.id.clone();
+        swap_option.asking_items = self.asking_items.iter()
+            .map(|item| item.to_swap_item(owner))
+            .collect();
+
+        return swap_option;
+    }
+
+    // Compute the serialized size of the swap option
+    pub fn space(&self) -> usize {
+        return SwapOption::space_for(
+            &self.id,
+            self.asking_items.iter().map(|item| SwapItem::space_for(&item.id)).sum(),
+        );
+    }
+
+    // Compute the serialized size of the collection asks recorded for the swap option
+    pub fn collection_asks_space(&self) -> usize {
+        return self.asking_items.iter()
+            .filter(|item| item.item_type == SwapItemType::Collection)
+            .map(|item| CollectionAsk::space_for(&self.id, &item.id))
+            .sum();
+    }
 }
 
 // Define params
@@ -28,6 +72,25 @@ pub struct CreateProposalParams {
 
     // define expiry date
     pub expired_at: u64,
+
+    // define where the escrowed tokens are held, fallback to the registry default
+    pub vault_mode: Option<VaultMode>,
+
+    // define the only participants allowed to fulfill the proposal, open to anyone if not provided
+    pub allowed_participants: Option<Vec<Pubkey>>,
+}
+
+impl CreateProposalParams {
+    // Compute the proposal account size from the submitted items and options
+    pub fn space(&self) -> usize {
+        return SwapProposal::space_for(
+            &self.id,
+            self.swap_options.iter().map(|option| option.id.len()).max().unwrap_or(0),
+            self.offered_items.iter().map(|item| SwapItem::space_for(&item.id)).sum(),
+            self.swap_options.iter().map(|option| option.space()).sum(),
+        ) + self.allowed_participants.as_ref().map(|participants| participants.len()).unwrap_or(0) * 32
+            + self.swap_options.iter().map(|option| option.collection_asks_space()).sum::<usize>();
+    }
 }
 
 // Define the context, passed in parameters when trigger from deployer.
@@ -40,9 +103,9 @@ pub struct CreateProposalContext<'info> {
 
     #[account(
         init,
-        seeds = [PROPOSAL_SEED, params.id.as_bytes().as_ref()],
+        seeds = [PROPOSAL_SEED, proposal_owner.key().as_ref(), SwapProposal::hash_id(&params.id).as_ref()],
         payer = proposal_owner,
-        space = 10240,
+        space = params.space(),
         bump
     )]
     pub swap_proposal: Account<'info, SwapProposal>,
@@ -53,6 +116,13 @@ pub struct CreateProposalContext<'info> {
     )]
     pub swap_registry: Account<'info, SwapPlatformRegistry>,
 
+    #[account(
+        mut,
+        seeds = [TREASURY_SEED],
+        bump,
+    )]
+    pub swap_treasury: SystemAccount<'info>,
+
     #[account(address = system_program::ID)]
     pub system_program: Program<'info, System>,
 }
@@ -64,44 +134,46 @@ impl<'info> CreateProposalContext<'info> {
         swap_proposal.owner = *self.proposal_owner.key;
 
         // Compute asking items
-        swap_proposal.swap_options = params.swap_options.into_iter().map(|option| {
-            let mut swap_option = SwapOption::default();
-
-            swap_option.id = option.id;
-            swap_option.asking_items =  option.asking_items.into_iter()
-                .map(|item| {
-                    let mut swap_item = SwapItem::default();
-                    swap_item.amount = item.amount;
-                    swap_item.mint_account = item.mint_account;
-                    swap_item.id = item.id;
-                    swap_item.owner = Pubkey::default();
-                    swap_item.item_type = item.item_type;
-
-                    return swap_item;
-                }).collect();
-
-            return swap_option;
-        }).collect();
+        swap_proposal.swap_options = params.swap_options.iter()
+            .map(|option| option.to_swap_option(Pubkey::default()))
+            .collect();
+        swap_proposal.record_collection_asks();
 
         // Compute offered items
-        swap_proposal.offered_items = params.offered_items.into_iter().map(|item| {
-            let mut swap_item = SwapItem::default();
-            swap_item.amount = item.amount;
-            swap_item.mint_account = item.mint_account;
-            swap_item.id = item.id;
-            swap_item.item_type = item.item_type;
-            swap_item.owner = self.proposal_owner.key().clone();
-
-            return swap_item;
-        }).collect();
+        swap_proposal.offered_items = params.offered_items.iter()
+            .map(|item| item.to_swap_item(self.proposal_owner.key().clone()))
+            .collect();
 
 
+        swap_proposal.allowed_participants = params.allowed_participants.unwrap_or_default();
+        swap_proposal.vault_mode = params.vault_mode.unwrap_or(self.swap_registry.default_vault_mode);
         swap_proposal.expired_at = params.expired_at;
         swap_proposal.id = params.id;
         swap_proposal.bump = bump;
+        swap_proposal.seed_version = ProposalSeedVersion::OwnerScoped;
 
         // Now to validate data state
-        self.handle_post_initialized().unwrap();
+        self.handle_post_initialized()?;
+
+        // Charge the proposal creation fee
+        let proposal_creation_fee = self.swap_registry.fee_config.proposal_creation_fee;
+        if proposal_creation_fee > 0 {
+            collect_platform_fee(
+                self.proposal_owner.to_account_info(),
+                self.swap_treasury.to_account_info(),
+                self.system_program.to_account_info(),
+                proposal_creation_fee,
+            )?;
+
+            swap_emit!(
+                FeeCollected {
+                    actor: self.proposal_owner.key().clone(),
+                    proposal_key: self.swap_proposal.key().clone(),
+                    mint_account: Pubkey::default(),
+                    amount: proposal_creation_fee,
+                }
+            );
+        }
 
         swap_emit!(
           ProposalCreated {
@@ -117,41 +189,6 @@ impl<'info> CreateProposalContext<'info> {
     }
 
     // validate mint accounts
-    fn validate_mint_accounts(&self, params: &Vec<SwapItem>) -> Result<()> {
-        // Cannot exceed max allowed items
-        if self.swap_registry.max_allowed_items < params.len() as u8 {
-            return Err(SwapError::InvalidValue.into());
-        }
-
-        // Check if user submitted un-allowed mint tokens
-        let iterator = params.iter();
-
-        for item in iterator {
-            if !self.swap_registry.is_mint_account_enabled(item.mint_account) {
-                return Err(SwapError::UnAllowedMintToken.into());
-            }
-        }
-
-        return Ok(());
-    }
-
-    fn validate_swap_options(&self, params: &Vec<SwapOption>) -> Result<()> {
-        // validate input
-        if self.swap_registry.max_allowed_options < params.len() as u8 {
-            return Err(SwapError::InvalidValue.into());
-        }
-
-        let iterator = params.iter();
-
-        // validate if the tokens were allowed
-        for item in iterator {
-            self.validate_mint_accounts(&item.asking_items).unwrap();
-        }
-
-        // ok
-        return Ok(());
-    }
-
     fn handle_post_initialized(&mut self) -> Result<()> {
         if self.swap_proposal.id == "".to_string() {
             return Err(SwapError::InvalidValue.into());
@@ -173,15 +210,19 @@ impl<'info> CreateProposalContext<'info> {
             return Err(SwapError::InvalidValue.into());
         }
 
-        if self.swap_proposal.expired_at <= Clock::get().unwrap().unix_timestamp as u64 {
+        if self.swap_proposal.expired_at <= Clock::get()?.unix_timestamp as u64 {
+            return Err(SwapError::InvalidValue.into());
+        }
+
+        if self.swap_proposal.allowed_participants.len() > MAX_ALLOWED_PARTICIPANTS {
             return Err(SwapError::InvalidValue.into());
         }
 
         // Check if user want to offer un-allowed mint tokens
-        self.validate_mint_accounts(&self.swap_proposal.offered_items).unwrap();
+        self.swap_registry.validate_swap_items(&self.swap_proposal.offered_items)?;
 
         // Check if user want to ask for un-allowed mint tokens
-        self.validate_swap_options(&self.swap_proposal.swap_options).unwrap();
+        self.swap_registry.validate_swap_options(&self.swap_proposal.swap_options)?;
 
         // ok
         return Ok(());
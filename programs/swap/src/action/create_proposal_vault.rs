@@ -0,0 +1,78 @@
+use crate::*;
+
+// Define the context, passed in parameters when trigger from the depositor.
+#[derive(Accounts)]
+pub struct CreateProposalVaultContext<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    #[account(
+        seeds = [PLATFORM_SEED],
+        bump = swap_registry.bump,
+    )]
+    pub swap_registry: Account<'info, SwapPlatformRegistry>,
+
+    #[account(
+        constraint = swap_proposal.vault_mode == VaultMode::PerProposal @ SwapError::InvalidVaultMode
+    )]
+    pub swap_proposal: Account<'info, SwapProposal>,
+
+    /// CHECK: the mint of either token program, checked against the token program
+    pub mint_account: AccountInfo<'info>,
+
+    /// CHECK: the vault is created and initialized by the token program of the mint
+    #[account(
+        mut,
+        seeds = [PROPOSAL_VAULT_SEED, swap_proposal.key().as_ref(), mint_account.key().as_ref()],
+        bump
+    )]
+    pub proposal_token_vault: AccountInfo<'info>,
+
+    #[account(address = system_program::ID)]
+    pub system_program: Program<'info, System>,
+
+    /// CHECK: the token program or the Token-2022 program, it must own the mint
+    #[account(
+        constraint = token_interface::is_token_program(token_program.key)
+            && *mint_account.owner == token_program.key() @ SwapError::InvalidTokenProgram
+    )]
+    pub token_program: AccountInfo<'info>,
+
+    #[account(address = sysvar::rent::ID)]
+    pub rent: Sysvar<'info, Rent>,
+}
+
+impl<'info> CreateProposalVaultContext<'info> {
+    pub fn execute(&mut self, bump: u8) -> Result<()> {
+        // Only whitelisted mints can be escrowed
+        if !self.swap_registry.is_mint_account_existed(self.mint_account.key().clone()) {
+            return Err(SwapError::UnAllowedMintToken.into());
+        }
+
+        // Create the vault with the token program of the mint
+        let proposal_key = self.swap_proposal.key();
+        let mint_key = self.mint_account.key();
+        token_interface::create_token_account(
+            self.signer.to_account_info(),
+            self.proposal_token_vault.to_account_info(),
+            self.mint_account.to_account_info(),
+            proposal_key,
+            self.token_program.to_account_info(),
+            self.system_program.to_account_info(),
+            &[&[PROPOSAL_VAULT_SEED, proposal_key.as_ref(), mint_key.as_ref(), &[bump]]],
+        )?;
+
+        // emit event
+        swap_emit!(
+            VaultCreated {
+                actor: self.signer.key().clone(),
+                authority: self.swap_proposal.key().clone(),
+                associated_account: self.proposal_token_vault.key().clone(),
+                mint_account: self.mint_account.key().clone(),
+                is_enabled: true
+            }
+        );
+
+        Ok(())
+    }
+}
@@ -9,25 +9,32 @@ pub struct CreateTokenVaultContext<'info> {
         mut,
         seeds = [PLATFORM_SEED],
         bump = swap_registry.bump,
+        realloc = swap_registry.space_with(1, 0, swap_registry.to_account_info().data_len()),
+        realloc::payer = signer,
+        realloc::zero = false,
     )]
     pub swap_registry: Account<'info, SwapPlatformRegistry>,
 
-    pub mint_account: Account<'info, Mint>,
+    /// CHECK: the mint of either token program, checked against the token program
+    pub mint_account: AccountInfo<'info>,
 
-    #[account(init,
-        token::mint = mint_account,
-        token::authority = swap_registry,
+    /// CHECK: the vault is created and initialized by the token program of the mint
+    #[account(
+        mut,
         seeds = [TOKEN_ACCOUNT_SEED, mint_account.key().as_ref()],
-        payer = signer,
         bump
     )]
-    pub swap_token_vault: Account<'info, TokenAccount>,
+    pub swap_token_vault: AccountInfo<'info>,
 
     #[account(address = system_program::ID)]
     pub system_program: Program<'info, System>,
 
-    #[account(address = spl_token::ID)]
-    pub token_program: Program<'info, Token>,
+    /// CHECK: the token program or the Token-2022 program, it must own the mint
+    #[account(
+        constraint = token_interface::is_token_program(token_program.key)
+            && *mint_account.owner == token_program.key() @ SwapError::InvalidTokenProgram
+    )]
+    pub token_program: AccountInfo<'info>,
 
     #[account(address = sysvar::rent::ID)]
     pub rent: Sysvar<'info, Rent>,
@@ -35,18 +42,38 @@ pub struct CreateTokenVaultContext<'info> {
 
 impl<'info> CreateTokenVaultContext<'info> {
     pub fn execute(&mut self, bump: u8) -> Result<()> {
+        // Check whether the signer is allowed to create the vault
+        if !self.swap_registry.can_create_vault(self.signer.key().clone()) {
+            return Err(SwapError::OnlyVaultCreator.into());
+        }
+
         // Avoid adding duplicated value
         if self.swap_registry.is_mint_account_existed(self.mint_account.key().clone()) {
             return Err(SwapError::MintAccountExisted.into());
         }
 
+        // Create the vault with the token program of the mint
+        let mint_key = self.mint_account.key();
+        token_interface::create_token_account(
+            self.signer.to_account_info(),
+            self.swap_token_vault.to_account_info(),
+            self.mint_account.to_account_info(),
+            self.swap_registry.key(),
+            self.token_program.to_account_info(),
+            self.system_program.to_account_info(),
+            &[&[TOKEN_ACCOUNT_SEED, mint_key.as_ref(), &[bump]]],
+        )?;
+
+        // Mints whitelisted by non-privileged signers wait for the owner approval
+        let is_enabled = self.swap_registry.is_vault_enabled_on_creation(self.signer.key().clone());
+
         // Now we push into the allowed mint tokens array.
         self.swap_registry.allowed_mint_accounts.push(
             MintInfo {
                 mint_account: self.mint_account.key().clone(),
                 token_account: self.swap_token_vault.key(),
                 bump,
-                is_enabled: true
+                is_enabled
             }
         );
 
@@ -56,7 +83,8 @@ impl<'info> CreateTokenVaultContext<'info> {
                 actor: self.signer.key().clone(),
                 authority: self.swap_registry.key().clone(),
                 associated_account: self.swap_token_vault.key().clone(),
-                mint_account: self.mint_account.key().clone()
+                mint_account: self.mint_account.key().clone(),
+                is_enabled
             }
         );
 
@@ -0,0 +1,118 @@
+use crate::*;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Default, Clone, Debug, PartialEq)]
+pub struct DepositAllParams {
+    pub proposal_id: String,
+    // The vault bumps of the items to deposit, following the order of the offered items
+    pub swap_token_vault_bumps: Vec<u8>,
+}
+
+// Define the context, the item accounts are passed through the remaining accounts
+// as [mint_account, signer_token_account, swap_token_vault] for every offered item not deposited yet,
+// or as [system_program, signer, native_vault] for native SOL items,
+// followed by the other token program when items of both token programs are mixed,
+// the transfer-hook extra accounts and the Token Metadata transfer accounts of programmable NFTs if any.
+#[derive(Accounts)]
+#[instruction(params: DepositAllParams)]
+pub struct DepositAllContext<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    #[account(
+        mut,
+        constraint = swap_proposal.is_proposal_address(swap_proposal.key(), &params.proposal_id) @ SwapError::InvalidProposal,
+    )]
+    pub swap_proposal: Account<'info, SwapProposal>,
+
+    #[account(
+        seeds = [PLATFORM_SEED],
+        bump = swap_registry.bump,
+    )]
+    pub swap_registry: Account<'info, SwapPlatformRegistry>,
+
+    #[account(address = system_program::ID)]
+    pub system_program: Program<'info, System>,
+
+    /// CHECK: the token program or the Token-2022 program
+    #[account(constraint = token_interface::is_token_program(token_program.key) @ SwapError::InvalidTokenProgram)]
+    pub token_program: AccountInfo<'info>,
+}
+
+impl<'info> DepositAllContext<'info> {
+    pub fn execute(&mut self, params: DepositAllParams, remaining_accounts: &[AccountInfo<'info>]) -> Result<()> {
+        let swap_proposal_key = self.swap_proposal.key().clone();
+
+        // check whether the proposal is still open for depositing
+        if !self.swap_proposal.is_proposal_open_for_depositing() {
+            return Err(SwapError::DepositIsNotAvailable.into());
+        }
+
+        // find the items to deposit
+        let item_indexes: Vec<usize> = self.swap_proposal.offered_items
+            .iter()
+            .enumerate()
+            .filter(|(_, item)| item.status == SwapItemStatus::Created)
+            .map(|(index, _)| index)
+            .collect();
+
+        if item_indexes.len() != params.swap_token_vault_bumps.len() {
+            return Err(SwapError::InvalidValue.into());
+        }
+
+        if item_indexes.len() * ITEM_ACCOUNTS_LEN > remaining_accounts.len() {
+            return Err(SwapError::InvalidTokenAccount.into());
+        }
+        let extra_accounts = extra_accounts(remaining_accounts, item_indexes.len());
+        let transfer = ItemTransfer {
+            signer: self.signer.to_account_info(),
+            token_program: self.token_program.to_account_info(),
+            system_program: self.system_program.to_account_info(),
+            extra_accounts,
+        };
+
+        for (position, &index) in item_indexes.iter().enumerate() {
+            let item = self.swap_proposal.offered_items[index].clone();
+
+            let received_amount = deposit_item(
+                &self.swap_registry,
+                &self.swap_proposal,
+                &item,
+                ItemAccounts {
+                    accounts: &remaining_accounts[position * ITEM_ACCOUNTS_LEN..(position + 1) * ITEM_ACCOUNTS_LEN],
+                    vault_bump: params.swap_token_vault_bumps[position],
+                },
+                &transfer,
+            )?;
+
+            // update the item status
+            let offered_item = &mut self.swap_proposal.offered_items[index];
+            offered_item.status = SwapItemStatus::Deposited;
+            offered_item.amount = received_amount;
+            offered_item.owner = self.signer.key().clone();
+
+            swap_emit!(
+                ItemDeposited {
+                    id: item.id.clone(),
+                    status: SwapItemStatus::Deposited,
+                    proposal_key: swap_proposal_key,
+                    actor: self.signer.key().clone(),
+                }
+            );
+        }
+
+        // every offered item is escrowed now
+        self.swap_proposal.status = SwapProposalStatus::Deposited;
+
+        // emit event
+        swap_emit!(
+            ProposalDeposited {
+                status: SwapProposalStatus::Deposited,
+                actor: self.signer.key().clone(),
+                proposal_key: swap_proposal_key,
+                id: self.swap_proposal.id.clone()
+            }
+        );
+
+        return Ok(());
+    }
+}
@@ -0,0 +1,46 @@
+use crate::*;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Default, Clone, Debug, PartialEq)]
+pub struct ExpireProposalParams {
+    id: String
+}
+
+// Define the context, public to anyone once the proposal passed its expiry.
+#[derive(Accounts)]
+#[instruction(params: ExpireProposalParams)]
+pub struct ExpireProposalContext<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    #[account(
+        mut,
+        constraint = swap_proposal.is_proposal_address(swap_proposal.key(), &params.id) @ SwapError::InvalidProposal,
+    )]
+    pub swap_proposal: Account<'info, SwapProposal>,
+
+    #[account(address = system_program::ID)]
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> ExpireProposalContext<'info> {
+    pub fn execute(&mut self, _params: ExpireProposalParams) -> Result<()> {
+        // Only proposals past their expiry without a fulfilled option can be expired
+        if !self.swap_proposal.is_proposal_expired() {
+            return Err(SwapError::ProposalCannotBeExpired.into());
+        }
+
+        self.swap_proposal.status = SwapProposalStatus::Expired;
+
+        // emit event
+        swap_emit!(
+            ProposalExpired {
+                actor: self.signer.key().clone(),
+                status: SwapProposalStatus::Expired,
+                id: self.swap_proposal.id.clone(),
+                proposal_key: self.swap_proposal.key().clone(),
+            }
+        );
+
+        return Ok(());
+    }
+}
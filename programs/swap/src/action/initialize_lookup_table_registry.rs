@@ -11,7 +11,7 @@ pub struct InitializeAddressLookupTableContext<'info> {
         init,
         seeds = [LOOKUP_TABLE_SEED, signer.key().as_ref()],
         payer = signer,
-        space = 10240,
+        space = LookupTableRegistry::space(0),
         bump
     )]
     pub lookup_table_registry: Account<'info, LookupTableRegistry>,
@@ -21,7 +21,7 @@ pub struct InitializeSwapPlatformContext<'info> {
         init,
         seeds = [PLATFORM_SEED],
         payer = owner,
-        space = 10240,
+        space = SwapPlatformRegistry::space(0, 0),
         bump
     )]
     pub swap_registry: Account<'info, SwapPlatformRegistry>,
@@ -34,7 +34,7 @@ pub struct InitializeSwapPlatformContext<'info> {
 impl<'info> InitializeSwapPlatformContext<'info> {
     pub fn execute(&mut self, params: InitializeSwapPlatformParams, bump: u8) -> Result<()> {
         // Handle post initialization
-        self.swap_registry.handle_post_initialized().unwrap();
+        self.swap_registry.handle_post_initialized()?;
 
         // Assigning values
         let swap_registry = &mut self.swap_registry;
@@ -42,6 +42,7 @@ impl<'info> InitializeSwapPlatformContext<'info> {
         swap_registry.owner = *self.owner.key;
         swap_registry.max_allowed_items = params.max_allowed_items;
         swap_registry.max_allowed_options = params.max_allowed_options;
+        swap_registry.fee_config.fee_recipient = *self.owner.key;
 
         Ok(())
     }
@@ -4,20 +4,64 @@ pub mod utils;
 pub mod initialize_swap_program;
 pub mod update_swap_registry;
 pub mod create_proposal;
+pub mod update_proposal;
 pub mod cancel_proposal;
+pub mod close_proposal;
+pub mod expire_proposal;
+pub mod release_reservation;
+pub mod withdraw_fulfillment;
+pub mod create_counter_offer;
+pub mod accept_counter_offer;
+pub mod withdraw_counter_offer;
 pub mod create_token_vault;
+pub mod create_proposal_vault;
 pub mod transfer_assets_to_vault;
+pub mod deposit_all;
+pub mod accept_proposal;
 pub mod transfer_assets_from_vault;
+pub mod transfer_all_assets_from_vault;
+pub mod crank_settle;
 pub mod create_address_lookup_table;
 pub mod initialize_lookup_table_registry;
+pub mod propose_owner;
+pub mod accept_ownership;
+pub mod set_mint_enabled;
+pub mod remove_mint;
+pub mod update_vault_creation_policy;
+pub mod update_fee_config;
+pub mod set_mint_fee_schedule;
+pub mod withdraw_platform_fees;
+pub mod withdraw_platform_token_fees;
 
 pub use utils::*;
 pub use initialize_swap_program::*;
 pub use update_swap_registry::*;
 pub use create_proposal::*;
+pub use update_proposal::*;
 pub use cancel_proposal::*;
+pub use close_proposal::*;
+pub use expire_proposal::*;
+pub use release_reservation::*;
+pub use withdraw_fulfillment::*;
+pub use create_counter_offer::*;
+pub use accept_counter_offer::*;
+pub use withdraw_counter_offer::*;
 pub use create_token_vault::*;
+pub use create_proposal_vault::*;
 pub use transfer_assets_to_vault::*;
+pub use deposit_all::*;
+pub use accept_proposal::*;
 pub use transfer_assets_from_vault::*;
+pub use transfer_all_assets_from_vault::*;
+pub use crank_settle::*;
 pub use create_address_lookup_table::*;
-pub use initialize_lookup_table_registry::*;
\ No newline at end of file
+pub use initialize_lookup_table_registry::*;
+pub use propose_owner::*;
+pub use accept_ownership::*;
+pub use set_mint_enabled::*;
+pub use remove_mint::*;
+pub use update_vault_creation_policy::*;
+pub use update_fee_config::*;
+pub use set_mint_fee_schedule::*;
+pub use withdraw_platform_fees::*;
+pub use withdraw_platform_token_fees::*;
\ No newline at end of file
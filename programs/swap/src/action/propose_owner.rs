@@ -0,0 +1,54 @@
+use crate::*;
+
+// Define params
+#[derive(AnchorSerialize, AnchorDeserialize, Default, Clone, Debug, PartialEq)]
+pub struct ProposeOwnerParams {
+    // define the owner candidate, use the default pubkey to revoke the pending transfer.
+    pub new_owner: Pubkey,
+}
+
+// Define the context, passed in parameters when trigger from deployer.
+#[derive(Accounts)]
+pub struct ProposeOwnerContext<'info> {
+    // We define the fee payer
+    #[account(
+        mut,
+        address = swap_registry.owner @ SwapError::OnlyAdministrator
+    )]
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [PLATFORM_SEED],
+        bump = swap_registry.bump,
+        has_one = owner
+    )]
+    pub swap_registry: Account<'info, SwapPlatformRegistry>,
+
+    #[account(address = system_program::ID)]
+    pub system_program: Program<'info, System>,
+}
+
+// implement the handler
+impl<'info> ProposeOwnerContext<'info> {
+    pub fn execute(&mut self, params: ProposeOwnerParams) -> Result<()> {
+        // The current owner cannot be proposed again
+        if params.new_owner == self.swap_registry.owner {
+            return Err(SwapError::InvalidValue.into());
+        }
+
+        // Assigning values
+        let swap_registry = &mut self.swap_registry;
+        swap_registry.pending_owner = params.new_owner;
+
+        // emit event
+        swap_emit!(
+            OwnershipTransferProposed {
+                actor: self.owner.key().clone(),
+                pending_owner: params.new_owner.clone(),
+            }
+        );
+
+        Ok(())
+    }
+}
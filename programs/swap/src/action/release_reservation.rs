@@ -0,0 +1,66 @@
+use crate::*;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Default, Clone, Debug, PartialEq)]
+pub struct ReleaseReservationParams {
+    pub proposal_id: String,
+}
+
+// Define the context, public to anyone once the reservation passed the reservation window.
+#[derive(Accounts)]
+#[instruction(params: ReleaseReservationParams)]
+pub struct ReleaseReservationContext<'info> {
+    // The signer pays for the space of the pending refunds
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    #[account(
+        seeds = [PLATFORM_SEED],
+        bump = swap_registry.bump,
+    )]
+    pub swap_registry: Account<'info, SwapPlatformRegistry>,
+
+    #[account(
+        mut,
+        constraint = swap_proposal.is_proposal_address(swap_proposal.key(), &params.proposal_id) @ SwapError::InvalidProposal,
+    )]
+    pub swap_proposal: Account<'info, SwapProposal>,
+
+    #[account(address = system_program::ID)]
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> ReleaseReservationContext<'info> {
+    pub fn execute(&mut self, _params: ReleaseReservationParams) -> Result<()> {
+        if !self.swap_proposal.is_reservation_stale(self.swap_registry.reservation_window) {
+            return Err(SwapError::ReservationIsNotStale.into());
+        }
+
+        let participant = self.swap_proposal.fulfilled_by.clone();
+        let option_id = self.swap_proposal.fulfilled_with_option_id.clone();
+
+        // reopen the proposal, the items of the participant become withdrawable
+        self.swap_proposal.release_reservation()?;
+
+        // grow the account to hold the pending refunds, it never shrinks below its current size
+        let new_space = self.swap_proposal.space().max(self.swap_proposal.to_account_info().data_len());
+        resize_proposal(
+            self.swap_proposal.to_account_info(),
+            self.signer.to_account_info(),
+            self.system_program.to_account_info(),
+            new_space,
+        )?;
+
+        // emit event
+        swap_emit!(
+            ReservationReleased {
+                actor: self.signer.key().clone(),
+                proposal_key: self.swap_proposal.key().clone(),
+                id: self.swap_proposal.id.clone(),
+                participant,
+                option_id,
+            }
+        );
+
+        return Ok(());
+    }
+}
@@ -0,0 +1,85 @@
+use crate::*;
+use crate::token_interface::InterfaceTokenAccount;
+
+// Define the context, passed in parameters when trigger from deployer.
+#[derive(Accounts)]
+pub struct RemoveMintContext<'info> {
+    // We define the fee payer, the vault rent is returned to the owner
+    #[account(
+        mut,
+        address = swap_registry.owner @ SwapError::OnlyAdministrator
+    )]
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [PLATFORM_SEED],
+        bump = swap_registry.bump,
+        has_one = owner
+    )]
+    pub swap_registry: Account<'info, SwapPlatformRegistry>,
+
+    /// CHECK: the mint of either token program, checked against the token program
+    #[account(
+        constraint = *mint_account.owner == token_program.key() @ SwapError::InvalidTokenProgram
+    )]
+    pub mint_account: AccountInfo<'info>,
+
+    /// CHECK: the vault of the mint, loaded through the token interface
+    #[account(
+        mut,
+        seeds = [TOKEN_ACCOUNT_SEED, mint_account.key().as_ref()],
+        bump
+    )]
+    pub swap_token_vault: AccountInfo<'info>,
+
+    #[account(address = system_program::ID)]
+    pub system_program: Program<'info, System>,
+
+    /// CHECK: the token program or the Token-2022 program, it must own the mint
+    #[account(
+        constraint = token_interface::is_token_program(token_program.key) @ SwapError::InvalidTokenProgram
+    )]
+    pub token_program: AccountInfo<'info>,
+}
+
+// implement the handler
+impl<'info> RemoveMintContext<'info> {
+    pub fn execute(&mut self) -> Result<()> {
+        // Escrowed balances and withheld transfer fees must be released before removal
+        let swap_token_vault = InterfaceTokenAccount::load(&self.swap_token_vault)?;
+        if swap_token_vault.amount > 0 || swap_token_vault.withheld_amount > 0 {
+            return Err(SwapError::VaultIsNotEmpty.into());
+        }
+
+        // Now we remove the mint from the allowed mint tokens array.
+        self.swap_registry.remove_mint_account(self.mint_account.key().clone())?;
+
+        // find the bump to sign with the pda
+        let bump = &[self.swap_registry.bump][..];
+        let signer = token_account_signer!(
+            PLATFORM_SEED,
+            bump
+        );
+
+        // close the vault and return the rent to the owner
+        token_interface::close_account(
+            self.token_program.to_account_info(),
+            self.swap_token_vault.to_account_info(),
+            self.owner.to_account_info(),
+            self.swap_registry.to_account_info(),
+            signer,
+        )?;
+
+        // emit event
+        swap_emit!(
+            MintAccountRemoved {
+                actor: self.owner.key().clone(),
+                mint_account: self.mint_account.key().clone(),
+                associated_account: self.swap_token_vault.key().clone(),
+            }
+        );
+
+        Ok(())
+    }
+}
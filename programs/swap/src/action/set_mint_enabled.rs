@@ -0,0 +1,55 @@
+use crate::*;
+
+// Define params
+#[derive(AnchorSerialize, AnchorDeserialize, Default, Clone, Debug, PartialEq)]
+pub struct SetMintEnabledParams {
+    // define the whitelisted mint account
+    pub mint_account: Pubkey,
+
+    // define whether the mint account can be used in proposals.
+    pub is_enabled: bool,
+}
+
+// Define the context, passed in parameters when trigger from deployer.
+#[derive(Accounts)]
+pub struct SetMintEnabledContext<'info> {
+    // We define the fee payer
+    #[account(
+        mut,
+        address = swap_registry.owner @ SwapError::OnlyAdministrator
+    )]
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [PLATFORM_SEED],
+        bump = swap_registry.bump,
+        has_one = owner
+    )]
+    pub swap_registry: Account<'info, SwapPlatformRegistry>,
+
+    #[account(address = system_program::ID)]
+    pub system_program: Program<'info, System>,
+}
+
+// implement the handler
+impl<'info> SetMintEnabledContext<'info> {
+    pub fn execute(&mut self, params: SetMintEnabledParams) -> Result<()> {
+        // Assigning values
+        self.swap_registry.set_mint_account_enabled(
+            params.mint_account,
+            params.is_enabled
+        )?;
+
+        // emit event
+        swap_emit!(
+            MintAccountUpdated {
+                actor: self.owner.key().clone(),
+                mint_account: params.mint_account.clone(),
+                is_enabled: params.is_enabled,
+            }
+        );
+
+        Ok(())
+    }
+}
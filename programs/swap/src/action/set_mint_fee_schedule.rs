@@ -0,0 +1,58 @@
+use crate::*;
+
+// Define params
+#[derive(AnchorSerialize, AnchorDeserialize, Default, Clone, Debug, PartialEq)]
+pub struct SetMintFeeScheduleParams {
+    // define the whitelisted mint account
+    pub mint_account: Pubkey,
+
+    // define the fee taken on currency legs, in basis points.
+    pub fee_bps: u16,
+}
+
+// Define the context, passed in parameters when trigger from deployer.
+#[derive(Accounts)]
+pub struct SetMintFeeScheduleContext<'info> {
+    // We define the fee payer
+    #[account(
+        mut,
+        address = swap_registry.owner @ SwapError::OnlyAdministrator
+    )]
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [PLATFORM_SEED],
+        bump = swap_registry.bump,
+        has_one = owner,
+        realloc = swap_registry.space_with(0, 1, swap_registry.to_account_info().data_len()),
+        realloc::payer = owner,
+        realloc::zero = false,
+    )]
+    pub swap_registry: Account<'info, SwapPlatformRegistry>,
+
+    #[account(address = system_program::ID)]
+    pub system_program: Program<'info, System>,
+}
+
+// implement the handler
+impl<'info> SetMintFeeScheduleContext<'info> {
+    pub fn execute(&mut self, params: SetMintFeeScheduleParams) -> Result<()> {
+        // Assigning values
+        self.swap_registry.set_mint_fee_schedule(
+            params.mint_account,
+            params.fee_bps
+        )?;
+
+        // emit event
+        swap_emit!(
+            MintFeeScheduleUpdated {
+                actor: self.owner.key().clone(),
+                mint_account: params.mint_account.clone(),
+                fee_bps: params.fee_bps,
+            }
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,209 @@
+use crate::*;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Default, Clone, Debug, PartialEq)]
+pub struct TransferAllAssetsFromVaultParams {
+    pub proposal_id: String,
+    // The vault bumps of the items the signer is entitled to, following the order of the items
+    pub swap_token_vault_bumps: Vec<u8>,
+}
+
+// Define the context, the item accounts are passed through the remaining accounts
+// as [mint_account, signer_token_account, swap_token_vault] for every item the signer is entitled to:
+// the offered items first, then the asking items of the fulfilled option, then the pending refunds,
+// followed by the platform vaults receiving the fees of per-proposal vaults,
+// the other token program when items of both token programs are mixed and the transfer-hook extra accounts if any.
+// Native SOL items are passed as [system_program, signer, native_vault] instead.
+#[derive(Accounts)]
+#[instruction(params: TransferAllAssetsFromVaultParams)]
+pub struct TransferAllAssetsFromVaultContext<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [PLATFORM_SEED],
+        bump = swap_registry.bump,
+    )]
+    pub swap_registry: Account<'info, SwapPlatformRegistry>,
+
+    #[account(
+        mut,
+        constraint = swap_proposal.is_proposal_address(swap_proposal.key(), &params.proposal_id) @ SwapError::InvalidProposal,
+    )]
+    pub swap_proposal: Account<'info, SwapProposal>,
+
+    #[account(address = system_program::ID)]
+    pub system_program: Program<'info, System>,
+
+    /// CHECK: the token program or the Token-2022 program
+    #[account(constraint = token_interface::is_token_program(token_program.key) @ SwapError::InvalidTokenProgram)]
+    pub token_program: AccountInfo<'info>,
+}
+
+impl<'info> TransferAllAssetsFromVaultContext<'info> {
+    pub fn redeem_all(&mut self, params: TransferAllAssetsFromVaultParams, remaining_accounts: &[AccountInfo<'info>]) -> Result<()> {
+        // check whether the proposal is still open for redeeming
+        if !self.swap_proposal.is_proposal_redeemable() {
+            return Err(SwapError::RedeemIsNotAvailable.into());
+        }
+
+        // the proposal owner redeems the asking items, the participant redeems the offered items
+        let is_owner = self.swap_proposal.is_proposal_owner(self.signer.key().clone());
+        let is_participant = self.swap_proposal.is_fulfilled_participant(self.signer.key().clone());
+
+        self.transfer_items(
+            params,
+            is_participant,
+            is_owner,
+            false,
+            SwapItemStatus::Redeemed,
+            remaining_accounts,
+        )?;
+
+        // Check and update the final status of the proposal
+        self.swap_proposal.update_redeemed_status()?;
+
+        return Ok(());
+    }
+
+    pub fn withdraw_all(&mut self, params: TransferAllAssetsFromVaultParams, remaining_accounts: &[AccountInfo<'info>]) -> Result<()> {
+        // the items of released reservations are withdrawable at any time
+        let is_withdrawable = self.swap_proposal.is_proposal_withdrawable();
+        let has_pending_refunds = self.swap_proposal.has_pending_refunds_of(self.signer.key().clone());
+
+        // check whether the proposal is still open for withdrawal
+        if !is_withdrawable && !has_pending_refunds {
+            return Err(SwapError::WithdrawalIsNotAvailable.into());
+        }
+
+        // the proposal owner withdraws the offered items, the participant withdraws the asking items
+        let is_owner = self.swap_proposal.is_proposal_owner(self.signer.key().clone());
+        let is_participant = self.swap_proposal.is_fulfilled_participant(self.signer.key().clone());
+
+        self.transfer_items(
+            params,
+            is_withdrawable && is_owner,
+            is_withdrawable && is_participant,
+            has_pending_refunds,
+            SwapItemStatus::Withdrawn,
+            remaining_accounts,
+        )?;
+
+        // Check and update the final status of the proposal
+        if is_withdrawable {
+            self.swap_proposal.update_withdrawn_status()?;
+        }
+
+        return Ok(());
+    }
+
+    fn transfer_items(
+        &mut self,
+        params: TransferAllAssetsFromVaultParams,
+        include_offered_items: bool,
+        include_asking_items: bool,
+        include_pending_refunds: bool,
+        status: SwapItemStatus,
+        remaining_accounts: &[AccountInfo<'info>],
+    ) -> Result<()> {
+        // Only the proposal owner and the participants can transfer the items
+        if !include_offered_items && !include_asking_items && !include_pending_refunds {
+            return Err(SwapError::OnlyParticipant.into());
+        }
+
+        // find the option id
+        let option_index = self.swap_proposal.swap_options
+            .iter()
+            .position(|x| x.id == self.swap_proposal.fulfilled_with_option_id);
+
+        // collect the deposited items the signer is entitled to
+        let mut entitled_items: Vec<ItemLocation> = vec![];
+        if include_offered_items {
+            for (index, item) in self.swap_proposal.offered_items.iter().enumerate() {
+                if item.status == SwapItemStatus::Deposited {
+                    entitled_items.push(ItemLocation::Offered(index));
+                }
+            }
+        }
+
+        if include_asking_items {
+            let option_index = option_index.ok_or(SwapError::SwapOptionNotFound)?;
+
+            for (index, item) in self.swap_proposal.swap_options[option_index].asking_items.iter().enumerate() {
+                if item.status == SwapItemStatus::Deposited {
+                    entitled_items.push(ItemLocation::Asking(option_index, index));
+                }
+            }
+        }
+
+        if include_pending_refunds {
+            for (index, item) in self.swap_proposal.pending_refunds.iter().enumerate() {
+                if item.status == SwapItemStatus::Deposited && item.owner == self.signer.key() {
+                    entitled_items.push(ItemLocation::PendingRefund(index));
+                }
+            }
+        }
+
+        if entitled_items.len() != params.swap_token_vault_bumps.len() {
+            return Err(SwapError::InvalidValue.into());
+        }
+
+        let mut cursor = 0;
+        let extra_accounts = extra_accounts(remaining_accounts, entitled_items.len());
+        let transfer = ItemTransfer {
+            signer: self.signer.to_account_info(),
+            token_program: self.token_program.to_account_info(),
+            system_program: self.system_program.to_account_info(),
+            extra_accounts,
+        };
+        for (position, location) in entitled_items.iter().enumerate() {
+            let item = match *location {
+                ItemLocation::Offered(index) => self.swap_proposal.offered_items[index].clone(),
+                ItemLocation::Asking(option_index, index) => self.swap_proposal.swap_options[option_index].asking_items[index].clone(),
+                ItemLocation::PendingRefund(index) => self.swap_proposal.pending_refunds[index].clone(),
+            };
+
+            release_item_to(
+                &mut self.swap_registry,
+                &self.swap_proposal,
+                &item,
+                status,
+                self.signer.key(),
+                ItemAccounts {
+                    accounts: next_item_accounts(remaining_accounts, &mut cursor)?,
+                    vault_bump: params.swap_token_vault_bumps[position],
+                },
+                &transfer,
+            )?;
+
+            // update the item status
+            match *location {
+                ItemLocation::Offered(index) => self.swap_proposal.offered_items[index].status = status,
+                ItemLocation::Asking(option_index, index) => self.swap_proposal.swap_options[option_index].asking_items[index].status = status,
+                ItemLocation::PendingRefund(index) => self.swap_proposal.pending_refunds[index].status = status,
+            };
+
+            if status == SwapItemStatus::Redeemed {
+                swap_emit!(
+                    ItemRedeemed {
+                        id: item.id.clone(),
+                        proposal_key: self.swap_proposal.key().clone(),
+                        status,
+                        actor: self.signer.key().clone()
+                    }
+                );
+            } else {
+                swap_emit!(
+                    ItemWithdrawn {
+                        id: item.id.clone(),
+                        proposal_key: self.swap_proposal.key().clone(),
+                        status,
+                        actor: self.signer.key().clone()
+                    }
+                );
+            }
+        }
+
+        return Ok(());
+    }
+}
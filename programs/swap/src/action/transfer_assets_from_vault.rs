@@ -1,5 +1,4 @@
 use crate::*;
-use std::borrow::{Borrow, BorrowMut};
 
 #[derive(AnchorSerialize, AnchorDeserialize, Default, Clone, Debug, PartialEq)]
 pub enum TransferActionType {
@@ -16,61 +15,68 @@ pub struct TransferAssetsFromVaultParams {
     pub swap_item_id: String,
 }
 
+// Define the context, the mint, the token account and the vault may belong to the token program or the Token-2022 program.
+// The transfer-hook extra accounts and the platform vaults receiving the fees of per-proposal vaults
+// are passed through the remaining accounts.
+// Native SOL items pass the system program as the mint, the wallet of the signer as the token account
+// and the native vault of the proposal as the vault.
+// Programmable NFTs also expect the accounts of the Token Metadata transfer in the remaining accounts:
+// the metadata, the master edition, the token records, the instructions sysvar, the associated token program,
+// the Token Metadata program and the rule set with the Token Auth Rules program.
 #[derive(Accounts)]
 #[instruction(params: TransferAssetsFromVaultParams)]
 pub struct TransferAssetsFromVaultContext<'info> {
     #[account(mut)]
     pub signer: Signer<'info>,
 
-    pub mint_account: Account<'info, Mint>,
+    /// CHECK: the mint of either token program, checked against the swap item
+    pub mint_account: AccountInfo<'info>,
 
     #[account(
+    mut,
     seeds = [PLATFORM_SEED],
     bump = swap_registry.bump,
     )]
     pub swap_registry: Account<'info, SwapPlatformRegistry>,
 
+    /// CHECK: the token account of the signer, loaded with the token interface and checked against the mint and the signer
     #[account(mut)]
-    /// CHECK: the signer token account can be verified later
     pub signer_token_account: AccountInfo<'info>,
 
     #[account(
     mut,
-    seeds = [PROPOSAL_SEED, params.proposal_id.as_bytes().as_ref()],
-    bump = swap_proposal.bump,
+    constraint = swap_proposal.is_proposal_address(swap_proposal.key(), &params.proposal_id) @ SwapError::InvalidProposal,
     )]
     pub swap_proposal: Account<'info, SwapProposal>,
 
-    #[account(
-    mut,
-    seeds = [TOKEN_ACCOUNT_SEED, mint_account.key().as_ref()],
-    bump = params.swap_token_vault_bump
-    )]
-    pub swap_token_vault: Account<'info, TokenAccount>,
+    /// CHECK: the vault of the mint, loaded with the token interface and checked against the vaults of the proposal
+    #[account(mut)]
+    pub swap_token_vault: AccountInfo<'info>,
 
     #[account(address = system_program::ID)]
     pub system_program: Program<'info, System>,
 
-    #[account(address = spl_token::ID)]
-    pub token_program: Program<'info, Token>,
+    /// CHECK: the token program or the Token-2022 program
+    #[account(constraint = token_interface::is_token_program(token_program.key) @ SwapError::InvalidTokenProgram)]
+    pub token_program: AccountInfo<'info>,
 }
 
 impl<'info> TransferAssetsFromVaultContext<'info> {
-    pub fn execute(&mut self, params: TransferAssetsFromVaultParams) -> Result<()> {
+    pub fn execute(&mut self, params: TransferAssetsFromVaultParams, remaining_accounts: &[AccountInfo<'info>]) -> Result<()> {
         // Check and route for redeeming
         if params.action_type == TransferActionType::Redeeming {
-            return self.redeem(params);
+            return self.redeem(params, remaining_accounts);
         }
 
         // Check and route for withdrawal
         if params.action_type == TransferActionType::Withdrawing {
-            return self.withdraw(params);
+            return self.withdraw(params, remaining_accounts);
         }
 
         return Err(SwapError::InvalidValue.into());
     }
 
-    fn redeem(&mut self, params: TransferAssetsFromVaultParams) -> Result<()> {
+    fn redeem(&mut self, params: TransferAssetsFromVaultParams, remaining_accounts: &[AccountInfo<'info>]) -> Result<()> {
         let current_params = params.clone();
 
         // check whether the proposal is still open for redeeming
@@ -78,23 +84,31 @@ impl<'info> TransferAssetsFromVaultContext<'info> {
             return Err(SwapError::RedeemIsNotAvailable.into());
         }
 
+        // Only the proposal owner and the fulfilled participant can redeem
+        if !self.swap_proposal.is_proposal_owner(self.signer.key().clone())
+            && !self.swap_proposal.is_fulfilled_participant(self.signer.key().clone()) {
+            return Err(SwapError::OnlyParticipant.into());
+        }
+
         // Check whether the signer is allowed to redeem.
         if self.swap_proposal.is_proposal_owner(self.signer.key().clone()) {
             self.transfer_asking_items(
                 current_params.clone(),
                 SwapItemStatus::Redeemed,
-            ).unwrap();
+                remaining_accounts,
+            )?;
         }
 
         if self.swap_proposal.is_fulfilled_participant(self.signer.key().clone()) {
             self.transfer_offered_items(
                 current_params.clone(),
-                SwapItemStatus::Redeemed
-            ).unwrap();
+                SwapItemStatus::Redeemed,
+                remaining_accounts,
+            )?;
         }
 
         // Check and update the final status of the proposal
-        self.swap_proposal.update_redeemed_status().unwrap();
+        self.swap_proposal.update_redeemed_status()?;
 
         swap_emit!(
             ItemRedeemed {
@@ -108,32 +122,45 @@ impl<'info> TransferAssetsFromVaultContext<'info> {
         return Ok(());
     }
 
-    fn withdraw(&mut self, params: TransferAssetsFromVaultParams) -> Result<()> {
+    fn withdraw(&mut self, params: TransferAssetsFromVaultParams, remaining_accounts: &[AccountInfo<'info>]) -> Result<()> {
         let current_params = params.clone();
 
+        // the items of released reservations are withdrawable at any time
+        if let Some(item_index) = self.swap_proposal.find_pending_refund_of(&params.swap_item_id, self.signer.key()) {
+            return self.transfer_pending_refund(current_params, item_index, remaining_accounts);
+        }
+
         // check whether the proposal is still open for withdrawal
         if !self.swap_proposal.is_proposal_withdrawable() {
             return Err(SwapError::WithdrawalIsNotAvailable.into());
         }
 
+        // Only the proposal owner and the fulfilled participant can withdraw
+        if !self.swap_proposal.is_proposal_owner(self.signer.key().clone())
+            && !self.swap_proposal.is_fulfilled_participant(self.signer.key().clone()) {
+            return Err(SwapError::OnlyParticipant.into());
+        }
+
         // Check whether the signer is allowed to withdraw.
         if self.swap_proposal.is_proposal_owner(self.signer.key().clone()) {
              self.transfer_offered_items(
                  current_params.clone(),
-                 SwapItemStatus::Withdrawn
-             ).unwrap();
+                 SwapItemStatus::Withdrawn,
+                 remaining_accounts,
+             )?;
         }
 
         if self.swap_proposal.is_fulfilled_participant(self.signer.key().clone()) {
              self.transfer_asking_items(
                  current_params.clone(),
-                 SwapItemStatus::Withdrawn
-             ).unwrap();
+                 SwapItemStatus::Withdrawn,
+                 remaining_accounts,
+             )?;
         }
 
 
         // Check and update the final status of the proposal
-        self.swap_proposal.update_withdrawn_status().unwrap();
+        self.swap_proposal.update_withdrawn_status()?;
 
         // emit
         swap_emit!(
@@ -148,92 +175,94 @@ impl<'info> TransferAssetsFromVaultContext<'info> {
         return Ok(());
     }
 
-    fn transfer_asking_items(&mut self, params: TransferAssetsFromVaultParams, desired_item_status: SwapItemStatus) -> Result<()> {
-        let current_params = params.clone();
-        let swap_proposal = self.swap_proposal.borrow_mut();
-        let option_id = swap_proposal.fulfilled_with_option_id.clone();
+    fn transfer_asking_items(&mut self, params: TransferAssetsFromVaultParams, desired_item_status: SwapItemStatus, remaining_accounts: &[AccountInfo<'info>]) -> Result<()> {
+        let option_id = self.swap_proposal.fulfilled_with_option_id.clone();
 
         // find the option id
-        let desired_option = swap_proposal.swap_options
-            .iter_mut()
-            .find(|x| x.id == option_id.clone())
-            .unwrap();
+        let option_index = self.swap_proposal.swap_options
+            .iter()
+            .position(|x| x.id == option_id.clone())
+            .ok_or(SwapError::SwapOptionNotFound)?;
 
         // find the swap item
-        let mut item = desired_option.asking_items
-            .iter_mut()
-            .find(|x| x.id == current_params.swap_item_id.clone())
-            .unwrap();
+        let item_index = self.swap_proposal.swap_options[option_index].asking_items
+            .iter()
+            .position(|x| x.id == params.swap_item_id.clone())
+            .ok_or(SwapError::SwapItemNotFound)?;
 
-        if item.status != SwapItemStatus::Deposited {
-            return Err(SwapError::TransferTokenFromVaultIsNotAvailable.into());
-        }
+        let item = self.swap_proposal.swap_options[option_index].asking_items[item_index].clone();
+        self.transfer_item(&item, desired_item_status, params.swap_token_vault_bump, remaining_accounts)?;
 
-        // find the bump to sign with the pda
-        let bump = &[self.swap_registry.bump][..];
-        let signer = token_account_signer!(
-            PLATFORM_SEED,
-            bump
-        );
+        // update the item status
+        self.swap_proposal.swap_options[option_index].asking_items[item_index].status = desired_item_status;
+
+        Ok(())
+    }
+
+    fn transfer_offered_items(&mut self, params: TransferAssetsFromVaultParams, status: SwapItemStatus, remaining_accounts: &[AccountInfo<'info>]) -> Result<()> {
+        // find the swap item
+        let item_index = self.swap_proposal.offered_items
+            .iter()
+            .position(|x| x.id == params.swap_item_id)
+            .ok_or(SwapError::SwapItemNotFound)?;
 
-        // transfer the token
-        token::transfer(
-            CpiContext::new_with_signer(
-                self.token_program.to_account_info(),
-                Transfer {
-                    from: self.swap_token_vault.to_account_info(),
-                    to: self.signer_token_account.to_account_info(),
-                    authority: self.swap_registry.to_account_info(),
-                },
-                signer,
-            ),
-            item.amount,
-        ).unwrap();
+        let item = self.swap_proposal.offered_items[item_index].clone();
+        self.transfer_item(&item, status, params.swap_token_vault_bump, remaining_accounts)?;
 
         // update the item status
-        item.status = desired_item_status;
+        self.swap_proposal.offered_items[item_index].status = status;
 
-        Ok(())
+        return Ok(());
     }
 
-    fn transfer_offered_items(&mut self, params: TransferAssetsFromVaultParams, status: SwapItemStatus) -> Result<()> {
-        let swap_proposal = self.swap_proposal.borrow_mut();
+    fn transfer_pending_refund(&mut self, params: TransferAssetsFromVaultParams, item_index: usize, remaining_accounts: &[AccountInfo<'info>]) -> Result<()> {
+        let item = self.swap_proposal.pending_refunds[item_index].clone();
+        self.transfer_item(&item, SwapItemStatus::Withdrawn, params.swap_token_vault_bump, remaining_accounts)?;
 
-        // find the swap item
-        let mut item = swap_proposal.offered_items
-            .iter_mut()
-            .find(|x| x.id == params.swap_item_id)
-            .unwrap();
+        // update the item status
+        self.swap_proposal.pending_refunds[item_index].status = SwapItemStatus::Withdrawn;
+
+        swap_emit!(
+            ItemWithdrawn {
+                id: params.swap_item_id.clone(),
+                proposal_key: self.swap_proposal.key().clone(),
+                status: SwapItemStatus::Withdrawn,
+                actor: self.signer.key().clone()
+            }
+        );
+
+        return Ok(());
+    }
 
+    fn transfer_item(&mut self, item: &SwapItem, status: SwapItemStatus, swap_token_vault_bump: u8, remaining_accounts: &[AccountInfo<'info>]) -> Result<()> {
         // Redeem is not available
         if item.status != SwapItemStatus::Deposited {
             return Err(SwapError::TransferTokenFromVaultIsNotAvailable.into());
         }
 
-        // find the bump to sign with the pda
-        let bump = &[self.swap_registry.bump][..];
-        let signer = token_account_signer!(
-            PLATFORM_SEED,
-            bump
+        let accounts = [
+            self.mint_account.to_account_info(),
+            self.signer_token_account.to_account_info(),
+            self.swap_token_vault.to_account_info(),
+        ];
+        let item_accounts = ItemAccounts {
+            accounts: &accounts,
+            vault_bump: swap_token_vault_bump,
+        };
+
+        return release_item_to(
+            &mut self.swap_registry,
+            &self.swap_proposal,
+            item,
+            status,
+            self.signer.key(),
+            item_accounts,
+            &ItemTransfer {
+                signer: self.signer.to_account_info(),
+                token_program: self.token_program.to_account_info(),
+                system_program: self.system_program.to_account_info(),
+                extra_accounts: remaining_accounts,
+            },
         );
-
-        // transfer the token
-        token::transfer(
-            CpiContext::new_with_signer(
-                self.token_program.to_account_info(),
-                Transfer {
-                    from: self.swap_token_vault.to_account_info(),
-                    to: self.signer_token_account.to_account_info(),
-                    authority: self.swap_registry.to_account_info(),
-                },
-                signer,
-            ),
-            item.amount,
-        ).unwrap();
-
-        // update the item status
-        item.status = status;
-
-        return Ok(());
     }
-}
\ No newline at end of file
+}
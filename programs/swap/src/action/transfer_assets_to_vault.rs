@@ -1,5 +1,5 @@
 use crate::*;
-use std::borrow::{Borrow, BorrowMut};
+use std::borrow::BorrowMut;
 
 #[derive(AnchorSerialize, AnchorDeserialize, Default, Clone, Debug, PartialEq)]
 pub enum ActionType {
@@ -17,68 +17,87 @@ pub struct TransferAssetsToVaultParams {
     pub swap_item_id: String,
 }
 
+// Define the context, the mint, the token account and the vault may belong to the token program or the Token-2022 program.
+// The transfer-hook extra accounts are passed through the remaining accounts,
+// along with the metadata account of the mint when fulfilling a collection ask.
+// Native SOL items pass the system program as the mint, the wallet of the signer as the token account
+// and the native vault of the proposal as the vault.
+// Programmable NFTs also expect the accounts of the Token Metadata transfer in the remaining accounts:
+// the master edition, the token records, the instructions sysvar, the associated token program,
+// the Token Metadata program and the rule set with the Token Auth Rules program.
 #[derive(Accounts)]
 #[instruction(params: TransferAssetsToVaultParams)]
 pub struct TransferAssetsToVaultContext<'info> {
     #[account(mut)]
     pub signer: Signer<'info>,
 
-    pub mint_account: Account<'info, Mint>,
+    /// CHECK: the mint of either token program, checked against the swap item
+    pub mint_account: AccountInfo<'info>,
 
+    /// CHECK: the token account of the signer, loaded with the token interface and checked against the mint and the signer
     #[account(mut)]
-    /// CHECK: the signer token account can be verified later
     pub signer_token_account: AccountInfo<'info>,
 
     #[account(
         mut,
-        seeds = [PROPOSAL_SEED, params.proposal_id.as_bytes().as_ref()],
-        bump = swap_proposal.bump,
+        constraint = swap_proposal.is_proposal_address(swap_proposal.key(), &params.proposal_id) @ SwapError::InvalidProposal,
     )]
     pub swap_proposal: Account<'info, SwapProposal>,
 
+    #[account(
+        seeds = [PLATFORM_SEED],
+        bump = swap_registry.bump,
+    )]
+    pub swap_registry: Account<'info, SwapPlatformRegistry>,
+
     #[account(
         mut,
-        seeds = [TOKEN_ACCOUNT_SEED, mint_account.key().as_ref()],
-        bump = params.swap_token_vault_bump
+        seeds = [TREASURY_SEED],
+        bump,
     )]
-    pub swap_token_vault: Account<'info, TokenAccount>,
+    pub swap_treasury: SystemAccount<'info>,
+
+    /// CHECK: the vault of the mint, loaded with the token interface and checked against the vaults of the proposal
+    #[account(mut)]
+    pub swap_token_vault: AccountInfo<'info>,
 
     #[account(address = system_program::ID)]
     pub system_program: Program<'info, System>,
 
-    #[account(address = spl_token::ID)]
-    pub token_program: Program<'info, Token>,
+    /// CHECK: the token program or the Token-2022 program
+    #[account(constraint = token_interface::is_token_program(token_program.key) @ SwapError::InvalidTokenProgram)]
+    pub token_program: AccountInfo<'info>,
 }
 
 impl<'info> TransferAssetsToVaultContext<'info> {
-    pub fn execute(&mut self, params: TransferAssetsToVaultParams) -> Result<()> {
+    pub fn execute(&mut self, params: TransferAssetsToVaultParams, remaining_accounts: &[AccountInfo<'info>]) -> Result<()> {
         // Check and route for depositing
         if params.action_type == ActionType::Depositing {
-            return self.deposit(params);
+            return self.deposit(params, remaining_accounts);
         }
 
         // Check and route for fulfilling
         if params.action_type == ActionType::Fulfilling {
-            return self.fulfill(params);
+            return self.fulfill(params, remaining_accounts);
         }
 
         return Err(SwapError::InvalidValue.into());
     }
 
-    fn deposit(&mut self, params: TransferAssetsToVaultParams) -> Result<()> {
-        let swap_proposal = self.swap_proposal.borrow_mut();
-        let swap_proposal_key = swap_proposal.key().clone();
+    fn deposit(&mut self, params: TransferAssetsToVaultParams, remaining_accounts: &[AccountInfo<'info>]) -> Result<()> {
+        let swap_proposal_key = self.swap_proposal.key().clone();
 
         // check whether the proposal is still open for depositing
-        if !swap_proposal.is_proposal_open_for_depositing() {
+        if !self.swap_proposal.is_proposal_open_for_depositing() {
             return Err(SwapError::DepositIsNotAvailable.into());
         }
 
         // find the swap item
-        let mut item = swap_proposal.offered_items
-            .iter_mut()
-            .find(|x| x.id == params.swap_item_id)
-            .unwrap();
+        let index = self.swap_proposal.offered_items
+            .iter()
+            .position(|x| x.id == params.swap_item_id)
+            .ok_or(SwapError::SwapItemNotFound)?;
+        let item = self.swap_proposal.offered_items[index].clone();
 
         // Raise error
         if item.status != SwapItemStatus::Created {
@@ -86,21 +105,14 @@ impl<'info> TransferAssetsToVaultContext<'info> {
         }
 
         // transfer the token
-        token::transfer(
-            CpiContext::new(
-                self.token_program.to_account_info(),
-                Transfer {
-                    from: self.signer_token_account.to_account_info(),
-                    to: self.swap_token_vault.to_account_info(),
-                    authority: self.signer.to_account_info(),
-                },
-            ),
-            item.amount,
-        ).unwrap();
+        let received_amount = self.transfer_item_to_vault(&item, params.swap_token_vault_bump, remaining_accounts)?;
 
         // update the item status
-        item.status = SwapItemStatus::Deposited;
-        item.owner = self.signer.key().clone();
+        let swap_proposal = self.swap_proposal.borrow_mut();
+        let offered_item = &mut swap_proposal.offered_items[index];
+        offered_item.status = SwapItemStatus::Deposited;
+        offered_item.amount = received_amount;
+        offered_item.owner = self.signer.key().clone();
 
         swap_emit!(
             ItemDeposited {
@@ -133,56 +145,66 @@ impl<'info> TransferAssetsToVaultContext<'info> {
         return Ok(());
     }
 
-    fn fulfill(&mut self, params: TransferAssetsToVaultParams) -> Result<()> {
+    fn fulfill(&mut self, params: TransferAssetsToVaultParams, remaining_accounts: &[AccountInfo<'info>]) -> Result<()> {
         let current_params = params.clone();
-        let swap_proposal = self.swap_proposal.borrow_mut();
-        let swap_proposal_key = swap_proposal.key().clone();
+        let swap_proposal_key = self.swap_proposal.key().clone();
 
         // check whether the proposal is still open for depositing
-        if !swap_proposal.is_proposal_open_for_fulfilling(
+        if !self.swap_proposal.is_proposal_open_for_fulfilling(
             params.option_id.clone(),
             self.signer.key().clone()
         ) {
             return Err(SwapError::FulfillingIsNotAvailable.into());
         }
 
-        // first reserve the proposal
-        swap_proposal.fulfilled_with_option_id = current_params.option_id;
-        swap_proposal.fulfilled_by = self.signer.key().clone();
+        // private proposals can only be fulfilled by the allowed participants
+        if !self.swap_proposal.is_allowed_participant(self.signer.key().clone()) {
+            return Err(SwapError::OnlyAllowedParticipant.into());
+        }
+
+        // first reserve the proposal, the reservation window starts with the first deposit
+        if self.swap_proposal.fulfilled_by == Pubkey::default() {
+            self.swap_proposal.reserved_at = Clock::get()?.unix_timestamp as u64;
+        }
+        self.swap_proposal.fulfilled_with_option_id = current_params.option_id;
+        self.swap_proposal.fulfilled_by = self.signer.key().clone();
 
         // find the option id
-        let desired_option = swap_proposal.swap_options
-            .iter_mut()
-            .find(|x| x.id == params.option_id.clone())
-            .unwrap();
+        let option_index = self.swap_proposal.swap_options
+            .iter()
+            .position(|x| x.id == params.option_id.clone())
+            .ok_or(SwapError::SwapOptionNotFound)?;
 
         // find the swap item
-        let mut item = desired_option.asking_items
-            .iter_mut()
-            .find(|x| x.id == current_params.swap_item_id.clone())
-            .unwrap();
+        let index = self.swap_proposal.swap_options[option_index].asking_items
+            .iter()
+            .position(|x| x.id == current_params.swap_item_id.clone())
+            .ok_or(SwapError::SwapItemNotFound)?;
 
         // Raise error
-        if item.status != SwapItemStatus::Created {
+        if self.swap_proposal.swap_options[option_index].asking_items[index].status != SwapItemStatus::Created {
             return Err(SwapError::FulfillingIsNotAvailable.into());
         }
 
+        // collection asks are fulfilled with the deposited mint
+        resolve_collection_item(
+            &self.swap_registry,
+            &mut self.swap_proposal.swap_options[option_index].asking_items[index],
+            &self.mint_account.to_account_info(),
+            remaining_accounts,
+        )?;
+        let item = self.swap_proposal.swap_options[option_index].asking_items[index].clone();
+
         // transfer the token
-        token::transfer(
-            CpiContext::new(
-                self.token_program.to_account_info(),
-                Transfer {
-                    from: self.signer_token_account.to_account_info(),
-                    to: self.swap_token_vault.to_account_info(),
-                    authority: self.signer.to_account_info(),
-                },
-            ),
-            item.amount,
-        ).unwrap();
+        let received_amount = self.transfer_item_to_vault(&item, params.swap_token_vault_bump, remaining_accounts)?;
 
         // update the item status
-        item.status = SwapItemStatus::Deposited;
-        item.owner = self.signer.key().clone();
+        let swap_proposal = self.swap_proposal.borrow_mut();
+        let desired_option = &mut swap_proposal.swap_options[option_index];
+        let asking_item = &mut desired_option.asking_items[index];
+        asking_item.status = SwapItemStatus::Deposited;
+        asking_item.amount = received_amount;
+        asking_item.owner = self.signer.key().clone();
 
         swap_emit!(
             ItemDeposited {
@@ -210,8 +232,55 @@ impl<'info> TransferAssetsToVaultContext<'info> {
                     id: swap_proposal.id.clone()
                 }
             );
+
+            // Charge the fulfillment fee to the participant
+            let fulfillment_fee = self.swap_registry.fee_config.fulfillment_fee;
+            if fulfillment_fee > 0 {
+                collect_platform_fee(
+                    self.signer.to_account_info(),
+                    self.swap_treasury.to_account_info(),
+                    self.system_program.to_account_info(),
+                    fulfillment_fee,
+                )?;
+
+                swap_emit!(
+                    FeeCollected {
+                        actor: self.signer.key().clone(),
+                        proposal_key: swap_proposal_key,
+                        mint_account: Pubkey::default(),
+                        amount: fulfillment_fee,
+                    }
+                );
+            }
         }
 
         return Ok(());
     }
-}
\ No newline at end of file
+
+    // Escrow the item of the signer and return the amount that actually arrived,
+    // programmable NFTs are moved by the Token Metadata program
+    fn transfer_item_to_vault(&self, item: &SwapItem, swap_token_vault_bump: u8, remaining_accounts: &[AccountInfo<'info>]) -> Result<u64> {
+        let accounts = [
+            self.mint_account.to_account_info(),
+            self.signer_token_account.to_account_info(),
+            self.swap_token_vault.to_account_info(),
+        ];
+        let item_accounts = ItemAccounts {
+            accounts: &accounts,
+            vault_bump: swap_token_vault_bump,
+        };
+
+        deposit_item(
+            &self.swap_registry,
+            &self.swap_proposal,
+            item,
+            item_accounts,
+            &ItemTransfer {
+                signer: self.signer.to_account_info(),
+                token_program: self.token_program.to_account_info(),
+                system_program: self.system_program.to_account_info(),
+                extra_accounts: remaining_accounts,
+            },
+        )
+    }
+}
@@ -0,0 +1,89 @@
+use crate::*;
+
+// Define params
+#[derive(AnchorSerialize, AnchorDeserialize, Default, Clone, Debug, PartialEq)]
+pub struct UpdateFeeConfigParams {
+    // define the account that receives the withdrawn fees.
+    pub fee_recipient: Pubkey,
+
+    // define the flat fee charged on proposal creation, in lamports.
+    pub proposal_creation_fee: u64,
+
+    // define the flat fee charged once a proposal is fulfilled, in lamports.
+    pub fulfillment_fee: u64,
+
+    // define the bounty paid to the crank caller out of the proposal rent in lamports, left unchanged if not provided.
+    pub crank_bounty: Option<u64>,
+}
+
+// Define the context, passed in parameters when trigger from deployer.
+#[derive(Accounts)]
+pub struct UpdateFeeConfigContext<'info> {
+    // We define the fee payer
+    #[account(
+        mut,
+        address = swap_registry.owner @ SwapError::OnlyAdministrator
+    )]
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [PLATFORM_SEED],
+        bump = swap_registry.bump,
+        has_one = owner
+    )]
+    pub swap_registry: Account<'info, SwapPlatformRegistry>,
+
+    #[account(
+        mut,
+        seeds = [TREASURY_SEED],
+        bump,
+    )]
+    pub swap_treasury: SystemAccount<'info>,
+
+    #[account(address = system_program::ID)]
+    pub system_program: Program<'info, System>,
+}
+
+// implement the handler
+impl<'info> UpdateFeeConfigContext<'info> {
+    pub fn execute(&mut self, params: UpdateFeeConfigParams) -> Result<()> {
+        if params.fee_recipient == Pubkey::default() {
+            return Err(SwapError::InvalidFeeRecipient.into());
+        }
+
+        // The treasury must be rent exempt before it can receive small fees
+        let rent_exempt_minimum = Rent::get()?.minimum_balance(0);
+        if self.swap_treasury.lamports() < rent_exempt_minimum {
+            collect_platform_fee(
+                self.owner.to_account_info(),
+                self.swap_treasury.to_account_info(),
+                self.system_program.to_account_info(),
+                rent_exempt_minimum - self.swap_treasury.lamports(),
+            )?;
+        }
+
+        // Assigning values
+        let fee_config = &mut self.swap_registry.fee_config;
+        fee_config.fee_recipient = params.fee_recipient;
+        fee_config.proposal_creation_fee = params.proposal_creation_fee;
+        fee_config.fulfillment_fee = params.fulfillment_fee;
+
+        if let Some(crank_bounty) = params.crank_bounty {
+            self.swap_registry.crank_bounty = crank_bounty;
+        }
+
+        // emit event
+        swap_emit!(
+            FeeConfigUpdated {
+                actor: self.owner.key().clone(),
+                fee_recipient: params.fee_recipient.clone(),
+                proposal_creation_fee: params.proposal_creation_fee,
+                fulfillment_fee: params.fulfillment_fee,
+                crank_bounty: self.swap_registry.crank_bounty,
+            }
+        );
+
+        Ok(())
+    }
+}
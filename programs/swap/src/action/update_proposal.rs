@@ -0,0 +1,91 @@
+use crate::*;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Default, Clone, Debug, PartialEq)]
+pub struct UpdateProposalParams {
+    pub id: String,
+
+    // define the swap options replacing the current ones, kept if not provided
+    pub swap_options: Option<Vec<SwapItemOptionInfo>>,
+
+    // define the new expiry date, kept if not provided
+    pub expired_at: Option<u64>,
+}
+
+// Define the context, passed in parameters when trigger from the proposal owner.
+#[derive(Accounts)]
+#[instruction(params: UpdateProposalParams)]
+pub struct UpdateProposalContext<'info> {
+    // The proposal owner pays or gets refunded the rent difference
+    #[account(mut)]
+    pub proposal_owner: Signer<'info>,
+
+    #[account(
+        mut,
+        constraint = swap_proposal.is_proposal_address(swap_proposal.key(), &params.id) @ SwapError::InvalidProposal,
+        constraint = swap_proposal.owner == proposal_owner.key() @ SwapError::OnlyOwner,
+    )]
+    pub swap_proposal: Account<'info, SwapProposal>,
+
+    #[account(
+        seeds = [PLATFORM_SEED],
+        bump = swap_registry.bump,
+    )]
+    pub swap_registry: Account<'info, SwapPlatformRegistry>,
+
+    #[account(address = system_program::ID)]
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> UpdateProposalContext<'info> {
+    pub fn execute(&mut self, params: UpdateProposalParams) -> Result<()> {
+        // The proposal cannot be updated once a taker reserved an option
+        if !self.swap_proposal.is_proposal_updatable() {
+            return Err(SwapError::ProposalCannotBeUpdated.into());
+        }
+
+        if let Some(swap_options) = params.swap_options {
+            let swap_options: Vec<SwapOption> = swap_options.iter()
+                .map(|option| option.to_swap_option(Pubkey::default()))
+                .collect();
+
+            if swap_options.len() < 1 {
+                return Err(SwapError::InvalidValue.into());
+            }
+
+            // Check if user want to ask for un-allowed mint tokens
+            self.swap_registry.validate_swap_options(&swap_options)?;
+
+            self.swap_proposal.swap_options = swap_options;
+            self.swap_proposal.record_collection_asks();
+        }
+
+        if let Some(expired_at) = params.expired_at {
+            if expired_at <= Clock::get()?.unix_timestamp as u64 {
+                return Err(SwapError::InvalidValue.into());
+            }
+
+            self.swap_proposal.expired_at = expired_at;
+        }
+
+        // fit the account to the updated options
+        let new_space = self.swap_proposal.space();
+        resize_proposal(
+            self.swap_proposal.to_account_info(),
+            self.proposal_owner.to_account_info(),
+            self.system_program.to_account_info(),
+            new_space,
+        )?;
+
+        // emit event
+        swap_emit!(
+            ProposalUpdated {
+                actor: self.proposal_owner.key().clone(),
+                proposal_key: self.swap_proposal.key().clone(),
+                id: self.swap_proposal.id.clone(),
+                expired_at: self.swap_proposal.expired_at as i64,
+            }
+        );
+
+        return Ok(());
+    }
+}
@@ -8,6 +8,15 @@ pub struct UpdateSwapPlatformParams {
 
     // define max allowed options can be asked.
     pub max_allowed_options: u8,
+
+    // define the vault mode applied to new proposals, left unchanged if not provided.
+    pub default_vault_mode: Option<VaultMode>,
+
+    // define how long in seconds a participant can hold a reservation, left unchanged if not provided.
+    pub reservation_window: Option<u64>,
+
+    // define how the creator royalties are handled, left unchanged if not provided.
+    pub royalty_mode: Option<RoyaltyMode>,
 }
 
 // Define the context, passed in parameters when trigger from deployer.
@@ -24,7 +33,10 @@ pub struct UpdateSwapPlatformContext<'info> {
         mut,
         seeds = [PLATFORM_SEED],
         bump = swap_registry.bump,
-        has_one = owner
+        has_one = owner,
+        realloc = swap_registry.space_with(0, 0, swap_registry.to_account_info().data_len()),
+        realloc::payer = owner,
+        realloc::zero = false,
     )]
     pub swap_registry: Account<'info, SwapPlatformRegistry>,
 
@@ -47,6 +59,15 @@ impl<'info> UpdateSwapPlatformContext<'info> {
         let swap_registry = &mut self.swap_registry;
         swap_registry.max_allowed_options = params.max_allowed_options.clone();
         swap_registry.max_allowed_items = params.max_allowed_items.clone();
+        if let Some(default_vault_mode) = params.default_vault_mode {
+            swap_registry.default_vault_mode = default_vault_mode;
+        }
+        if let Some(reservation_window) = params.reservation_window {
+            swap_registry.reservation_window = reservation_window;
+        }
+        if let Some(royalty_mode) = params.royalty_mode {
+            swap_registry.royalty_mode = royalty_mode;
+        }
 
         // emit event
         swap_emit!(
@@ -54,6 +75,9 @@ impl<'info> UpdateSwapPlatformContext<'info> {
                 actor: self.owner.key().clone(),
                 max_allowed_options: params.max_allowed_options.clone(),
                 max_allowed_items: params.max_allowed_items.clone(),
+                default_vault_mode: self.swap_registry.default_vault_mode,
+                reservation_window: self.swap_registry.reservation_window,
+                royalty_mode: self.swap_registry.royalty_mode,
             }
         );
 
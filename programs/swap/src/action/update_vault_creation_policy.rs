@@ -0,0 +1,54 @@
+use crate::*;
+
+// Define params
+#[derive(AnchorSerialize, AnchorDeserialize, Default, Clone, Debug, PartialEq)]
+pub struct UpdateVaultCreationPolicyParams {
+    // define who can create token vaults.
+    pub vault_creation_policy: VaultCreationPolicy,
+
+    // define the delegated vault creator, use the default pubkey to revoke the role.
+    pub vault_creator: Pubkey,
+}
+
+// Define the context, passed in parameters when trigger from deployer.
+#[derive(Accounts)]
+pub struct UpdateVaultCreationPolicyContext<'info> {
+    // We define the fee payer
+    #[account(
+        mut,
+        address = swap_registry.owner @ SwapError::OnlyAdministrator
+    )]
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [PLATFORM_SEED],
+        bump = swap_registry.bump,
+        has_one = owner
+    )]
+    pub swap_registry: Account<'info, SwapPlatformRegistry>,
+
+    #[account(address = system_program::ID)]
+    pub system_program: Program<'info, System>,
+}
+
+// implement the handler
+impl<'info> UpdateVaultCreationPolicyContext<'info> {
+    pub fn execute(&mut self, params: UpdateVaultCreationPolicyParams) -> Result<()> {
+        // Assigning values
+        let swap_registry = &mut self.swap_registry;
+        swap_registry.vault_creation_policy = params.vault_creation_policy;
+        swap_registry.vault_creator = params.vault_creator;
+
+        // emit event
+        swap_emit!(
+            VaultCreationPolicyUpdated {
+                actor: self.owner.key().clone(),
+                vault_creator: params.vault_creator.clone(),
+                vault_creation_policy: params.vault_creation_policy,
+            }
+        );
+
+        Ok(())
+    }
+}
@@ -1,14 +1,777 @@
 use crate::*;
+use crate::token_interface::{InterfaceTokenAccount, TransferChecked};
 
-pub fn random_number(recent_slothashes: &UncheckedAccount, seed: String) -> u64 {
+pub fn random_number(recent_slothashes: &UncheckedAccount, seed: String) -> Result<u64> {
     let data = recent_slothashes.data.borrow();
     let most_recent = array_ref![data, 12, 8];
 
-    let clock = Clock::get().unwrap();
+    let clock = Clock::get()?;
     // seed for the random number is a combination of the slot_hash - timestamp
     let seed = u64::from_le_bytes(*array_ref![seed.as_bytes(), 96, 8])
         .saturating_sub(u64::from_le_bytes(*most_recent).
             saturating_sub(clock.unix_timestamp as u64));
 
-    return seed;
-}
\ No newline at end of file
+    return Ok(seed);
+}
+// Transfer the platform fee in lamports from the payer to the treasury
+pub fn collect_platform_fee<'info>(
+    payer: AccountInfo<'info>,
+    swap_treasury: AccountInfo<'info>,
+    system_program: AccountInfo<'info>,
+    amount: u64,
+) -> Result<()> {
+    if amount == 0 {
+        return Ok(());
+    }
+
+    anchor_lang::system_program::transfer(
+        CpiContext::new(
+            system_program,
+            anchor_lang::system_program::Transfer {
+                from: payer,
+                to: swap_treasury,
+            },
+        ),
+        amount,
+    )
+}
+
+// Resize the proposal account, only the rent difference is moved so that the lamports held by the proposal are never touched
+pub fn resize_proposal<'info>(
+    swap_proposal: AccountInfo<'info>,
+    payer: AccountInfo<'info>,
+    system_program: AccountInfo<'info>,
+    new_space: usize,
+) -> Result<()> {
+    let current_space = swap_proposal.data_len();
+    if new_space == current_space {
+        return Ok(());
+    }
+
+    let rent = Rent::get()?;
+    let current_rent_minimum = rent.minimum_balance(current_space);
+    let new_rent_minimum = rent.minimum_balance(new_space);
+
+    if new_rent_minimum > current_rent_minimum {
+        anchor_lang::system_program::transfer(
+            CpiContext::new(
+                system_program,
+                anchor_lang::system_program::Transfer {
+                    from: payer,
+                    to: swap_proposal.clone(),
+                },
+            ),
+            new_rent_minimum - current_rent_minimum,
+        )?;
+    } else if current_rent_minimum > new_rent_minimum {
+        let refund = current_rent_minimum - new_rent_minimum;
+        **swap_proposal.try_borrow_mut_lamports()? -= refund;
+        **payer.try_borrow_mut_lamports()? += refund;
+    }
+
+    swap_proposal.realloc(new_space, false)?;
+
+    return Ok(());
+}
+
+// Define the signer moving the swap items with the programs it calls, grouped like the accounts of a context.
+// The extra accounts are the accounts passed after the accounts of the swap items.
+#[derive(Clone)]
+pub struct ItemTransfer<'a, 'info> {
+    pub signer: AccountInfo<'info>,
+    pub token_program: AccountInfo<'info>,
+    pub system_program: AccountInfo<'info>,
+    pub extra_accounts: &'a [AccountInfo<'info>],
+}
+
+impl<'a, 'info> ItemTransfer<'a, 'info> {
+    // Use the token program owning the mint, the other token program is taken from the extra accounts
+    pub fn with_token_program_of(&self, mint_account: &AccountInfo<'info>) -> Result<ItemTransfer<'a, 'info>> {
+        let token_program = token_interface::token_program_of(mint_account, &self.token_program, self.extra_accounts)?;
+
+        return Ok(ItemTransfer { token_program, ..self.clone() });
+    }
+}
+
+// Define where an escrowed item is stored in the proposal
+#[derive(Clone, Copy)]
+pub enum ItemLocation {
+    Offered(usize),
+    Asking(usize, usize),
+    PendingRefund(usize),
+}
+
+// Define the accounts of a swap item with the bump of its vault.
+// The accounts are expected as [mint_account, token_account, swap_token_vault],
+// or as [system_program, wallet, native_vault] for native SOL items.
+#[derive(Clone, Copy)]
+pub struct ItemAccounts<'a, 'info> {
+    pub accounts: &'a [AccountInfo<'info>],
+    pub vault_bump: u8,
+}
+
+// Define the loaded token accounts of a swap item, the token account belongs to the owner or the recipient of the item
+pub struct ItemTokenAccounts<'info> {
+    pub mint_account: AccountInfo<'info>,
+    pub token_account: InterfaceTokenAccount<'info>,
+    pub swap_token_vault: InterfaceTokenAccount<'info>,
+}
+
+// Transfer the tokens of the signer into the vault and return the amount that actually arrived,
+// the transfer fee of Token-2022 mints is withheld on the way in.
+pub fn transfer_to_vault<'info>(
+    item_token_accounts: &mut ItemTokenAccounts<'info>,
+    transfer: &ItemTransfer<'_, 'info>,
+    amount: u64,
+) -> Result<u64> {
+    let balance_before = item_token_accounts.swap_token_vault.amount;
+
+    token_interface::transfer_checked(
+        TransferChecked {
+            token_program: transfer.token_program.clone(),
+            from: item_token_accounts.token_account.to_account_info(),
+            mint: item_token_accounts.mint_account.clone(),
+            to: item_token_accounts.swap_token_vault.to_account_info(),
+            authority: transfer.signer.clone(),
+        },
+        transfer.extra_accounts,
+        amount,
+        &[],
+    )?;
+
+    item_token_accounts.swap_token_vault.reload()?;
+
+    return Ok(item_token_accounts.swap_token_vault.amount.saturating_sub(balance_before));
+}
+
+// Load the token account and the vault of a swap item from the item accounts
+pub fn load_item_accounts<'info>(
+    swap_proposal: &Account<'info, SwapProposal>,
+    item: &SwapItem,
+    token_account_owner: Pubkey,
+    item_accounts: ItemAccounts<'_, 'info>,
+) -> Result<ItemTokenAccounts<'info>> {
+    let accounts = item_accounts.accounts;
+    if accounts.len() != ITEM_ACCOUNTS_LEN {
+        return Err(SwapError::InvalidTokenAccount.into());
+    }
+
+    // The mint must match the swap item
+    if accounts[0].key() != item.mint_account {
+        return Err(SwapError::MintAccountMismatch.into());
+    }
+
+    // The token accounts must belong to the token program of the mint
+    if accounts[1].owner != accounts[0].owner || accounts[2].owner != accounts[0].owner {
+        return Err(SwapError::InvalidTokenProgram.into());
+    }
+
+    let token_account = InterfaceTokenAccount::load(&accounts[1])?;
+    if token_account.mint != item.mint_account {
+        return Err(SwapError::InvalidTokenAccountMint.into());
+    }
+    if token_account.owner != token_account_owner {
+        return Err(SwapError::InvalidTokenAccountOwner.into());
+    }
+
+    if !swap_proposal.is_vault_of(
+        swap_proposal.key(),
+        item.mint_account,
+        item_accounts.vault_bump,
+        accounts[2].key(),
+    ) {
+        return Err(SwapError::InvalidVault.into());
+    }
+    let swap_token_vault = InterfaceTokenAccount::load(&accounts[2])?;
+
+    return Ok(ItemTokenAccounts {
+        mint_account: accounts[0].clone(),
+        token_account,
+        swap_token_vault,
+    });
+}
+
+// Load the wallet and the native vault of a native SOL item from the item accounts
+pub fn load_native_item_accounts<'info>(
+    swap_proposal: &Account<'info, SwapProposal>,
+    wallet: Pubkey,
+    item_accounts: ItemAccounts<'_, 'info>,
+) -> Result<(AccountInfo<'info>, AccountInfo<'info>)> {
+    let accounts = item_accounts.accounts;
+    if accounts.len() != ITEM_ACCOUNTS_LEN {
+        return Err(SwapError::InvalidTokenAccount.into());
+    }
+
+    // Native SOL items are not backed by a mint, the system program takes its place
+    if accounts[0].key() != system_program::ID {
+        return Err(SwapError::MintAccountMismatch.into());
+    }
+
+    if accounts[1].key() != wallet {
+        return Err(SwapError::InvalidTokenAccountOwner.into());
+    }
+
+    if !swap_proposal.is_native_vault_of(
+        swap_proposal.key(),
+        item_accounts.vault_bump,
+        accounts[2].key(),
+    ) {
+        return Err(SwapError::InvalidVault.into());
+    }
+
+    return Ok((accounts[1].clone(), accounts[2].clone()));
+}
+
+// Escrow a swap item of the signer and return the amount that actually arrived.
+// Native SOL items are held as lamports by the native vault of the proposal,
+// programmable NFTs are transferred with the Token Metadata accounts found in the extra accounts.
+pub fn deposit_item<'info>(
+    swap_registry: &Account<'info, SwapPlatformRegistry>,
+    swap_proposal: &Account<'info, SwapProposal>,
+    item: &SwapItem,
+    item_accounts: ItemAccounts<'_, 'info>,
+    transfer: &ItemTransfer<'_, 'info>,
+) -> Result<u64> {
+    if item.item_type == SwapItemType::NativeSol {
+        let (_, native_vault) = load_native_item_accounts(
+            swap_proposal,
+            transfer.signer.key(),
+            item_accounts,
+        )?;
+
+        // The depositor tops the native vault up to its rent, it is held by the proposal once the vault is emptied.
+        // The vault may already hold a few lamports sent by anyone.
+        let amount = item.amount + Rent::get()?.minimum_balance(0).saturating_sub(native_vault.lamports());
+
+        if transfer.signer.lamports() < amount {
+            return Err(SwapError::InsufficientBalance.into());
+        }
+
+        anchor_lang::system_program::transfer(
+            CpiContext::new(
+                transfer.system_program.clone(),
+                anchor_lang::system_program::Transfer {
+                    from: transfer.signer.clone(),
+                    to: native_vault,
+                },
+            ),
+            amount,
+        )?;
+
+        return Ok(item.amount);
+    }
+
+    let mut item_token_accounts = load_item_accounts(
+        swap_proposal,
+        item,
+        transfer.signer.key(),
+        item_accounts,
+    )?;
+
+    if item_token_accounts.token_account.amount < item.amount {
+        return Err(SwapError::InsufficientBalance.into());
+    }
+
+    // transfer the token
+    let transfer = transfer.with_token_program_of(&item_token_accounts.mint_account)?;
+
+    // programmable NFTs are frozen in their token account and transferred with the Token Metadata program
+    if let Some(nft_metadata) = metadata::find_programmable(&item.mint_account, transfer.extra_accounts) {
+        let vault_authority = vault_authority_of(swap_registry, swap_proposal);
+        metadata::transfer_programmable(
+            &nft_metadata,
+            metadata::TransferProgrammable {
+                source: item_token_accounts.token_account.to_account_info(),
+                source_owner: transfer.signer.clone(),
+                destination: item_token_accounts.swap_token_vault.to_account_info(),
+                destination_owner: vault_authority,
+                mint_account: item_token_accounts.mint_account.clone(),
+                authority: transfer.signer.clone(),
+                payer: transfer.signer.clone(),
+                system_program: transfer.system_program.clone(),
+                token_program: transfer.token_program.clone(),
+            },
+            transfer.extra_accounts,
+            item.amount,
+            &[],
+        )?;
+
+        return Ok(item.amount);
+    }
+
+    transfer_to_vault(&mut item_token_accounts, &transfer, item.amount)
+}
+
+// Resolve a collection ask to the concrete mint named by the participant, other items are left untouched.
+// The metadata account of the concrete mint is looked up in the extra accounts.
+pub fn resolve_collection_item<'info>(
+    swap_registry: &SwapPlatformRegistry,
+    item: &mut SwapItem,
+    mint_account: &AccountInfo<'info>,
+    extra_accounts: &[AccountInfo<'info>],
+) -> Result<()> {
+    if !item.is_unresolved_collection_item() {
+        return Ok(());
+    }
+
+    let metadata = metadata::Metadata::find(&mint_account.key(), extra_accounts)?;
+    item.resolve_collection_item(&metadata)?;
+
+    // the concrete mint must be whitelisted to be escrowed
+    if !swap_registry.is_mint_account_existed(item.mint_account) {
+        return Err(SwapError::UnAllowedMintToken.into());
+    }
+
+    if !swap_registry.is_mint_account_enabled(item.mint_account) {
+        return Err(SwapError::MintAccountDisabled.into());
+    }
+
+    return Ok(());
+}
+
+// Take the extra accounts passed after the accounts of the swap items:
+// the platform vaults receiving the fees of per-proposal vaults, the other token program, the transfer-hook extra accounts,
+// the metadata accounts of the mints fulfilling collection asks and the Token Metadata transfer accounts of programmable NFTs.
+pub fn extra_accounts<'a, 'info>(
+    remaining_accounts: &'a [AccountInfo<'info>],
+    item_count: usize,
+) -> &'a [AccountInfo<'info>] {
+    return remaining_accounts.get(item_count * ITEM_ACCOUNTS_LEN..).unwrap_or(&[]);
+}
+
+// Take the accounts of the next swap item from the remaining accounts and advance the cursor
+pub fn next_item_accounts<'a, 'info>(
+    remaining_accounts: &'a [AccountInfo<'info>],
+    cursor: &mut usize,
+) -> Result<&'a [AccountInfo<'info>]> {
+    let end = *cursor + ITEM_ACCOUNTS_LEN;
+    if end > remaining_accounts.len() {
+        return Err(SwapError::InvalidTokenAccount.into());
+    }
+
+    let accounts = &remaining_accounts[*cursor..end];
+    *cursor = end;
+
+    return Ok(accounts);
+}
+
+// Transfer the escrowed tokens out of the vault, signed by the vault authority of the proposal.
+// The token program of the transfer must own the mint.
+pub fn transfer_from_vault<'info>(
+    swap_registry: &Account<'info, SwapPlatformRegistry>,
+    swap_proposal: &Account<'info, SwapProposal>,
+    mint_account: AccountInfo<'info>,
+    swap_token_vault: AccountInfo<'info>,
+    destination: AccountInfo<'info>,
+    transfer: &ItemTransfer<'_, 'info>,
+    amount: u64,
+) -> Result<()> {
+    let accounts = TransferChecked {
+        token_program: transfer.token_program.clone(),
+        from: swap_token_vault,
+        mint: mint_account,
+        to: destination,
+        authority: vault_authority_of(swap_registry, swap_proposal),
+    };
+
+    if swap_proposal.vault_mode == VaultMode::PerProposal {
+        let id_hash = SwapProposal::hash_id(&swap_proposal.id);
+        let bump = &[swap_proposal.bump][..];
+        let seeds = swap_proposal.signer_seeds(&id_hash, bump);
+        let signer = proposal_signer!(seeds);
+
+        return token_interface::transfer_checked(accounts, transfer.extra_accounts, amount, signer);
+    }
+
+    // find the bump to sign with the pda
+    let bump = &[swap_registry.bump][..];
+    let signer = token_account_signer!(
+        PLATFORM_SEED,
+        bump
+    );
+
+    token_interface::transfer_checked(accounts, transfer.extra_accounts, amount, signer)
+}
+
+// Get the authority of the vaults holding the escrowed tokens of the proposal
+pub fn vault_authority_of<'info>(
+    swap_registry: &Account<'info, SwapPlatformRegistry>,
+    swap_proposal: &Account<'info, SwapProposal>,
+) -> AccountInfo<'info> {
+    if swap_proposal.vault_mode == VaultMode::PerProposal {
+        return swap_proposal.to_account_info();
+    }
+
+    return swap_registry.to_account_info();
+}
+
+// Transfer an escrowed programmable NFT out of the vault with the Token Metadata program,
+// signed by the vault authority of the proposal. The signer pays for the token record of the destination.
+// The owner of the destination token account is either the signer or looked up in the extra accounts.
+pub fn transfer_programmable_from_vault<'info>(
+    swap_registry: &Account<'info, SwapPlatformRegistry>,
+    swap_proposal: &Account<'info, SwapProposal>,
+    nft_metadata: &metadata::Metadata,
+    item_token_accounts: &ItemTokenAccounts<'info>,
+    transfer: &ItemTransfer<'_, 'info>,
+    amount: u64,
+) -> Result<()> {
+    let destination_owner_key = item_token_accounts.token_account.owner;
+    let destination_owner = if transfer.signer.key() == destination_owner_key {
+        transfer.signer.clone()
+    } else {
+        transfer.extra_accounts
+            .iter()
+            .find(|account| account.key() == destination_owner_key)
+            .cloned()
+            .ok_or(SwapError::InvalidTokenAccountOwner)?
+    };
+
+    let vault_authority = vault_authority_of(swap_registry, swap_proposal);
+    let accounts = metadata::TransferProgrammable {
+        source: item_token_accounts.swap_token_vault.to_account_info(),
+        source_owner: vault_authority.clone(),
+        destination: item_token_accounts.token_account.to_account_info(),
+        destination_owner,
+        mint_account: item_token_accounts.mint_account.clone(),
+        authority: vault_authority,
+        payer: transfer.signer.clone(),
+        system_program: transfer.system_program.clone(),
+        token_program: transfer.token_program.clone(),
+    };
+
+    if swap_proposal.vault_mode == VaultMode::PerProposal {
+        let id_hash = SwapProposal::hash_id(&swap_proposal.id);
+        let bump = &[swap_proposal.bump][..];
+        let seeds = swap_proposal.signer_seeds(&id_hash, bump);
+        let signer = proposal_signer!(seeds);
+
+        return metadata::transfer_programmable(nft_metadata, accounts, transfer.extra_accounts, amount, signer);
+    }
+
+    // find the bump to sign with the pda
+    let bump = &[swap_registry.bump][..];
+    let signer = token_account_signer!(
+        PLATFORM_SEED,
+        bump
+    );
+
+    metadata::transfer_programmable(nft_metadata, accounts, transfer.extra_accounts, amount, signer)
+}
+
+// Pay the creator royalties of the NFTs traded against a redeemed currency leg and return the amount paid.
+// The amount is shared evenly between the NFTs, each share pays the seller fee of the NFT to its verified creators.
+// The metadata accounts of the NFTs and the token accounts of the creators are looked up in the extra accounts.
+pub fn pay_royalties<'info>(
+    swap_registry: &Account<'info, SwapPlatformRegistry>,
+    swap_proposal: &Account<'info, SwapProposal>,
+    item: &SwapItem,
+    item_token_accounts: &ItemTokenAccounts<'info>,
+    transfer: &ItemTransfer<'_, 'info>,
+    amount: u64,
+) -> Result<u64> {
+    let royalty_mode = swap_registry.royalty_mode;
+    if royalty_mode == RoyaltyMode::Off || item.item_type != SwapItemType::Currency {
+        return Ok(0);
+    }
+
+    let nft_items = swap_proposal.find_counter_leg_nft_items(item);
+    if nft_items.is_empty() {
+        return Ok(0);
+    }
+
+    let nft_share = amount / nft_items.len() as u64;
+    let mut paid_amount: u64 = 0;
+
+    for nft_item in nft_items.iter() {
+        let nft_metadata = match metadata::Metadata::find(&nft_item.mint_account, transfer.extra_accounts) {
+            Ok(nft_metadata) => nft_metadata,
+            Err(error) => {
+                if royalty_mode == RoyaltyMode::Enforced {
+                    return Err(error);
+                }
+
+                continue;
+            }
+        };
+
+        let royalty = (nft_share as u128)
+            .checked_mul(nft_metadata.seller_fee_basis_points as u128)
+            .ok_or(SwapError::InvalidValue)?
+            / 10_000;
+
+        for creator in nft_metadata.creators.unwrap_or_default().iter().filter(|creator| creator.verified) {
+            let creator_amount = (royalty * creator.share as u128 / 100) as u64;
+            if creator_amount == 0 {
+                continue;
+            }
+
+            let creator_token_account_key = token_interface::get_associated_token_address_with_program_id(
+                &creator.address,
+                &item.mint_account,
+                transfer.token_program.key,
+            );
+            let creator_token_account = match transfer.extra_accounts
+                .iter()
+                .find(|account| account.key() == creator_token_account_key)
+            {
+                Some(creator_token_account) => creator_token_account,
+                None => {
+                    if royalty_mode == RoyaltyMode::Enforced {
+                        return Err(SwapError::RoyaltyAccountNotFound.into());
+                    }
+
+                    continue;
+                }
+            };
+
+            transfer_from_vault(
+                swap_registry,
+                swap_proposal,
+                item_token_accounts.mint_account.clone(),
+                item_token_accounts.swap_token_vault.to_account_info(),
+                creator_token_account.clone(),
+                transfer,
+                creator_amount,
+            )?;
+            paid_amount += creator_amount;
+
+            swap_emit!(
+                RoyaltyPaid {
+                    actor: transfer.signer.key(),
+                    proposal_key: swap_proposal.key().clone(),
+                    nft_mint_account: nft_item.mint_account.clone(),
+                    creator: creator.address.clone(),
+                    mint_account: item.mint_account.clone(),
+                    amount: creator_amount,
+                }
+            );
+        }
+    }
+
+    return Ok(paid_amount);
+}
+
+// Release an escrowed item from the vault to the token account of the item accounts,
+// the platform fee and the creator royalties are taken on redeemed currency legs.
+// The platform vaults receiving the fees of per-proposal vaults and the accounts of programmable NFT transfers
+// are looked up in the extra accounts.
+pub fn release_item<'info>(
+    swap_registry: &mut Account<'info, SwapPlatformRegistry>,
+    swap_proposal: &Account<'info, SwapProposal>,
+    item: &SwapItem,
+    status: SwapItemStatus,
+    item_token_accounts: &mut ItemTokenAccounts<'info>,
+    transfer: &ItemTransfer<'_, 'info>,
+) -> Result<()> {
+    let transfer = &transfer.with_token_program_of(&item_token_accounts.mint_account)?;
+
+    // Take the platform fee on redeemed currency legs
+    let mut fee = 0;
+    let mut royalty = 0;
+    if status == SwapItemStatus::Redeemed {
+        fee = swap_registry.take_token_fee(item)?;
+
+        // the royalties are computed on the amount left after the platform fee
+        royalty = pay_royalties(
+            swap_registry,
+            swap_proposal,
+            item,
+            item_token_accounts,
+            transfer,
+            item.amount - fee,
+        )?;
+    }
+
+    // transfer the token, programmable NFTs are moved by the Token Metadata program
+    match metadata::find_programmable(&item.mint_account, transfer.extra_accounts) {
+        Some(nft_metadata) => transfer_programmable_from_vault(
+            swap_registry,
+            swap_proposal,
+            &nft_metadata,
+            item_token_accounts,
+            transfer,
+            item.amount - fee - royalty,
+        )?,
+        None => transfer_from_vault(
+            swap_registry,
+            swap_proposal,
+            item_token_accounts.mint_account.clone(),
+            item_token_accounts.swap_token_vault.to_account_info(),
+            item_token_accounts.token_account.to_account_info(),
+            transfer,
+            item.amount - fee - royalty,
+        )?,
+    }
+
+    if fee > 0 {
+        let mut collected_fee = fee;
+
+        // The fee of a per-proposal vault is moved into the platform vault of the mint
+        if swap_proposal.vault_mode == VaultMode::PerProposal {
+            let platform_token_vault_key = swap_registry.get_mint_info(item.mint_account)?.token_account;
+            let platform_token_vault = transfer.extra_accounts
+                .iter()
+                .find(|account| account.key() == platform_token_vault_key)
+                .ok_or(SwapError::InvalidVault)?;
+            let mut platform_token_vault = InterfaceTokenAccount::load(platform_token_vault)?;
+            let balance_before = platform_token_vault.amount;
+
+            transfer_from_vault(
+                swap_registry,
+                swap_proposal,
+                item_token_accounts.mint_account.clone(),
+                item_token_accounts.swap_token_vault.to_account_info(),
+                platform_token_vault.to_account_info(),
+                transfer,
+                fee,
+            )?;
+
+            // Only the fee that arrived is accrued, the transfer fee of Token-2022 mints is withheld on the way in
+            platform_token_vault.reload()?;
+            collected_fee = platform_token_vault.amount.saturating_sub(balance_before);
+            swap_registry.release_token_fee(item.mint_account, fee - collected_fee)?;
+        }
+
+        swap_emit!(
+            FeeCollected {
+                actor: transfer.signer.key().clone(),
+                proposal_key: swap_proposal.key().clone(),
+                mint_account: item.mint_account.clone(),
+                amount: collected_fee,
+            }
+        );
+    }
+
+    // Close the per-proposal vault once it is emptied
+    close_proposal_vault(
+        swap_proposal,
+        &mut item_token_accounts.swap_token_vault,
+        transfer.token_program.clone(),
+    )?;
+
+    return Ok(());
+}
+
+// Release an escrowed item to the recipient, the item accounts are loaded for the recipient.
+pub fn release_item_to<'info>(
+    swap_registry: &mut Account<'info, SwapPlatformRegistry>,
+    swap_proposal: &Account<'info, SwapProposal>,
+    item: &SwapItem,
+    status: SwapItemStatus,
+    recipient: Pubkey,
+    item_accounts: ItemAccounts<'_, 'info>,
+    transfer: &ItemTransfer<'_, 'info>,
+) -> Result<()> {
+    if item.item_type == SwapItemType::NativeSol {
+        let (recipient_wallet, native_vault) = load_native_item_accounts(
+            swap_proposal,
+            recipient,
+            item_accounts,
+        )?;
+
+        return release_native_item(
+            swap_proposal,
+            item,
+            native_vault,
+            item_accounts.vault_bump,
+            recipient_wallet,
+            transfer,
+        );
+    }
+
+    let mut item_token_accounts = load_item_accounts(
+        swap_proposal,
+        item,
+        recipient,
+        item_accounts,
+    )?;
+
+    release_item(
+        swap_registry,
+        swap_proposal,
+        item,
+        status,
+        &mut item_token_accounts,
+        transfer,
+    )
+}
+
+// Release the lamports of a native SOL item from the native vault, signed by the native vault itself.
+// The native vault is closed once emptied, the rent is held by the proposal
+// and returned to the proposal owner once the proposal is closed.
+pub fn release_native_item<'info>(
+    swap_proposal: &Account<'info, SwapProposal>,
+    item: &SwapItem,
+    native_vault: AccountInfo<'info>,
+    native_vault_bump: u8,
+    destination: AccountInfo<'info>,
+    transfer: &ItemTransfer<'_, 'info>,
+) -> Result<()> {
+    let swap_proposal_key = swap_proposal.key();
+    let bump = &[native_vault_bump][..];
+    let seeds = [NATIVE_VAULT_SEED, swap_proposal_key.as_ref(), bump];
+    let signer = proposal_signer!(seeds);
+
+    anchor_lang::system_program::transfer(
+        CpiContext::new_with_signer(
+            transfer.system_program.clone(),
+            anchor_lang::system_program::Transfer {
+                from: native_vault.clone(),
+                to: destination,
+            },
+            signer,
+        ),
+        item.amount,
+    )?;
+
+    // Close the native vault once only its rent is left
+    let rent_minimum = Rent::get()?.minimum_balance(0);
+    if native_vault.lamports() != rent_minimum {
+        return Ok(());
+    }
+
+    anchor_lang::system_program::transfer(
+        CpiContext::new_with_signer(
+            transfer.system_program.clone(),
+            anchor_lang::system_program::Transfer {
+                from: native_vault,
+                to: swap_proposal.to_account_info(),
+            },
+            signer,
+        ),
+        rent_minimum,
+    )
+}
+
+// Close an emptied per-proposal vault, the rent is held by the proposal
+// and returned to the proposal owner once the proposal is closed.
+pub fn close_proposal_vault<'info>(
+    swap_proposal: &Account<'info, SwapProposal>,
+    swap_token_vault: &mut InterfaceTokenAccount<'info>,
+    token_program: AccountInfo<'info>,
+) -> Result<()> {
+    // the shared vaults are never closed
+    if swap_proposal.vault_mode != VaultMode::PerProposal {
+        return Ok(());
+    }
+
+    // a Token-2022 vault holding withheld transfer fees cannot be closed
+    swap_token_vault.reload()?;
+    if swap_token_vault.amount > 0 || swap_token_vault.withheld_amount > 0 {
+        return Ok(());
+    }
+
+    let id_hash = SwapProposal::hash_id(&swap_proposal.id);
+    let bump = &[swap_proposal.bump][..];
+    let seeds = swap_proposal.signer_seeds(&id_hash, bump);
+    let signer = proposal_signer!(seeds);
+
+    token_interface::close_account(
+        token_program,
+        swap_token_vault.to_account_info(),
+        swap_proposal.to_account_info(),
+        swap_proposal.to_account_info(),
+        signer,
+    )
+}
@@ -0,0 +1,153 @@
+use crate::*;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Default, Clone, Debug, PartialEq)]
+pub struct WithdrawCounterOfferParams {
+    pub proposal_id: String,
+    // The vault bumps of the escrowed items, following the order of the counter offer asking items
+    pub swap_token_vault_bumps: Vec<u8>,
+}
+
+// Define the context, passed in parameters when trigger from the taker or the proposal owner.
+// The item accounts are passed through the remaining accounts as
+// [mint_account, taker_token_account, swap_token_vault] for every escrowed item of the counter offer,
+// or as [system_program, taker, native_vault] for native SOL items,
+// followed by the other token program when items of both token programs are mixed
+// and the transfer-hook extra accounts if any.
+#[derive(Accounts)]
+#[instruction(params: WithdrawCounterOfferParams)]
+pub struct WithdrawCounterOfferContext<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [PLATFORM_SEED],
+        bump = swap_registry.bump,
+    )]
+    pub swap_registry: Account<'info, SwapPlatformRegistry>,
+
+    #[account(
+        mut,
+        constraint = swap_proposal.is_proposal_address(swap_proposal.key(), &params.proposal_id) @ SwapError::InvalidProposal,
+    )]
+    pub swap_proposal: Account<'info, SwapProposal>,
+
+    // The counter offer is closed, the rent and the held fulfillment fee go back to the taker
+    #[account(
+        mut,
+        seeds = [COUNTER_OFFER_SEED, swap_proposal.key().as_ref(), taker.key().as_ref()],
+        bump = counter_offer.bump,
+        close = taker
+    )]
+    pub counter_offer: Account<'info, CounterOffer>,
+
+    #[account(mut)]
+    pub taker: SystemAccount<'info>,
+
+    #[account(address = system_program::ID)]
+    pub system_program: Program<'info, System>,
+
+    /// CHECK: the token program or the Token-2022 program
+    #[account(constraint = token_interface::is_token_program(token_program.key) @ SwapError::InvalidTokenProgram)]
+    pub token_program: AccountInfo<'info>,
+}
+
+impl<'info> WithdrawCounterOfferContext<'info> {
+    // The proposal owner rejects the counter offer, the escrow is released to the taker
+    pub fn reject(&mut self, params: WithdrawCounterOfferParams, remaining_accounts: &[AccountInfo<'info>]) -> Result<()> {
+        if !self.swap_proposal.is_proposal_owner(self.signer.key().clone()) {
+            return Err(SwapError::OnlyOwner.into());
+        }
+
+        self.release_escrow(params, remaining_accounts)?;
+
+        // emit event
+        swap_emit!(
+            CounterOfferRejected {
+                actor: self.signer.key().clone(),
+                proposal_key: self.swap_proposal.key().clone(),
+                id: self.swap_proposal.id.clone(),
+                counter_offer_key: self.counter_offer.key().clone(),
+                taker: self.counter_offer.taker.clone(),
+                option_id: self.counter_offer.swap_option.id.clone(),
+            }
+        );
+
+        return Ok(());
+    }
+
+    // The taker withdraws the counter offer once it expired or the proposal is no longer open
+    pub fn withdraw(&mut self, params: WithdrawCounterOfferParams, remaining_accounts: &[AccountInfo<'info>]) -> Result<()> {
+        if !self.counter_offer.is_taker(self.signer.key().clone()) {
+            return Err(SwapError::OnlyParticipant.into());
+        }
+
+        // the taker is committed to the counter offer until it expires
+        if self.counter_offer.is_counter_offer_still_in_time_window()
+            && self.swap_proposal.is_proposal_open_for_participants()
+        {
+            return Err(SwapError::CounterOfferCannotBeWithdrawn.into());
+        }
+
+        self.release_escrow(params, remaining_accounts)?;
+
+        // emit event
+        swap_emit!(
+            CounterOfferWithdrawn {
+                actor: self.signer.key().clone(),
+                proposal_key: self.swap_proposal.key().clone(),
+                id: self.swap_proposal.id.clone(),
+                counter_offer_key: self.counter_offer.key().clone(),
+                option_id: self.counter_offer.swap_option.id.clone(),
+            }
+        );
+
+        return Ok(());
+    }
+
+    // Return every escrowed item of the counter offer to the taker
+    fn release_escrow(&mut self, params: WithdrawCounterOfferParams, remaining_accounts: &[AccountInfo<'info>]) -> Result<()> {
+        let items = self.counter_offer.swap_option.asking_items.clone();
+
+        if items.len() != params.swap_token_vault_bumps.len() {
+            return Err(SwapError::InvalidValue.into());
+        }
+
+        let mut cursor = 0;
+        let extra_accounts = extra_accounts(remaining_accounts, items.len());
+        let transfer = ItemTransfer {
+            signer: self.signer.to_account_info(),
+            token_program: self.token_program.to_account_info(),
+            system_program: self.system_program.to_account_info(),
+            extra_accounts,
+        };
+        for (index, item) in items.iter().enumerate() {
+            release_item_to(
+                &mut self.swap_registry,
+                &self.swap_proposal,
+                item,
+                SwapItemStatus::Withdrawn,
+                self.counter_offer.taker.clone(),
+                ItemAccounts {
+                    accounts: next_item_accounts(remaining_accounts, &mut cursor)?,
+                    vault_bump: params.swap_token_vault_bumps[index],
+                },
+                &transfer,
+            )?;
+
+            swap_emit!(
+                ItemWithdrawn {
+                    id: item.id.clone(),
+                    proposal_key: self.swap_proposal.key().clone(),
+                    status: SwapItemStatus::Withdrawn,
+                    actor: self.signer.key().clone()
+                }
+            );
+        }
+
+        // the proposal can be closed once no counter offer holds escrowed items
+        self.swap_proposal.open_counter_offers = self.swap_proposal.open_counter_offers.saturating_sub(1);
+
+        return Ok(());
+    }
+}
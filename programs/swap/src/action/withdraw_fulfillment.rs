@@ -0,0 +1,126 @@
+use crate::*;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Default, Clone, Debug, PartialEq)]
+pub struct WithdrawFulfillmentParams {
+    pub proposal_id: String,
+    // The vault bumps of the deposited asking items, following the order of the option asking items
+    pub swap_token_vault_bumps: Vec<u8>,
+}
+
+// Define the context, the item accounts are passed through the remaining accounts
+// as [mint_account, signer_token_account, swap_token_vault] for every deposited asking item of the reserved option,
+// or as [system_program, signer, native_vault] for native SOL items,
+// followed by the other token program when items of both token programs are mixed
+// and the transfer-hook extra accounts if any.
+#[derive(Accounts)]
+#[instruction(params: WithdrawFulfillmentParams)]
+pub struct WithdrawFulfillmentContext<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [PLATFORM_SEED],
+        bump = swap_registry.bump,
+    )]
+    pub swap_registry: Account<'info, SwapPlatformRegistry>,
+
+    #[account(
+        mut,
+        constraint = swap_proposal.is_proposal_address(swap_proposal.key(), &params.proposal_id) @ SwapError::InvalidProposal,
+    )]
+    pub swap_proposal: Account<'info, SwapProposal>,
+
+    #[account(address = system_program::ID)]
+    pub system_program: Program<'info, System>,
+
+    /// CHECK: the token program or the Token-2022 program
+    #[account(constraint = token_interface::is_token_program(token_program.key) @ SwapError::InvalidTokenProgram)]
+    pub token_program: AccountInfo<'info>,
+}
+
+impl<'info> WithdrawFulfillmentContext<'info> {
+    pub fn execute(&mut self, params: WithdrawFulfillmentParams, remaining_accounts: &[AccountInfo<'info>]) -> Result<()> {
+        // Only the participant holding the reservation can withdraw its fulfillment
+        if !self.swap_proposal.is_fulfilled_participant(self.signer.key().clone()) {
+            return Err(SwapError::OnlyParticipant.into());
+        }
+
+        // A completed fulfillment cannot be withdrawn
+        if self.swap_proposal.status != SwapProposalStatus::Deposited {
+            return Err(SwapError::WithdrawalIsNotAvailable.into());
+        }
+
+        let option_id = self.swap_proposal.fulfilled_with_option_id.clone();
+
+        // find the option id
+        let option_index = self.swap_proposal.swap_options
+            .iter()
+            .position(|x| x.id == option_id)
+            .ok_or(SwapError::SwapOptionNotFound)?;
+
+        // find the deposited items
+        let item_indexes: Vec<usize> = self.swap_proposal.swap_options[option_index].asking_items
+            .iter()
+            .enumerate()
+            .filter(|(_, item)| item.status == SwapItemStatus::Deposited)
+            .map(|(index, _)| index)
+            .collect();
+
+        if item_indexes.len() != params.swap_token_vault_bumps.len() {
+            return Err(SwapError::InvalidValue.into());
+        }
+
+        let mut cursor = 0;
+        let extra_accounts = extra_accounts(remaining_accounts, item_indexes.len());
+        let transfer = ItemTransfer {
+            signer: self.signer.to_account_info(),
+            token_program: self.token_program.to_account_info(),
+            system_program: self.system_program.to_account_info(),
+            extra_accounts,
+        };
+        for (position, &index) in item_indexes.iter().enumerate() {
+            let item = self.swap_proposal.swap_options[option_index].asking_items[index].clone();
+
+            release_item_to(
+                &mut self.swap_registry,
+                &self.swap_proposal,
+                &item,
+                SwapItemStatus::Withdrawn,
+                self.signer.key(),
+                ItemAccounts {
+                    accounts: next_item_accounts(remaining_accounts, &mut cursor)?,
+                    vault_bump: params.swap_token_vault_bumps[position],
+                },
+                &transfer,
+            )?;
+
+            // update the item status
+            self.swap_proposal.swap_options[option_index].asking_items[index].status = SwapItemStatus::Withdrawn;
+
+            swap_emit!(
+                ItemWithdrawn {
+                    id: item.id.clone(),
+                    proposal_key: self.swap_proposal.key().clone(),
+                    status: SwapItemStatus::Withdrawn,
+                    actor: self.signer.key().clone()
+                }
+            );
+        }
+
+        // reopen the proposal, nothing is left to refund since every item was returned
+        self.swap_proposal.release_reservation()?;
+
+        // emit event
+        swap_emit!(
+            FulfillmentWithdrawn {
+                actor: self.signer.key().clone(),
+                proposal_key: self.swap_proposal.key().clone(),
+                id: self.swap_proposal.id.clone(),
+                option_id,
+            }
+        );
+
+        return Ok(());
+    }
+}
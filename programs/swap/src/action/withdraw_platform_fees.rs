@@ -0,0 +1,87 @@
+use crate::*;
+
+// Define params
+#[derive(AnchorSerialize, AnchorDeserialize, Default, Clone, Debug, PartialEq)]
+pub struct WithdrawPlatformFeesParams {
+    // define the lamports to be withdrawn from the treasury
+    pub amount: u64,
+}
+
+// Define the context, passed in parameters when trigger from deployer.
+#[derive(Accounts)]
+pub struct WithdrawPlatformFeesContext<'info> {
+    // We define the fee payer
+    #[account(
+        mut,
+        address = swap_registry.owner @ SwapError::OnlyAdministrator
+    )]
+    pub owner: Signer<'info>,
+
+    #[account(
+        seeds = [PLATFORM_SEED],
+        bump = swap_registry.bump,
+        has_one = owner
+    )]
+    pub swap_registry: Account<'info, SwapPlatformRegistry>,
+
+    #[account(
+        mut,
+        seeds = [TREASURY_SEED],
+        bump,
+    )]
+    pub swap_treasury: SystemAccount<'info>,
+
+    #[account(
+        mut,
+        address = swap_registry.fee_config.fee_recipient @ SwapError::InvalidFeeRecipient
+    )]
+    pub fee_recipient: SystemAccount<'info>,
+
+    #[account(address = system_program::ID)]
+    pub system_program: Program<'info, System>,
+}
+
+// implement the handler
+impl<'info> WithdrawPlatformFeesContext<'info> {
+    pub fn execute(&mut self, params: WithdrawPlatformFeesParams, treasury_bump: u8) -> Result<()> {
+        // The treasury must stay rent exempt
+        let rent_exempt_minimum = Rent::get()?.minimum_balance(0);
+        let withdrawable_amount = self.swap_treasury.lamports().saturating_sub(rent_exempt_minimum);
+
+        if params.amount == 0 || params.amount > withdrawable_amount {
+            return Err(SwapError::InsufficientAccruedFees.into());
+        }
+
+        // find the bump to sign with the pda
+        let bump = &[treasury_bump][..];
+        let signer = token_account_signer!(
+            TREASURY_SEED,
+            bump
+        );
+
+        // transfer the lamports
+        anchor_lang::system_program::transfer(
+            CpiContext::new_with_signer(
+                self.system_program.to_account_info(),
+                anchor_lang::system_program::Transfer {
+                    from: self.swap_treasury.to_account_info(),
+                    to: self.fee_recipient.to_account_info(),
+                },
+                signer,
+            ),
+            params.amount,
+        )?;
+
+        // emit event
+        swap_emit!(
+            FeeWithdrawn {
+                actor: self.owner.key().clone(),
+                fee_recipient: self.fee_recipient.key().clone(),
+                mint_account: Pubkey::default(),
+                amount: params.amount,
+            }
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,108 @@
+use crate::*;
+use crate::token_interface::{InterfaceTokenAccount, TransferChecked};
+
+// Define params
+#[derive(AnchorSerialize, AnchorDeserialize, Default, Clone, Debug, PartialEq)]
+pub struct WithdrawPlatformTokenFeesParams {
+    // define the accrued fees to be withdrawn from the vault
+    pub amount: u64,
+}
+
+// Define the context, passed in parameters when trigger from deployer.
+// The transfer-hook extra accounts of Token-2022 mints are passed through the remaining accounts.
+#[derive(Accounts)]
+pub struct WithdrawPlatformTokenFeesContext<'info> {
+    // We define the fee payer
+    #[account(
+        mut,
+        address = swap_registry.owner @ SwapError::OnlyAdministrator
+    )]
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [PLATFORM_SEED],
+        bump = swap_registry.bump,
+        has_one = owner
+    )]
+    pub swap_registry: Account<'info, SwapPlatformRegistry>,
+
+    /// CHECK: the mint of either token program, checked against the token program
+    #[account(
+        constraint = *mint_account.owner == token_program.key() @ SwapError::InvalidTokenProgram
+    )]
+    pub mint_account: AccountInfo<'info>,
+
+    /// CHECK: the vault of the mint, loaded through the token interface
+    #[account(
+        mut,
+        seeds = [TOKEN_ACCOUNT_SEED, mint_account.key().as_ref()],
+        bump
+    )]
+    pub swap_token_vault: AccountInfo<'info>,
+
+    /// CHECK: the token account of the fee recipient, loaded through the token interface
+    #[account(mut)]
+    pub fee_recipient_token_account: AccountInfo<'info>,
+
+    #[account(address = system_program::ID)]
+    pub system_program: Program<'info, System>,
+
+    /// CHECK: the token program or the Token-2022 program, it must own the mint
+    #[account(
+        constraint = token_interface::is_token_program(token_program.key) @ SwapError::InvalidTokenProgram
+    )]
+    pub token_program: AccountInfo<'info>,
+}
+
+// implement the handler
+impl<'info> WithdrawPlatformTokenFeesContext<'info> {
+    pub fn execute(&mut self, params: WithdrawPlatformTokenFeesParams, remaining_accounts: &[AccountInfo<'info>]) -> Result<()> {
+        if params.amount == 0 {
+            return Err(SwapError::InvalidValue.into());
+        }
+
+        // The fees must be sent to a token account of the fee recipient
+        let fee_recipient_token_account = InterfaceTokenAccount::load(&self.fee_recipient_token_account)?;
+        if fee_recipient_token_account.owner != self.swap_registry.fee_config.fee_recipient
+            || fee_recipient_token_account.mint != self.mint_account.key() {
+            return Err(SwapError::InvalidFeeRecipient.into());
+        }
+
+        // Only the accrued fees can leave the vault
+        self.swap_registry.release_token_fee(self.mint_account.key().clone(), params.amount)?;
+
+        // find the bump to sign with the pda
+        let bump = &[self.swap_registry.bump][..];
+        let signer = token_account_signer!(
+            PLATFORM_SEED,
+            bump
+        );
+
+        // transfer the token
+        token_interface::transfer_checked(
+            TransferChecked {
+                token_program: self.token_program.to_account_info(),
+                from: self.swap_token_vault.to_account_info(),
+                mint: self.mint_account.to_account_info(),
+                to: self.fee_recipient_token_account.to_account_info(),
+                authority: self.swap_registry.to_account_info(),
+            },
+            remaining_accounts,
+            params.amount,
+            signer,
+        )?;
+
+        // emit event
+        swap_emit!(
+            FeeWithdrawn {
+                actor: self.owner.key().clone(),
+                fee_recipient: self.swap_registry.fee_config.fee_recipient.clone(),
+                mint_account: self.mint_account.key().clone(),
+                amount: params.amount,
+            }
+        );
+
+        Ok(())
+    }
+}
@@ -4,3 +4,16 @@ pub const PLATFORM_SEED: &[u8] = b"SEED::SWAP::PLATFORM";
 pub const TOKEN_ACCOUNT_SEED: &[u8] = b"SEED::SWAP::TOKEN_VAULT_SEED";
 pub const PROPOSAL_SEED: &[u8] = b"SEED::SWAP::PROPOSAL_SEED";
 pub const LOOKUP_TABLE_SEED: &[u8] = b"SEED::SWAP::LOOKUP_TABLE_SEED";
+pub const PROPOSAL_VAULT_SEED: &[u8] = b"SEED::SWAP::PROPOSAL_VAULT_SEED";
+pub const TREASURY_SEED: &[u8] = b"SEED::SWAP::TREASURY_SEED";
+pub const COUNTER_OFFER_SEED: &[u8] = b"SEED::SWAP::COUNTER_OFFER_SEED";
+pub const NATIVE_VAULT_SEED: &[u8] = b"SEED::SWAP::NATIVE_VAULT_SEED";
+
+// Define the fee denominator, fees are expressed in basis points
+pub const FEE_BPS_DENOMINATOR: u64 = 10_000;
+
+// Define the max number of participants a private proposal can be restricted to
+pub const MAX_ALLOWED_PARTICIPANTS: usize = 10;
+
+// Define the number of remaining accounts passed for each swap item: mint, token account and vault
+pub const ITEM_ACCOUNTS_LEN: usize = 3;
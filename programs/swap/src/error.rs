@@ -24,8 +24,10 @@ pub enum SwapError {
     InvalidOffer,
     #[msg("Invalid value")]
     InvalidValue,
-    #[msg("Invalid value")]
+    #[msg("The mint token is not allowed")]
     UnAllowedMintToken,
+    #[msg("The mint account is disabled or pending approval")]
+    MintAccountDisabled,
     #[msg("Proposal cannot be canceled")]
     ProposalCannotBeCanceled,
     #[msg("Withdrawal is not available for the proposal")]
@@ -40,4 +42,60 @@ pub enum SwapError {
     FulfillingIsNotAvailable,
     #[msg("Only participants can execute this operation")]
     OnlyParticipant,
+    #[msg("Only the pending owner can accept the ownership")]
+    OnlyPendingOwner,
+    #[msg("The mint account was not found")]
+    MintAccountNotFound,
+    #[msg("The vault still holds escrowed balances")]
+    VaultIsNotEmpty,
+    #[msg("Only Platform Admin or Vault Creator")]
+    OnlyVaultCreator,
+    #[msg("The fee recipient is not configured")]
+    InvalidFeeRecipient,
+    #[msg("The withdrawn amount exceeds the accrued fees")]
+    InsufficientAccruedFees,
+    #[msg("The swap item was not found")]
+    SwapItemNotFound,
+    #[msg("The swap option was not found")]
+    SwapOptionNotFound,
+    #[msg("Invalid token account")]
+    InvalidTokenAccount,
+    #[msg("The token account mint does not match the mint account")]
+    InvalidTokenAccountMint,
+    #[msg("The token account is not owned by the signer")]
+    InvalidTokenAccountOwner,
+    #[msg("The mint account does not match the swap item")]
+    MintAccountMismatch,
+    #[msg("Insufficient balance")]
+    InsufficientBalance,
+    #[msg("The vault does not belong to the proposal")]
+    InvalidVault,
+    #[msg("The operation is not available for the vault mode of the proposal")]
+    InvalidVaultMode,
+    #[msg("Proposal cannot be closed until it is fully redeemed or withdrawn")]
+    ProposalCannotBeClosed,
+    #[msg("The proposal account does not match the proposal id")]
+    InvalidProposal,
+    #[msg("Proposal cannot be expired before its expiry or once it is settled")]
+    ProposalCannotBeExpired,
+    #[msg("Proposal cannot be settled until it is canceled, expired or fulfilled")]
+    ProposalCannotBeSettled,
+    #[msg("The reservation is still within the reservation window")]
+    ReservationIsNotStale,
+    #[msg("Only the participants allowed by the proposal owner can fulfill the proposal")]
+    OnlyAllowedParticipant,
+    #[msg("The counter offer has expired")]
+    CounterOfferExpired,
+    #[msg("The counter offer cannot be withdrawn before its expiry while the proposal is open")]
+    CounterOfferCannotBeWithdrawn,
+    #[msg("Proposal cannot be updated once an option is reserved or after its expiry")]
+    ProposalCannotBeUpdated,
+    #[msg("The token program does not own the mint account")]
+    InvalidTokenProgram,
+    #[msg("The metadata account is invalid")]
+    InvalidMetadata,
+    #[msg("The mint is not a verified member of the collection")]
+    CollectionMismatch,
+    #[msg("The token account of a creator receiving royalties was not provided")]
+    RoyaltyAccountNotFound,
 }
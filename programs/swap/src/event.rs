@@ -17,6 +17,9 @@ pub struct SwapConfigUpdated {
     pub actor: Pubkey,
     pub max_allowed_items: u8,
     pub max_allowed_options: u8,
+    pub default_vault_mode: VaultMode,
+    pub reservation_window: u64,
+    pub royalty_mode: RoyaltyMode,
 }
 
 
@@ -31,6 +34,7 @@ pub struct VaultCreated {
     pub mint_account: Pubkey,
     #[index]
     pub associated_account: Pubkey,
+    pub is_enabled: bool,
 }
 
 /// Emitted when a [ProposalCreated] is created.
@@ -118,3 +122,242 @@ pub struct ItemRedeemed {
     pub status: SwapItemStatus,
 }
 
+/// Emitted when a [OwnershipTransferProposed] is created.
+#[event]
+pub struct OwnershipTransferProposed {
+    #[index]
+    pub actor: Pubkey,
+    #[index]
+    pub pending_owner: Pubkey,
+}
+
+/// Emitted when a [OwnershipTransferred] is created.
+#[event]
+pub struct OwnershipTransferred {
+    #[index]
+    pub actor: Pubkey,
+    #[index]
+    pub previous_owner: Pubkey,
+    #[index]
+    pub new_owner: Pubkey,
+}
+
+/// Emitted when a [MintAccountUpdated] is created.
+#[event]
+pub struct MintAccountUpdated {
+    #[index]
+    pub actor: Pubkey,
+    #[index]
+    pub mint_account: Pubkey,
+    pub is_enabled: bool,
+}
+
+/// Emitted when a [MintAccountRemoved] is created.
+#[event]
+pub struct MintAccountRemoved {
+    #[index]
+    pub actor: Pubkey,
+    #[index]
+    pub mint_account: Pubkey,
+    #[index]
+    pub associated_account: Pubkey,
+}
+
+/// Emitted when a [VaultCreationPolicyUpdated] is created.
+#[event]
+pub struct VaultCreationPolicyUpdated {
+    #[index]
+    pub actor: Pubkey,
+    #[index]
+    pub vault_creator: Pubkey,
+    pub vault_creation_policy: VaultCreationPolicy,
+}
+
+/// Emitted when a [FeeConfigUpdated] is created.
+#[event]
+pub struct FeeConfigUpdated {
+    #[index]
+    pub actor: Pubkey,
+    #[index]
+    pub fee_recipient: Pubkey,
+    pub proposal_creation_fee: u64,
+    pub fulfillment_fee: u64,
+    pub crank_bounty: u64,
+}
+
+/// Emitted when a [MintFeeScheduleUpdated] is created.
+#[event]
+pub struct MintFeeScheduleUpdated {
+    #[index]
+    pub actor: Pubkey,
+    #[index]
+    pub mint_account: Pubkey,
+    pub fee_bps: u16,
+}
+
+/// Emitted when a [FeeCollected] is created, the default mint account stands for lamports.
+#[event]
+pub struct FeeCollected {
+    #[index]
+    pub actor: Pubkey,
+    #[index]
+    pub proposal_key: Pubkey,
+    #[index]
+    pub mint_account: Pubkey,
+    pub amount: u64,
+}
+
+/// Emitted when a [FeeWithdrawn] is created, the default mint account stands for lamports.
+#[event]
+pub struct FeeWithdrawn {
+    #[index]
+    pub actor: Pubkey,
+    #[index]
+    pub fee_recipient: Pubkey,
+    #[index]
+    pub mint_account: Pubkey,
+    pub amount: u64,
+}
+
+/// Emitted when a [ProposalExpired] is created.
+#[event]
+pub struct ProposalExpired {
+    #[index]
+    pub actor: Pubkey,
+    #[index]
+    pub proposal_key: Pubkey,
+    #[index]
+    pub id: String,
+    pub status: SwapProposalStatus,
+}
+
+/// Emitted when a [ProposalSettled] is created.
+#[event]
+pub struct ProposalSettled {
+    #[index]
+    pub actor: Pubkey,
+    #[index]
+    pub proposal_key: Pubkey,
+    #[index]
+    pub id: String,
+    pub status: SwapProposalStatus,
+    pub bounty: u64,
+}
+
+/// Emitted when a [ReservationReleased] is created.
+#[event]
+pub struct ReservationReleased {
+    #[index]
+    pub actor: Pubkey,
+    #[index]
+    pub proposal_key: Pubkey,
+    #[index]
+    pub id: String,
+    pub participant: Pubkey,
+    pub option_id: String,
+}
+
+/// Emitted when a [FulfillmentWithdrawn] is created.
+#[event]
+pub struct FulfillmentWithdrawn {
+    #[index]
+    pub actor: Pubkey,
+    #[index]
+    pub proposal_key: Pubkey,
+    #[index]
+    pub id: String,
+    pub option_id: String,
+}
+
+/// Emitted when a [ProposalUpdated] is created.
+#[event]
+pub struct ProposalUpdated {
+    #[index]
+    pub actor: Pubkey,
+    #[index]
+    pub proposal_key: Pubkey,
+    #[index]
+    pub id: String,
+    pub expired_at: i64,
+}
+
+/// Emitted when a [CounterOfferCreated] is created.
+#[event]
+pub struct CounterOfferCreated {
+    #[index]
+    pub actor: Pubkey,
+    #[index]
+    pub proposal_key: Pubkey,
+    #[index]
+    pub id: String,
+    pub counter_offer_key: Pubkey,
+    pub option_id: String,
+    pub expired_at: u64,
+}
+
+/// Emitted when a [CounterOfferAccepted] is created.
+#[event]
+pub struct CounterOfferAccepted {
+    #[index]
+    pub actor: Pubkey,
+    #[index]
+    pub proposal_key: Pubkey,
+    #[index]
+    pub id: String,
+    pub counter_offer_key: Pubkey,
+    pub taker: Pubkey,
+    pub option_id: String,
+}
+
+/// Emitted when a [CounterOfferRejected] is created.
+#[event]
+pub struct CounterOfferRejected {
+    #[index]
+    pub actor: Pubkey,
+    #[index]
+    pub proposal_key: Pubkey,
+    #[index]
+    pub id: String,
+    pub counter_offer_key: Pubkey,
+    pub taker: Pubkey,
+    pub option_id: String,
+}
+
+/// Emitted when a [CounterOfferWithdrawn] is created.
+#[event]
+pub struct CounterOfferWithdrawn {
+    #[index]
+    pub actor: Pubkey,
+    #[index]
+    pub proposal_key: Pubkey,
+    #[index]
+    pub id: String,
+    pub counter_offer_key: Pubkey,
+    pub option_id: String,
+}
+
+/// Emitted when a [RoyaltyPaid] is created, the royalty is paid in the currency mint to the creator of the NFT.
+#[event]
+pub struct RoyaltyPaid {
+    #[index]
+    pub actor: Pubkey,
+    #[index]
+    pub proposal_key: Pubkey,
+    #[index]
+    pub nft_mint_account: Pubkey,
+    pub creator: Pubkey,
+    pub mint_account: Pubkey,
+    pub amount: u64,
+}
+
+/// Emitted when a [ProposalClosed] is created.
+#[event]
+pub struct ProposalClosed {
+    #[index]
+    pub actor: Pubkey,
+    #[index]
+    pub proposal_key: Pubkey,
+    #[index]
+    pub id: String,
+    pub status: SwapProposalStatus,
+}
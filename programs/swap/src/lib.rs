@@ -1,4 +1,3 @@
-use std::borrow::BorrowMut;
 use anchor_lang::prelude::*;
 use anchor_lang::solana_program::{system_program, sysvar};
 use anchor_spl::token::{self, Mint, Token, TokenAccount, Transfer};
@@ -12,6 +11,8 @@ pub mod event;
 pub mod state;
 pub mod constants;
 pub mod macros;
+pub mod token_interface;
+pub mod metadata;
 
 pub use action::*;
 pub use constants::*;
@@ -35,7 +36,7 @@ pub mod swap {
         ctx.accounts.execute(
             params,
             *ctx.bumps.get("swap_registry").unwrap(),
-        ).unwrap();
+        )?;
 
         // Program result should be ok.
         Ok(())
@@ -47,19 +48,30 @@ pub mod swap {
         params: UpdateSwapPlatformParams
     ) -> Result<()> {
         // execute with context
-        ctx.accounts.execute(params).unwrap();
+        ctx.accounts.execute(params)?;
 
         // Program result should be ok.
         Ok(())
     }
 
-    // Create proposal, public to anyone
+    // Create token vault, gated by the vault creation policy
     pub fn create_token_vault(
         ctx: Context<CreateTokenVaultContext>
     ) -> Result<()> {
         ctx.accounts.execute(
             *ctx.bumps.get("swap_token_vault").unwrap(),
-        ).unwrap();
+        )?;
+
+        Ok(())
+    }
+
+    // Create the escrow vault of a mint for a per-proposal vault proposal
+    pub fn create_proposal_vault(
+        ctx: Context<CreateProposalVaultContext>
+    ) -> Result<()> {
+        ctx.accounts.execute(
+            *ctx.bumps.get("proposal_token_vault").unwrap(),
+        )?;
 
         Ok(())
     }
@@ -72,8 +84,17 @@ pub mod swap {
         ctx.accounts.execute(
             params,
             *ctx.bumps.get("swap_proposal").unwrap(),
-        ).unwrap();
+        )?;
+
+        Ok(())
+    }
 
+    // Update the options and the expiry of a proposal before it is reserved, only the proposal owner
+    pub fn update_proposal(
+        ctx: Context<UpdateProposalContext>,
+        params: UpdateProposalParams
+    ) -> Result<()> {
+        ctx.accounts.execute(params)?;
         Ok(())
     }
 
@@ -82,26 +103,157 @@ pub mod swap {
         ctx: Context<CancelProposalContext>,
         params: CancelProposalParams
     ) -> Result<()> {
-        ctx.accounts.execute(params).unwrap();
+        ctx.accounts.execute(params)?;
+        Ok(())
+    }
+
+    // Mark a proposal past its expiry as expired, public to anyone
+    pub fn expire_proposal(
+        ctx: Context<ExpireProposalContext>,
+        params: ExpireProposalParams
+    ) -> Result<()> {
+        ctx.accounts.execute(params)?;
+        Ok(())
+    }
+
+    // Release a stale reservation and reopen the proposal, public to anyone
+    pub fn release_reservation(
+        ctx: Context<ReleaseReservationContext>,
+        params: ReleaseReservationParams
+    ) -> Result<()> {
+        ctx.accounts.execute(params)?;
+        Ok(())
+    }
+
+    // Close a settled proposal and reclaim the rent
+    pub fn close_proposal(
+        ctx: Context<CloseProposalContext>,
+        params: CloseProposalParams
+    ) -> Result<()> {
+        ctx.accounts.execute(params)?;
         Ok(())
     }
 
     // Deposit or fulfilling the proposal
-    pub fn transfer_assets_to_vault(
-        ctx: Context<TransferAssetsToVaultContext>,
+    pub fn transfer_assets_to_vault<'info>(
+        ctx: Context<'_, '_, '_, 'info, TransferAssetsToVaultContext<'info>>,
         params: TransferAssetsToVaultParams
     ) -> Result<()> {
-        ctx.accounts.execute(params).unwrap();
+        ctx.accounts.execute(params, ctx.remaining_accounts)?;
+
+        Ok(())
+    }
+
+    // Deposit every offered item of the proposal at once
+    pub fn deposit_all<'info>(
+        ctx: Context<'_, '_, '_, 'info, DepositAllContext<'info>>,
+        params: DepositAllParams
+    ) -> Result<()> {
+        ctx.accounts.execute(params, ctx.remaining_accounts)?;
+
+        Ok(())
+    }
+
+    // Fulfill the proposal and redeem the offered items in one go
+    pub fn accept_proposal<'info>(
+        ctx: Context<'_, '_, '_, 'info, AcceptProposalContext<'info>>,
+        params: AcceptProposalParams
+    ) -> Result<()> {
+        ctx.accounts.execute(params, ctx.remaining_accounts)?;
 
         Ok(())
     }
 
     // Withdrawing or redeeming the proposal
-    pub fn transfer_assets_from_vault(
-        ctx: Context<TransferAssetsFromVaultContext>,
+    pub fn transfer_assets_from_vault<'info>(
+        ctx: Context<'_, '_, '_, 'info, TransferAssetsFromVaultContext<'info>>,
         params: TransferAssetsFromVaultParams
     ) -> Result<()> {
-        ctx.accounts.execute(params).unwrap();
+        ctx.accounts.execute(params, ctx.remaining_accounts)?;
+
+        Ok(())
+    }
+
+    // Redeem every item the signer is entitled to
+    pub fn redeem_all<'info>(
+        ctx: Context<'_, '_, '_, 'info, TransferAllAssetsFromVaultContext<'info>>,
+        params: TransferAllAssetsFromVaultParams
+    ) -> Result<()> {
+        ctx.accounts.redeem_all(params, ctx.remaining_accounts)?;
+
+        Ok(())
+    }
+
+    // Withdraw every item the signer is entitled to
+    pub fn withdraw_all<'info>(
+        ctx: Context<'_, '_, '_, 'info, TransferAllAssetsFromVaultContext<'info>>,
+        params: TransferAllAssetsFromVaultParams
+    ) -> Result<()> {
+        ctx.accounts.withdraw_all(params, ctx.remaining_accounts)?;
+
+        Ok(())
+    }
+
+    // The participant takes back its partial fulfillment and reopens the proposal
+    pub fn withdraw_fulfillment<'info>(
+        ctx: Context<'_, '_, '_, 'info, WithdrawFulfillmentContext<'info>>,
+        params: WithdrawFulfillmentParams
+    ) -> Result<()> {
+        ctx.accounts.execute(params, ctx.remaining_accounts)?;
+
+        Ok(())
+    }
+
+    // Make a counter offer on a proposal, the proposed asking items are escrowed up front
+    pub fn create_counter_offer<'info>(
+        ctx: Context<'_, '_, '_, 'info, CreateCounterOfferContext<'info>>,
+        params: CreateCounterOfferParams
+    ) -> Result<()> {
+        ctx.accounts.execute(
+            params,
+            *ctx.bumps.get("counter_offer").unwrap(),
+            ctx.remaining_accounts,
+        )?;
+
+        Ok(())
+    }
+
+    // Accept a counter offer as the fulfilled option, only the proposal owner
+    pub fn accept_counter_offer(
+        ctx: Context<AcceptCounterOfferContext>,
+        params: AcceptCounterOfferParams
+    ) -> Result<()> {
+        ctx.accounts.execute(params)?;
+
+        Ok(())
+    }
+
+    // Reject a counter offer and release the escrow of the taker, only the proposal owner
+    pub fn reject_counter_offer<'info>(
+        ctx: Context<'_, '_, '_, 'info, WithdrawCounterOfferContext<'info>>,
+        params: WithdrawCounterOfferParams
+    ) -> Result<()> {
+        ctx.accounts.reject(params, ctx.remaining_accounts)?;
+
+        Ok(())
+    }
+
+    // Withdraw an expired or outdated counter offer, only the taker
+    pub fn withdraw_counter_offer<'info>(
+        ctx: Context<'_, '_, '_, 'info, WithdrawCounterOfferContext<'info>>,
+        params: WithdrawCounterOfferParams
+    ) -> Result<()> {
+        ctx.accounts.withdraw(params, ctx.remaining_accounts)?;
+
+        Ok(())
+    }
+
+    // Push every deposited item of a settled proposal to its rightful owner, public to anyone
+    pub fn crank_settle<'info>(
+        ctx: Context<'_, '_, '_, 'info, CrankSettleContext<'info>>,
+        params: CrankSettleParams
+    ) -> Result<()> {
+        ctx.accounts.execute(params, ctx.remaining_accounts)?;
 
         Ok(())
     }
@@ -111,7 +263,7 @@ pub mod swap {
         ctx: Context<CreateAddressLookupTableContext>,
         params: CreateAddressLookupTableParams
     ) -> Result<()> {
-        ctx.accounts.execute(params).unwrap();
+        ctx.accounts.execute(params)?;
 
         Ok(())
     }
@@ -122,7 +274,98 @@ pub mod swap {
     ) -> Result<()> {
         ctx.accounts.execute(
             *ctx.bumps.get("lookup_table_registry").unwrap(),
-        ).unwrap();
+        )?;
+
+        Ok(())
+    }
+
+    // Deployer proposes a new owner for the swap registry
+    pub fn propose_owner(
+        ctx: Context<ProposeOwnerContext>,
+        params: ProposeOwnerParams
+    ) -> Result<()> {
+        ctx.accounts.execute(params)?;
+
+        Ok(())
+    }
+
+    // The pending owner accepts the ownership of the swap registry
+    pub fn accept_ownership(
+        ctx: Context<AcceptOwnershipContext>,
+    ) -> Result<()> {
+        ctx.accounts.execute()?;
+
+        Ok(())
+    }
+
+    // Deployer enables or disables a whitelisted mint
+    pub fn set_mint_enabled(
+        ctx: Context<SetMintEnabledContext>,
+        params: SetMintEnabledParams
+    ) -> Result<()> {
+        ctx.accounts.execute(params)?;
+
+        Ok(())
+    }
+
+    // Deployer removes a whitelisted mint once its vault is empty
+    pub fn remove_mint(
+        ctx: Context<RemoveMintContext>,
+    ) -> Result<()> {
+        ctx.accounts.execute()?;
+
+        Ok(())
+    }
+
+    // Deployer configures who can create token vaults
+    pub fn update_vault_creation_policy(
+        ctx: Context<UpdateVaultCreationPolicyContext>,
+        params: UpdateVaultCreationPolicyParams
+    ) -> Result<()> {
+        ctx.accounts.execute(params)?;
+
+        Ok(())
+    }
+
+    // Deployer configures the platform fees
+    pub fn update_fee_config(
+        ctx: Context<UpdateFeeConfigContext>,
+        params: UpdateFeeConfigParams
+    ) -> Result<()> {
+        ctx.accounts.execute(params)?;
+
+        Ok(())
+    }
+
+    // Deployer configures the fee taken on currency legs of a mint
+    pub fn set_mint_fee_schedule(
+        ctx: Context<SetMintFeeScheduleContext>,
+        params: SetMintFeeScheduleParams
+    ) -> Result<()> {
+        ctx.accounts.execute(params)?;
+
+        Ok(())
+    }
+
+    // Deployer withdraws the lamports collected in the treasury
+    pub fn withdraw_platform_fees(
+        ctx: Context<WithdrawPlatformFeesContext>,
+        params: WithdrawPlatformFeesParams
+    ) -> Result<()> {
+        ctx.accounts.execute(
+            params,
+            *ctx.bumps.get("swap_treasury").unwrap(),
+        )?;
+
+        Ok(())
+    }
+
+    // Deployer withdraws the token fees accrued in a vault
+    pub fn withdraw_platform_token_fees<'info>(
+        ctx: Context<'_, '_, '_, 'info, WithdrawPlatformTokenFeesContext<'info>>,
+        params: WithdrawPlatformTokenFeesParams
+    ) -> Result<()> {
+        ctx.accounts.execute(params, ctx.remaining_accounts)?;
 
         Ok(())
     }
@@ -7,3 +7,10 @@ macro_rules! token_account_signer {
         &[&[$seed, $bump][..]]
     };
 }
+
+#[macro_export]
+macro_rules! proposal_signer {
+    ($seeds: expr) => {
+        &[&$seeds[..]]
+    };
+}
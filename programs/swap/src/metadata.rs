@@ -0,0 +1,386 @@
+//! Token Metadata shim, anchor-spl 0.26 ships the metadata bindings of an outdated Token Metadata version.
+//! Only the leading fields of the metadata account used by the program are read.
+use crate::*;
+use anchor_lang::solana_program::instruction::{AccountMeta, Instruction};
+use anchor_lang::solana_program::program::invoke_signed;
+
+// The Metaplex Token Metadata program
+pub mod metadata_program {
+    use anchor_lang::declare_id;
+
+    declare_id!("metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s");
+}
+
+// The Metaplex Token Auth Rules program, evaluating the rule sets of programmable NFTs
+pub mod auth_rules_program {
+    use anchor_lang::declare_id;
+
+    declare_id!("auth9SigNpDKz4sJJ1DfCTuZrZNSAgh9sFD3rboVmgg");
+}
+
+// The account key of a metadata account
+const METADATA_V1_KEY: u8 = 4;
+
+// The token standard of programmable NFTs
+const PROGRAMMABLE_NON_FUNGIBLE: u8 = 4;
+
+// The instruction index of the Token Metadata transfer instruction
+const TRANSFER_INSTRUCTION: u8 = 49;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq)]
+pub struct Creator {
+    pub address: Pubkey,
+    pub verified: bool,
+    // Define the share of the royalties in percent
+    pub share: u8,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq)]
+pub struct Collection {
+    pub verified: bool,
+    pub key: Pubkey,
+}
+
+// Define the fields of the metadata account, following the Token Metadata account layout
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq)]
+pub struct Metadata {
+    pub key: u8,
+    pub update_authority: Pubkey,
+    pub mint: Pubkey,
+    pub name: String,
+    pub symbol: String,
+    pub uri: String,
+    pub seller_fee_basis_points: u16,
+    pub creators: Option<Vec<Creator>>,
+    pub primary_sale_happened: bool,
+    pub is_mutable: bool,
+    pub edition_nonce: Option<u8>,
+    pub token_standard: Option<u8>,
+    pub collection: Option<Collection>,
+
+    // Define the rule set of a programmable NFT, read from the trailing fields of the account
+    #[borsh_skip]
+    pub rule_set: Option<Pubkey>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq)]
+pub struct Uses {
+    pub use_method: u8,
+    pub remaining: u64,
+    pub total: u64,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq)]
+pub enum CollectionDetails {
+    V1 { size: u64 },
+    V2 { padding: [u8; 8] },
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq)]
+pub enum ProgrammableConfig {
+    V1 { rule_set: Option<Pubkey> },
+}
+
+// Define the trailing fields of the metadata account, missing on accounts created by older Token Metadata versions
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq)]
+struct MetadataExtension {
+    pub uses: Option<Uses>,
+    pub collection_details: Option<CollectionDetails>,
+    pub programmable_config: Option<ProgrammableConfig>,
+}
+
+// Derive the metadata account of the mint
+pub fn find_metadata_address(mint_account: &Pubkey) -> Pubkey {
+    return Pubkey::find_program_address(
+        &[b"metadata", metadata_program::ID.as_ref(), mint_account.as_ref()],
+        &metadata_program::ID,
+    ).0;
+}
+
+// Derive the master edition account of the mint
+pub fn find_master_edition_address(mint_account: &Pubkey) -> Pubkey {
+    return Pubkey::find_program_address(
+        &[b"metadata", metadata_program::ID.as_ref(), mint_account.as_ref(), b"edition"],
+        &metadata_program::ID,
+    ).0;
+}
+
+// Derive the token record of a programmable NFT held by the token account
+pub fn find_token_record_address(mint_account: &Pubkey, token_account: &Pubkey) -> Pubkey {
+    return Pubkey::find_program_address(
+        &[b"metadata", metadata_program::ID.as_ref(), mint_account.as_ref(), b"token_record", token_account.as_ref()],
+        &metadata_program::ID,
+    ).0;
+}
+
+// Find the metadata of the mint among the given accounts if the mint is a programmable NFT
+pub fn find_programmable(mint_account: &Pubkey, accounts: &[AccountInfo]) -> Option<Metadata> {
+    return Metadata::find(mint_account, accounts)
+        .ok()
+        .filter(|metadata| metadata.is_programmable());
+}
+
+// Find the account with the given address among the given accounts
+fn find_account<'info>(accounts: &[AccountInfo<'info>], address: Pubkey) -> Result<AccountInfo<'info>> {
+    return accounts
+        .iter()
+        .find(|account| account.key() == address)
+        .cloned()
+        .ok_or(SwapError::InvalidMetadata.into());
+}
+
+// Encode the Token Metadata transfer instruction: TransferArgs::V1 { amount, authorization_data: None }
+fn transfer_instruction_data(amount: u64) -> Vec<u8> {
+    let mut data = vec![TRANSFER_INSTRUCTION, 0];
+    data.extend_from_slice(&amount.to_le_bytes());
+    data.push(0);
+
+    return data;
+}
+
+// Define the accounts of a Token Metadata transfer passed by the caller
+#[derive(Clone)]
+pub struct TransferProgrammable<'info> {
+    pub source: AccountInfo<'info>,
+    pub source_owner: AccountInfo<'info>,
+    pub destination: AccountInfo<'info>,
+    pub destination_owner: AccountInfo<'info>,
+    pub mint_account: AccountInfo<'info>,
+    pub authority: AccountInfo<'info>,
+    pub payer: AccountInfo<'info>,
+    pub system_program: AccountInfo<'info>,
+    pub token_program: AccountInfo<'info>,
+}
+
+// Transfer a programmable NFT with the Token Metadata transfer instruction.
+// The metadata, the master edition, the token records, the instructions sysvar, the associated token program,
+// the Token Metadata program and the rule set with the Token Auth Rules program are looked up in the given accounts.
+pub fn transfer_programmable<'info>(
+    metadata: &Metadata,
+    transfer_accounts: TransferProgrammable<'info>,
+    accounts: &[AccountInfo<'info>],
+    amount: u64,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    let TransferProgrammable {
+        source,
+        source_owner,
+        destination,
+        destination_owner,
+        mint_account,
+        authority,
+        payer,
+        system_program,
+        token_program,
+    } = transfer_accounts;
+
+    let mint_key = mint_account.key();
+    let metadata_account = find_account(accounts, find_metadata_address(&mint_key))?;
+    let edition = find_account(accounts, find_master_edition_address(&mint_key))?;
+    let owner_token_record = find_account(accounts, find_token_record_address(&mint_key, &source.key()))?;
+    let destination_token_record = find_account(accounts, find_token_record_address(&mint_key, &destination.key()))?;
+    let instructions_sysvar = find_account(accounts, sysvar::instructions::ID)?;
+    let associated_token_program = find_account(accounts, anchor_spl::associated_token::ID)?;
+    let token_metadata_program = find_account(accounts, metadata_program::ID)?;
+
+    // the optional accounts are replaced by the Token Metadata program
+    let (auth_rules_program, auth_rules) = match metadata.rule_set {
+        Some(rule_set) => (
+            find_account(accounts, auth_rules_program::ID)?,
+            find_account(accounts, rule_set)?,
+        ),
+        None => (token_metadata_program.clone(), token_metadata_program.clone()),
+    };
+
+    let instruction = Instruction {
+        program_id: metadata_program::ID,
+        accounts: vec![
+            AccountMeta::new(source.key(), false),
+            AccountMeta::new_readonly(source_owner.key(), false),
+            AccountMeta::new(destination.key(), false),
+            AccountMeta::new_readonly(destination_owner.key(), false),
+            AccountMeta::new_readonly(mint_key, false),
+            AccountMeta::new(metadata_account.key(), false),
+            AccountMeta::new_readonly(edition.key(), false),
+            AccountMeta::new(owner_token_record.key(), false),
+            AccountMeta::new(destination_token_record.key(), false),
+            AccountMeta::new_readonly(authority.key(), true),
+            AccountMeta::new(payer.key(), true),
+            AccountMeta::new_readonly(system_program.key(), false),
+            AccountMeta::new_readonly(instructions_sysvar.key(), false),
+            AccountMeta::new_readonly(token_program.key(), false),
+            AccountMeta::new_readonly(associated_token_program.key(), false),
+            AccountMeta::new_readonly(auth_rules_program.key(), false),
+            AccountMeta::new_readonly(auth_rules.key(), false),
+        ],
+        data: transfer_instruction_data(amount),
+    };
+
+    invoke_signed(
+        &instruction,
+        &[
+            source,
+            source_owner,
+            destination,
+            destination_owner,
+            mint_account,
+            metadata_account,
+            edition,
+            owner_token_record,
+            destination_token_record,
+            authority,
+            payer,
+            system_program,
+            instructions_sysvar,
+            token_program,
+            associated_token_program,
+            auth_rules_program,
+            auth_rules,
+            token_metadata_program,
+        ],
+        signer_seeds,
+    )?;
+
+    return Ok(());
+}
+
+impl Metadata {
+    // Load the metadata of the mint, the account must be the metadata address of the mint
+    pub fn load(info: &AccountInfo, mint_account: &Pubkey) -> Result<Metadata> {
+        if *info.owner != metadata_program::ID || info.key() != find_metadata_address(mint_account) {
+            return Err(SwapError::InvalidMetadata.into());
+        }
+
+        let data = info.try_borrow_data()?;
+        let mut remaining_data = &data[..];
+        let mut metadata = Metadata::deserialize(&mut remaining_data)
+            .map_err(|_| SwapError::InvalidMetadata)?;
+
+        // the trailing fields are left empty if they cannot be read
+        metadata.rule_set = MetadataExtension::deserialize(&mut remaining_data)
+            .ok()
+            .and_then(|extension| extension.programmable_config)
+            .and_then(|programmable_config| match programmable_config {
+                ProgrammableConfig::V1 { rule_set } => rule_set,
+            });
+
+        if metadata.key != METADATA_V1_KEY || metadata.mint != *mint_account {
+            return Err(SwapError::InvalidMetadata.into());
+        }
+
+        return Ok(metadata);
+    }
+
+    // Find and load the metadata of the mint among the given accounts
+    pub fn find(mint_account: &Pubkey, accounts: &[AccountInfo]) -> Result<Metadata> {
+        let metadata_address = find_metadata_address(mint_account);
+        let info = accounts
+            .iter()
+            .find(|account| account.key() == metadata_address)
+            .ok_or(SwapError::InvalidMetadata)?;
+
+        return Metadata::load(info, mint_account);
+    }
+
+    // Check whether the mint is a programmable NFT
+    pub fn is_programmable(&self) -> bool {
+        return self.token_standard == Some(PROGRAMMABLE_NON_FUNGIBLE);
+    }
+
+    // Check whether the mint belongs to the collection, the collection must be verified
+    pub fn is_verified_collection_member(&self, collection_mint: Pubkey) -> bool {
+        return match &self.collection {
+            Some(collection) => collection.verified && collection.key == collection_mint,
+            None => false,
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // The transfer arguments of the Token Metadata program, without the authorization data payload
+    #[derive(AnchorSerialize)]
+    enum TransferArgs {
+        V1 { amount: u64, authorization_data: Option<Vec<u8>> },
+    }
+
+    fn metadata_data(mint_account: Pubkey, token_standard: Option<u8>, rule_set: Option<Pubkey>) -> Vec<u8> {
+        let metadata = Metadata {
+            key: METADATA_V1_KEY,
+            update_authority: Pubkey::new_unique(),
+            mint: mint_account,
+            name: "Hamster".to_string(),
+            symbol: "HAM".to_string(),
+            uri: "https://hamsterswap.xyz".to_string(),
+            seller_fee_basis_points: 500,
+            creators: None,
+            primary_sale_happened: false,
+            is_mutable: true,
+            edition_nonce: Some(255),
+            token_standard,
+            collection: None,
+            rule_set: None,
+        };
+        let extension = MetadataExtension {
+            uses: None,
+            collection_details: None,
+            programmable_config: Some(ProgrammableConfig::V1 { rule_set }),
+        };
+
+        let mut data = metadata.try_to_vec().unwrap();
+        data.extend(extension.try_to_vec().unwrap());
+        return data;
+    }
+
+    #[test]
+    fn encodes_transfer_instruction_data() {
+        let amount = 1_234_567;
+
+        let mut expected = vec![TRANSFER_INSTRUCTION];
+        expected.extend(TransferArgs::V1 { amount, authorization_data: None }.try_to_vec().unwrap());
+
+        assert_eq!(transfer_instruction_data(amount), expected);
+        assert_eq!(transfer_instruction_data(amount)[0], 49);
+    }
+
+    #[test]
+    fn finds_programmable_metadata_with_rule_set() {
+        let mint_account = Pubkey::new_unique();
+        let rule_set = Pubkey::new_unique();
+        let key = find_metadata_address(&mint_account);
+        let mut lamports = 0;
+        let mut data = metadata_data(mint_account, Some(PROGRAMMABLE_NON_FUNGIBLE), Some(rule_set));
+        let info = AccountInfo::new(&key, false, true, &mut lamports, &mut data, &metadata_program::ID, false, 0);
+
+        let metadata = find_programmable(&mint_account, &[info]).unwrap();
+        assert!(metadata.is_programmable());
+        assert_eq!(metadata.rule_set, Some(rule_set));
+    }
+
+    #[test]
+    fn skips_metadata_of_non_programmable_nft() {
+        let mint_account = Pubkey::new_unique();
+        let key = find_metadata_address(&mint_account);
+        let mut lamports = 0;
+        let mut data = metadata_data(mint_account, Some(0), None);
+        let info = AccountInfo::new(&key, false, true, &mut lamports, &mut data, &metadata_program::ID, false, 0);
+
+        assert!(find_programmable(&mint_account, &[info.clone()]).is_none());
+        assert!(Metadata::find(&mint_account, &[info]).is_ok());
+    }
+
+    #[test]
+    fn skips_metadata_not_owned_by_token_metadata() {
+        let mint_account = Pubkey::new_unique();
+        let key = find_metadata_address(&mint_account);
+        let owner = Pubkey::new_unique();
+        let mut lamports = 0;
+        let mut data = metadata_data(mint_account, Some(PROGRAMMABLE_NON_FUNGIBLE), None);
+        let info = AccountInfo::new(&key, false, true, &mut lamports, &mut data, &owner, false, 0);
+
+        assert!(find_programmable(&mint_account, &[info]).is_none());
+        assert!(find_programmable(&mint_account, &[]).is_none());
+    }
+}
@@ -1,5 +1,6 @@
 use crate::*;
 use std::borrow::Borrow;
+use anchor_lang::solana_program::hash::hash;
 use solana_address_lookup_table_program::state::AddressLookupTable;
 
 // ================ Swap Platform Config ================ //
@@ -13,6 +14,97 @@ pub struct MintInfo {
     pub bump: u8
 }
 
+impl MintInfo {
+    // Define the serialized size
+    pub const SPACE: usize = 1 + 32 + 32 + 1;
+}
+
+// Here we define the fee schedule applied to a whitelisted mint.
+#[derive(AnchorSerialize, AnchorDeserialize, Default, Clone, Copy, Debug, PartialEq)]
+pub struct MintFeeSchedule {
+    // Define the mint the fee applies to.
+    pub mint_account: Pubkey,
+
+    // Define the fee taken on currency legs, in basis points.
+    pub fee_bps: u16,
+
+    // Define the fees collected into the vault that were not withdrawn yet.
+    pub accrued_amount: u64,
+}
+
+impl MintFeeSchedule {
+    // Define the serialized size
+    pub const SPACE: usize = 32 + 2 + 8;
+}
+
+// Here we define the platform fee config, the flat fees are paid in lamports to the treasury PDA.
+#[derive(AnchorSerialize, AnchorDeserialize, Default, Clone, Copy, Debug, PartialEq)]
+pub struct FeeConfig {
+    // Define the account that receives the withdrawn fees.
+    pub fee_recipient: Pubkey,
+
+    // Define the flat fee charged on proposal creation.
+    pub proposal_creation_fee: u64,
+
+    // Define the flat fee charged once a proposal is fulfilled.
+    pub fulfillment_fee: u64,
+}
+
+impl FeeConfig {
+    // Define the serialized size
+    pub const SPACE: usize = 32 + 8 + 8;
+}
+
+// Here we define where the escrowed tokens of a proposal are held.
+#[derive(AnchorSerialize, AnchorDeserialize, Default, Clone, Copy, Debug, PartialEq)]
+pub enum VaultMode {
+    // Tokens are held in the vault shared by every proposal, signed by the registry
+    #[default]
+    Shared,
+
+    // Tokens are held in vaults dedicated to the proposal, signed by the proposal
+    PerProposal,
+}
+
+// Here we define how the address of a proposal is derived.
+#[derive(AnchorSerialize, AnchorDeserialize, Default, Clone, Copy, Debug, PartialEq)]
+pub enum ProposalSeedVersion {
+    // Proposals created before the migration, derived from [PROPOSAL_SEED, id]
+    #[default]
+    Legacy,
+
+    // Derived from [PROPOSAL_SEED, owner, hash(id)] so that ids cannot be squatted by other users
+    OwnerScoped,
+}
+
+// Here we define who is allowed to create token vaults and whitelist mints.
+#[derive(AnchorSerialize, AnchorDeserialize, Default, Clone, Copy, Debug, PartialEq)]
+pub enum VaultCreationPolicy {
+    // Only the owner or the vault creator can create vaults
+    #[default]
+    OwnerOnly,
+
+    // Everyone can create vaults, but the mint stays disabled until approved
+    PermissionlessWithApproval,
+
+    // Everyone can create vaults and the mint is enabled right away
+    Permissionless,
+}
+
+// Here we define how the creator royalties of the NFTs traded against currency legs are handled.
+#[derive(AnchorSerialize, AnchorDeserialize, Default, Clone, Copy, Debug, PartialEq)]
+pub enum RoyaltyMode {
+    // Royalties are not paid
+    #[default]
+    Off,
+
+    // Royalties are paid to the creators whose accounts are provided at redeem
+    Optional,
+
+    // Royalties must be paid, redeem fails without the metadata and the creator accounts
+    Enforced,
+}
+
 #[account]
 #[derive(Default)]
 pub struct SwapPlatformRegistry {
@@ -33,10 +125,64 @@ pub struct SwapPlatformRegistry {
 
     // define whitelisted mint token account
     pub allowed_mint_accounts: Vec<MintInfo>,
+
+    // define the owner candidate that has to accept the ownership transfer.
+    pub pending_owner: Pubkey,
+
+    // define who can create token vaults.
+    pub vault_creation_policy: VaultCreationPolicy,
+
+    // define the delegated account that can create vaults on behalf of the owner.
+    pub vault_creator: Pubkey,
+
+    // define the platform fee config.
+    pub fee_config: FeeConfig,
+
+    // define the fee schedules of the whitelisted mints.
+    pub mint_fee_schedules: Vec<MintFeeSchedule>,
+
+    // define the vault mode applied to new proposals.
+    pub default_vault_mode: VaultMode,
+
+    // define the bounty in lamports paid out of the proposal rent to whoever settles it with the crank.
+    pub crank_bounty: u64,
+
+    // define how long in seconds a participant can hold a reservation on a proposal, 0 means no timeout.
+    pub reservation_window: u64,
+
+    // define how the creator royalties are handled on currency legs traded against NFTs.
+    pub royalty_mode: RoyaltyMode,
 }
 
 // Define handler
 impl SwapPlatformRegistry {
+    // Compute the account size for the given number of whitelisted mints and fee schedules
+    pub fn space(mint_count: usize, fee_schedule_count: usize) -> usize {
+        return 8 // discriminator
+            + 32 // owner
+            + 1 // was_initialized
+            + 1 // bump
+            + 1 // max_allowed_items
+            + 1 // max_allowed_options
+            + 4 + mint_count * MintInfo::SPACE // allowed_mint_accounts
+            + 32 // pending_owner
+            + 1 // vault_creation_policy
+            + 32 // vault_creator
+            + FeeConfig::SPACE // fee_config
+            + 4 + fee_schedule_count * MintFeeSchedule::SPACE // mint_fee_schedules
+            + 1 // default_vault_mode
+            + 8 // crank_bounty
+            + 8 // reservation_window
+            + 1; // royalty_mode
+    }
+
+    // Compute the account size once new entries are added, the account never shrinks
+    pub fn space_with(&self, additional_mints: usize, additional_fee_schedules: usize, current_space: usize) -> usize {
+        return SwapPlatformRegistry::space(
+            self.allowed_mint_accounts.len() + additional_mints,
+            self.mint_fee_schedules.len() + additional_fee_schedules,
+        ).max(current_space);
+    }
     // handle data integrity after initialization
     pub fn handle_post_initialized(&mut self) -> Result<()> {
         if self.was_initialized == false {
@@ -48,6 +194,24 @@ impl SwapPlatformRegistry {
         return Err(SwapError::AlreadyInitialized.into());
     }
 
+    // Check whether the signer holds the vault creator role.
+    pub fn is_vault_creator(&self, signer: Pubkey) -> bool {
+        return self.owner == signer.key().clone()
+            || (self.vault_creator != Pubkey::default() && self.vault_creator == signer.key().clone());
+    }
+
+    // Check whether the signer can create a vault under the current policy.
+    pub fn can_create_vault(&self, signer: Pubkey) -> bool {
+        return self.vault_creation_policy != VaultCreationPolicy::OwnerOnly
+            || self.is_vault_creator(signer);
+    }
+
+    // Check whether a vault created by the signer is enabled right away.
+    pub fn is_vault_enabled_on_creation(&self, signer: Pubkey) -> bool {
+        return self.vault_creation_policy == VaultCreationPolicy::Permissionless
+            || self.is_vault_creator(signer);
+    }
+
     // Check whether the mint account was previously added or not.
     pub fn is_mint_account_existed(&self, mint_account: Pubkey) -> bool {
         return self.allowed_mint_accounts.iter()
@@ -66,12 +230,186 @@ impl SwapPlatformRegistry {
             .count() >= 1;
     }
 
+    // Enable or disable a whitelisted mint account
+    pub fn set_mint_account_enabled(&mut self, mint_account: Pubkey, is_enabled: bool) -> Result<()> {
+        let mint_info = self.allowed_mint_accounts.iter_mut()
+            .find(|mint_info| mint_info.mint_account == mint_account.key().clone())
+            .ok_or(SwapError::MintAccountNotFound)?;
+
+        mint_info.is_enabled = is_enabled;
+        return Ok(());
+    }
+
+    // Remove a whitelisted mint account
+    pub fn remove_mint_account(&mut self, mint_account: Pubkey) -> Result<()> {
+        if !self.is_mint_account_existed(mint_account) {
+            return Err(SwapError::MintAccountNotFound.into());
+        }
+
+        self.allowed_mint_accounts
+            .retain(|mint_info| mint_info.mint_account != mint_account.key().clone());
+        self.mint_fee_schedules
+            .retain(|fee_schedule| fee_schedule.mint_account != mint_account.key().clone());
+        return Ok(());
+    }
+
+    // Create or update the fee schedule of a mint
+    pub fn set_mint_fee_schedule(&mut self, mint_account: Pubkey, fee_bps: u16) -> Result<()> {
+        if !self.is_mint_account_existed(mint_account) {
+            return Err(SwapError::MintAccountNotFound.into());
+        }
+
+        if fee_bps as u64 > FEE_BPS_DENOMINATOR {
+            return Err(SwapError::InvalidValue.into());
+        }
+
+        let fee_schedule = self.mint_fee_schedules.iter_mut()
+            .find(|fee_schedule| fee_schedule.mint_account == mint_account.key().clone());
+
+        match fee_schedule {
+            Some(fee_schedule) => fee_schedule.fee_bps = fee_bps,
+            None => self.mint_fee_schedules.push(
+                MintFeeSchedule {
+                    mint_account: mint_account.key().clone(),
+                    fee_bps,
+                    accrued_amount: 0,
+                }
+            ),
+        }
+
+        return Ok(());
+    }
+
+    // Compute the fee taken on a currency leg of the mint
+    pub fn compute_token_fee(&self, mint_account: Pubkey, amount: u64) -> Result<u64> {
+        let fee_schedule = self.mint_fee_schedules.iter()
+            .find(|fee_schedule| fee_schedule.mint_account == mint_account.key().clone());
+
+        let fee_bps = match fee_schedule {
+            Some(fee_schedule) => fee_schedule.fee_bps,
+            None => return Ok(0),
+        };
+
+        let fee = (amount as u128)
+            .checked_mul(fee_bps as u128)
+            .ok_or(SwapError::InvalidValue)?
+            .checked_div(FEE_BPS_DENOMINATOR as u128)
+            .ok_or(SwapError::InvalidValue)?;
+
+        return Ok(fee as u64);
+    }
+
+    // Record the fee left in the vault of the mint
+    pub fn accrue_token_fee(&mut self, mint_account: Pubkey, fee: u64) -> Result<()> {
+        let fee_schedule = self.mint_fee_schedules.iter_mut()
+            .find(|fee_schedule| fee_schedule.mint_account == mint_account.key().clone())
+            .ok_or(SwapError::MintAccountNotFound)?;
+
+        fee_schedule.accrued_amount = fee_schedule.accrued_amount
+            .checked_add(fee)
+            .ok_or(SwapError::InvalidValue)?;
+
+        return Ok(());
+    }
+
+    // Validate that the swap items only use whitelisted and enabled mints
+    pub fn validate_swap_items(&self, items: &[SwapItem]) -> Result<()> {
+        // collection asks are only allowed in the swap options
+        if items.iter().any(|item| item.item_type == SwapItemType::Collection) {
+            return Err(SwapError::InvalidValue.into());
+        }
+
+        return self.validate_items(items);
+    }
+
+    fn validate_items(&self, items: &[SwapItem]) -> Result<()> {
+        // Cannot exceed max allowed items
+        if self.max_allowed_items < items.len() as u8 {
+            return Err(SwapError::InvalidValue.into());
+        }
+
+        // Check if user submitted un-allowed mint tokens
+        for item in items.iter() {
+            // native SOL items are not backed by a mint
+            if item.item_type == SwapItemType::NativeSol {
+                if item.mint_account != Pubkey::default() || item.amount == 0 {
+                    return Err(SwapError::InvalidValue.into());
+                }
+
+                continue;
+            }
+
+            // collection asks are checked against the whitelist once the concrete mint is known
+            if item.item_type == SwapItemType::Collection {
+                if item.mint_account == Pubkey::default() || item.amount == 0 {
+                    return Err(SwapError::InvalidValue.into());
+                }
+
+                continue;
+            }
+
+            if !self.is_mint_account_existed(item.mint_account) {
+                return Err(SwapError::UnAllowedMintToken.into());
+            }
+
+            if !self.is_mint_account_enabled(item.mint_account) {
+                return Err(SwapError::MintAccountDisabled.into());
+            }
+        }
+
+        return Ok(());
+    }
+
+    // Validate the swap options against the registry limits and whitelisted mints
+    pub fn validate_swap_options(&self, options: &[SwapOption]) -> Result<()> {
+        // validate input
+        if self.max_allowed_options < options.len() as u8 {
+            return Err(SwapError::InvalidValue.into());
+        }
+
+        // validate if the tokens were allowed
+        for option in options.iter() {
+            self.validate_items(&option.asking_items)?;
+        }
+
+        // ok
+        return Ok(());
+    }
+
+    // Take the platform fee on a redeemed currency leg, the fee stays in the vault
+    pub fn take_token_fee(&mut self, item: &SwapItem) -> Result<u64> {
+        if item.item_type != SwapItemType::Currency {
+            return Ok(0);
+        }
+
+        let fee = self.compute_token_fee(item.mint_account, item.amount)?;
+        if fee > 0 {
+            self.accrue_token_fee(item.mint_account, fee)?;
+        }
+
+        return Ok(fee);
+    }
+
+    // Release the accrued fee of the mint so that it can be withdrawn
+    pub fn release_token_fee(&mut self, mint_account: Pubkey, amount: u64) -> Result<()> {
+        let fee_schedule = self.mint_fee_schedules.iter_mut()
+            .find(|fee_schedule| fee_schedule.mint_account == mint_account.key().clone())
+            .ok_or(SwapError::MintAccountNotFound)?;
+
+        fee_schedule.accrued_amount = fee_schedule.accrued_amount
+            .checked_sub(amount)
+            .ok_or(SwapError::InsufficientAccruedFees)?;
+
+        return Ok(());
+    }
+
     // Get mint info
-    pub fn get_mint_info(&self, mint_account: Pubkey) -> &MintInfo {
-        return self.allowed_mint_accounts.iter()
+    pub fn get_mint_info(&self, mint_account: Pubkey) -> Result<&MintInfo> {
+        let mint_info = self.allowed_mint_accounts.iter()
             .find(|&mint_account_key| mint_account_key.mint_account == mint_account.key().clone())
-            .unwrap()
-            .borrow();
+            .ok_or(SwapError::MintAccountNotFound)?;
+
+        return Ok(mint_info.borrow());
     }
 }
 
@@ -85,6 +423,12 @@ pub enum SwapItemType {
 
     // Define the offchain swap item
     Currency,
+
+    // Define the native SOL swap item, escrowed as lamports by the native vault of the proposal
+    NativeSol,
+
+    // Define the asking item matched by any NFT of a verified Metaplex collection
+    Collection,
 }
 
 // Here we define the swap option type
@@ -127,6 +471,16 @@ pub struct SwapItem {
 
 // Implement the swap item functions
 impl SwapItem {
+    // Compute the serialized size of a swap item with the given id
+    pub fn space_for(id: &str) -> usize {
+        return 4 + id.len() // id
+            + 32 // mint_account
+            + 8 // amount
+            + 1 // status
+            + 1 // item_type
+            + 32; // owner
+    }
+
     // Define default value
     fn default() -> SwapItem {
         SwapItem {
@@ -139,6 +493,23 @@ impl SwapItem {
         }
     }
 
+    // Check whether the item is a collection ask still waiting for a concrete mint
+    pub fn is_unresolved_collection_item(&self) -> bool {
+        return self.item_type == SwapItemType::Collection && self.status == SwapItemStatus::Created;
+    }
+
+    // Resolve the collection ask to the concrete mint, the mint must be a verified member of the collection.
+    // The mint account of an unresolved collection ask holds the collection mint.
+    pub fn resolve_collection_item(&mut self, metadata: &metadata::Metadata) -> Result<()> {
+        if !metadata.is_verified_collection_member(self.mint_account) {
+            return Err(SwapError::CollectionMismatch.into());
+        }
+
+        self.mint_account = metadata.mint;
+
+        return Ok(());
+    }
+
     // Define deposit function
     pub fn handle_post_deposited(&mut self) {
         self.status = SwapItemStatus::Deposited;
@@ -170,6 +541,9 @@ pub enum SwapProposalStatus {
 
     // Declare that the proposal is fully withdrawn by both participant and proposal owner
     Withdrawn,
+
+    // Declare that the proposal expired before any option was fulfilled
+    Expired,
 }
 
 #[derive(AnchorSerialize, AnchorDeserialize, Default, Clone, Debug, PartialEq)]
@@ -181,6 +555,43 @@ pub struct SwapOption {
     pub asking_items: Vec<SwapItem>
 }
 
+impl SwapOption {
+    // Compute the serialized size of a swap option with the given id and asking items size
+    pub fn space_for(id: &str, asking_items_space: usize) -> usize {
+        return 4 + id.len() // id
+            + 4 + asking_items_space; // asking_items
+    }
+
+    // Compute the serialized size of the swap option
+    pub fn space(&self) -> usize {
+        return SwapOption::space_for(
+            &self.id,
+            self.asking_items.iter().map(|item| SwapItem::space_for(&item.id)).sum(),
+        );
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Default, Clone, Debug, PartialEq)]
+pub struct CollectionAsk {
+    // Define the swap option holding the collection ask
+    pub option_id: String,
+
+    // Define the asking item id
+    pub item_id: String,
+
+    // Define the collection mint the asking item must belong to
+    pub collection_mint: Pubkey,
+}
+
+impl CollectionAsk {
+    // Compute the serialized size of a collection ask with the given ids
+    pub fn space_for(option_id: &str, item_id: &str) -> usize {
+        return 4 + option_id.len() // option_id
+            + 4 + item_id.len() // item_id
+            + 32; // collection_mint
+    }
+}
+
 // Here we define the account state that holds the swap order. SwapOrder will be the PDA.
 #[account]
 #[derive(Default)]
@@ -211,10 +622,86 @@ pub struct SwapProposal {
 
     // Define the proposal status
     pub status: SwapProposalStatus,
+
+    // Define where the escrowed tokens are held
+    pub vault_mode: VaultMode,
+
+    // Define how the proposal address is derived
+    pub seed_version: ProposalSeedVersion,
+
+    // Define when the participant reserved the proposal
+    pub reserved_at: u64,
+
+    // Define the items of released reservations waiting to be withdrawn by their owners
+    pub pending_refunds: Vec<SwapItem>,
+
+    // Define the only participants allowed to fulfill the proposal, open to anyone if empty
+    pub allowed_participants: Vec<Pubkey>,
+
+    // Define the number of counter offers still holding escrowed items
+    pub open_counter_offers: u8,
+
+    // Define the collection mints of the collection asks, kept once the asking items are resolved to a concrete mint
+    pub collection_asks: Vec<CollectionAsk>,
 }
 
 // Implement some domain logic
 impl SwapProposal {
+    // Define the serialized size of the fixed length fields
+    pub const FIXED_SPACE: usize = 8 // discriminator
+        + 4 // id
+        + 1 // bump
+        + 32 // owner
+        + 32 // fulfilled_by
+        + 4 // fulfilled_with_option_id, sized by the longest option id
+        + 4 // offered_items
+        + 4 // swap_options
+        + 8 // expired_at
+        + 1 // status
+        + 1 // vault_mode
+        + 1 // seed_version
+        + 8 // reserved_at
+        + 4 // pending_refunds
+        + 4 // allowed_participants
+        + 1 // open_counter_offers
+        + 4; // collection_asks
+
+    // Compute the account size from the variable length fields
+    pub fn space_for(id: &str, max_option_id_len: usize, offered_items_space: usize, swap_options_space: usize) -> usize {
+        return SwapProposal::FIXED_SPACE
+            + id.len()
+            + max_option_id_len
+            + offered_items_space
+            + swap_options_space;
+    }
+
+    // Compute the account size of the proposal
+    pub fn space(&self) -> usize {
+        return SwapProposal::space_for(
+            &self.id,
+            self.swap_options.iter().map(|option| option.id.len()).max().unwrap_or(0),
+            self.offered_items.iter().map(|item| SwapItem::space_for(&item.id)).sum(),
+            self.swap_options.iter().map(|option| option.space()).sum(),
+        ) + self.pending_refunds.iter().map(|item| SwapItem::space_for(&item.id)).sum::<usize>()
+            + self.allowed_participants.len() * 32
+            + self.collection_asks.iter().map(|ask| CollectionAsk::space_for(&ask.option_id, &ask.item_id)).sum::<usize>();
+    }
+
+    // Record the collection mints of the collection asks of the swap options
+    pub fn record_collection_asks(&mut self) {
+        self.collection_asks = self.swap_options.iter()
+            .flat_map(|option| {
+                option.asking_items.iter()
+                    .filter(|item| item.item_type == SwapItemType::Collection)
+                    .map(move |item| CollectionAsk {
+                        option_id: option.id.clone(),
+                        item_id: item.id.clone(),
+                        collection_mint: item.mint_account,
+                    })
+            })
+            .collect();
+    }
+
     // Define default value
     pub fn default() -> SwapProposal {
         SwapProposal {
@@ -227,12 +714,75 @@ impl SwapProposal {
             offered_items: vec![],
             swap_options: vec![],
             expired_at: 0,
+            vault_mode: VaultMode::Shared,
+            seed_version: ProposalSeedVersion::Legacy,
+            reserved_at: 0,
+            pending_refunds: vec![],
+            allowed_participants: vec![],
+            open_counter_offers: 0,
+            collection_asks: vec![],
+        }
+    }
+
+    // Hash the proposal id so that ids of any length can be used as a seed
+    pub fn hash_id(id: &str) -> [u8; 32] {
+        return hash(id.as_bytes()).to_bytes();
+    }
+
+    // Build the seeds used to sign on behalf of the proposal
+    pub fn signer_seeds<'a>(&'a self, id_hash: &'a [u8], bump: &'a [u8]) -> Vec<&'a [u8]> {
+        return match self.seed_version {
+            ProposalSeedVersion::Legacy => vec![PROPOSAL_SEED, self.id.as_bytes(), bump],
+            ProposalSeedVersion::OwnerScoped => vec![PROPOSAL_SEED, self.owner.as_ref(), id_hash, bump],
+        };
+    }
+
+    // Check whether the account is the proposal derived from the given id, legacy proposals are still accepted
+    pub fn is_proposal_address(&self, proposal_key: Pubkey, id: &str) -> bool {
+        if self.id != id {
+            return false;
         }
+
+        let id_hash = SwapProposal::hash_id(&self.id);
+        let bump = [self.bump];
+
+        return Pubkey::create_program_address(&self.signer_seeds(&id_hash, &bump), &crate::ID)
+            .map(|address| address == proposal_key)
+            .unwrap_or(false);
+    }
+
+    // Check whether the vault holds the escrowed tokens of the mint for this proposal.
+    pub fn is_vault_of(&self, proposal_key: Pubkey, mint_account: Pubkey, bump: u8, vault: Pubkey) -> bool {
+        let vault_address = match self.vault_mode {
+            VaultMode::Shared => Pubkey::create_program_address(
+                &[TOKEN_ACCOUNT_SEED, mint_account.as_ref(), &[bump]],
+                &crate::ID,
+            ),
+            VaultMode::PerProposal => Pubkey::create_program_address(
+                &[PROPOSAL_VAULT_SEED, proposal_key.as_ref(), mint_account.as_ref(), &[bump]],
+                &crate::ID,
+            ),
+        };
+
+        return vault_address.map(|address| address == vault).unwrap_or(false);
+    }
+
+    // Check whether the account is the native vault holding the escrowed lamports of this proposal.
+    pub fn is_native_vault_of(&self, proposal_key: Pubkey, bump: u8, vault: Pubkey) -> bool {
+        return Pubkey::create_program_address(
+            &[NATIVE_VAULT_SEED, proposal_key.as_ref(), &[bump]],
+            &crate::ID,
+        )
+            .map(|address| address == vault)
+            .unwrap_or(false);
     }
 
     // Define whether the proposal is still in time window
     pub fn is_proposal_still_in_time_window(&self) -> bool {
-        return self.expired_at > Clock::get().unwrap().unix_timestamp as u64
+        // the proposal is considered out of the time window if the clock is unavailable
+        return Clock::get()
+            .map(|clock| self.expired_at > clock.unix_timestamp as u64)
+            .unwrap_or(false);
     }
 
     // Check whether the proposal owner is the signer.
@@ -259,12 +809,29 @@ impl SwapProposal {
             && (self.owner.key() == signer.key() || self.fulfilled_by.key() == signer.key());
     }
 
+    // Define whether the proposal can still be updated by its owner, no taker reserved an option yet.
+    pub fn is_proposal_updatable(&self) -> bool {
+        return self.is_proposal_still_in_time_window()
+            && (
+            self.status == SwapProposalStatus::Created
+                || self.status == SwapProposalStatus::Deposited
+        )
+            && self.fulfilled_by == Pubkey::default()
+            && self.fulfilled_with_option_id == String::default();
+    }
+
     // Define whether the state is open for depositing.
     pub fn is_proposal_open_for_depositing(&self) -> bool {
         return self.is_proposal_still_in_time_window()
             && self.status == SwapProposalStatus::Created;
     }
 
+    // Check whether the participant is allowed to fulfill a private proposal.
+    pub fn is_allowed_participant(&self, participant: Pubkey) -> bool {
+        return self.allowed_participants.is_empty()
+            || self.allowed_participants.contains(&participant);
+    }
+
     // Define whether the state is open for depositing.
     pub fn is_proposal_open_for_fulfilling(&self, option_id: String, participant: Pubkey) -> bool {
         return self.is_proposal_still_in_time_window()
@@ -285,10 +852,138 @@ impl SwapProposal {
                 && self.status == SwapProposalStatus::Fulfilled; // need to be updated once depositing is completed.
     }
 
-    // Define the state that the proposal is withdrawable (the swap is canceled).
+    // Define the state that the proposal is withdrawable (the swap is canceled or expired).
     pub fn is_proposal_withdrawable(&self) -> bool {
         return !self.is_proposal_open_for_participants()
-            && self.status == SwapProposalStatus::Canceled; // need to be updated once the proposal owner cancel the proposal.
+            && (
+            self.status == SwapProposalStatus::Canceled // need to be updated once the proposal owner cancel the proposal.
+                || self.status == SwapProposalStatus::Expired
+                || self.is_proposal_expired()
+        );
+    }
+
+    // Define the state that the proposal passed its expiry without any option being fulfilled.
+    pub fn is_proposal_expired(&self) -> bool {
+        return !self.is_proposal_still_in_time_window()
+            && (
+            self.status == SwapProposalStatus::Created
+                || self.status == SwapProposalStatus::Deposited
+        );
+    }
+
+    // Define the state that the proposal is settled and its account can be closed.
+    // Canceled and expired proposals are settled once no item is left in escrow, the items never deposited stay untouched.
+    pub fn is_proposal_closable(&self) -> bool {
+        return (
+            self.status == SwapProposalStatus::Redeemed
+                || self.status == SwapProposalStatus::Withdrawn
+                || (
+                (self.status == SwapProposalStatus::Canceled || self.status == SwapProposalStatus::Expired)
+                    && !self.has_deposited_items()
+            )
+        )
+            && self.open_counter_offers == 0
+            && !self.has_pending_refunds();
+    }
+
+    // Check whether any offered or asking item is still held in escrow.
+    pub fn has_deposited_items(&self) -> bool {
+        return self.offered_items
+            .iter()
+            .chain(self.swap_options.iter().flat_map(|option| option.asking_items.iter()))
+            .any(|item| item.status == SwapItemStatus::Deposited);
+    }
+
+    // Check whether the signer has items of a released reservation to withdraw.
+    pub fn has_pending_refunds_of(&self, signer: Pubkey) -> bool {
+        return self.pending_refunds
+            .iter()
+            .any(|item| item.status == SwapItemStatus::Deposited && item.owner == signer);
+    }
+
+    // Check whether any item of a released reservation is still held in escrow.
+    pub fn has_pending_refunds(&self) -> bool {
+        return self.pending_refunds
+            .iter()
+            .any(|item| item.status == SwapItemStatus::Deposited);
+    }
+
+    // Find the item of a released reservation the signer can withdraw.
+    pub fn find_pending_refund_of(&self, item_id: &str, signer: Pubkey) -> Option<usize> {
+        return self.pending_refunds
+            .iter()
+            .position(|item| item.id == item_id && item.status == SwapItemStatus::Deposited && item.owner == signer);
+    }
+
+    // Define whether the reservation of the participant passed the reservation window.
+    pub fn is_reservation_stale(&self, reservation_window: u64) -> bool {
+        // the reservation never times out if the window is not configured
+        if reservation_window == 0 {
+            return false;
+        }
+
+        return self.status == SwapProposalStatus::Deposited
+            && self.fulfilled_by != Pubkey::default()
+            && Clock::get()
+                .map(|clock| clock.unix_timestamp as u64 >= self.reserved_at.saturating_add(reservation_window))
+                .unwrap_or(false);
+    }
+
+    // Release the reservation, the deposited asking items are moved to the pending refunds of their owner
+    pub fn release_reservation(&mut self) -> Result<()> {
+        let option_id = self.fulfilled_with_option_id.clone();
+
+        let desired_option = self.swap_options
+            .iter_mut()
+            .find(|option| option.id == option_id)
+            .ok_or(SwapError::SwapOptionNotFound)?;
+
+        for item in desired_option.asking_items.iter_mut() {
+            if item.status == SwapItemStatus::Deposited {
+                self.pending_refunds.push(item.clone());
+            }
+
+            // reopen the item for other participants
+            item.status = SwapItemStatus::Created;
+            item.owner = Pubkey::default();
+
+            // collection asks can be fulfilled with another mint of the collection
+            if item.item_type == SwapItemType::Collection {
+                item.mint_account = self.collection_asks
+                    .iter()
+                    .find(|ask| ask.option_id == option_id && ask.item_id == item.id)
+                    .map(|ask| ask.collection_mint)
+                    .ok_or(SwapError::SwapItemNotFound)?;
+            }
+        }
+
+        self.fulfilled_by = Pubkey::default();
+        self.fulfilled_with_option_id = String::default();
+        self.reserved_at = 0;
+
+        return Ok(());
+    }
+
+    // Find the NFTs traded against the item, on the other leg of the fulfilled swap
+    pub fn find_counter_leg_nft_items(&self, item: &SwapItem) -> Vec<SwapItem> {
+        let is_offered_item = self.offered_items.iter().any(|offered_item| offered_item.id == item.id);
+
+        let counter_leg_items = if is_offered_item {
+            self.swap_options
+                .iter()
+                .find(|option| option.id == self.fulfilled_with_option_id)
+                .map(|option| option.asking_items.clone())
+                .unwrap_or_default()
+        } else {
+            self.offered_items.clone()
+        };
+
+        return counter_leg_items
+            .into_iter()
+            .filter(|counter_leg_item| {
+                counter_leg_item.item_type == SwapItemType::NFT || counter_leg_item.item_type == SwapItemType::Collection
+            })
+            .collect();
     }
 
     // Update redeem status
@@ -300,7 +995,7 @@ impl SwapProposal {
             .clone()
             .into_iter()
             .find(|option| option.id == fulfilled_option_id)
-            .unwrap();
+            .ok_or(SwapError::SwapOptionNotFound)?;
 
         // all offered items must be redeemed
         let all_offered_items_redeemed = offered_items
@@ -334,7 +1029,7 @@ impl SwapProposal {
                 .clone()
                 .into_iter()
                 .find(|option| option.id == fulfilled_option_id)
-                .unwrap();
+                .ok_or(SwapError::SwapOptionNotFound)?;
 
             // all asking items must be redeemed
             all_asking_items_withdrawn = fulfilled_option.asking_items.clone()
@@ -358,6 +1053,58 @@ impl SwapProposal {
     }
 }
 
+// ================ Counter Offer Interface ================ //
+// Here we define the account state that holds a counter offer of a taker. CounterOffer will be the PDA.
+#[account]
+#[derive(Default)]
+pub struct CounterOffer {
+    // Bump to help define the PDA of the counter offer.
+    pub bump: u8,
+
+    // Define the proposal the counter offer was made for
+    pub proposal: Pubkey,
+
+    // Define the taker that made the counter offer
+    pub taker: Pubkey,
+
+    // The option proposed by the taker, its asking items are escrowed up front
+    pub swap_option: SwapOption,
+
+    // Expiry date
+    pub expired_at: u64,
+
+    // The fulfillment fee held until the counter offer is accepted
+    pub fulfillment_fee: u64,
+}
+
+impl CounterOffer {
+    // Define the serialized size of the fixed length fields
+    pub const FIXED_SPACE: usize = 8 // discriminator
+        + 1 // bump
+        + 32 // proposal
+        + 32 // taker
+        + 8 // expired_at
+        + 8; // fulfillment_fee
+
+    // Compute the account size from the size of the proposed option
+    pub fn space_for(swap_option_space: usize) -> usize {
+        return CounterOffer::FIXED_SPACE + swap_option_space;
+    }
+
+    // Define whether the counter offer is still in time window
+    pub fn is_counter_offer_still_in_time_window(&self) -> bool {
+        // the counter offer is considered out of the time window if the clock is unavailable
+        return Clock::get()
+            .map(|clock| self.expired_at > clock.unix_timestamp as u64)
+            .unwrap_or(false);
+    }
+
+    // Check whether the taker is the signer.
+    pub fn is_taker(&self, signer: Pubkey) -> bool {
+        return self.taker == signer;
+    }
+}
+
 // Here we define the account state that holds the lookup table for every users.
 #[account]
 #[derive(Default)]
@@ -370,4 +1117,231 @@ pub struct LookupTableRegistry {
 
     // Define the lookup table for specific users
     pub lookup_table_addresses: Vec<Pubkey>
-}
\ No newline at end of file
+}
+
+impl LookupTableRegistry {
+    // Compute the account size for the given number of lookup tables
+    pub fn space(lookup_table_count: usize) -> usize {
+        return 8 // discriminator
+            + 1 // bump
+            + 32 // owner
+            + 4 + lookup_table_count * 32; // lookup_table_addresses
+    }
+
+    // Compute the account size once new lookup tables are added, the account never shrinks
+    pub fn space_with(&self, additional_lookup_tables: usize, current_space: usize) -> usize {
+        return LookupTableRegistry::space(
+            self.lookup_table_addresses.len() + additional_lookup_tables
+        ).max(current_space);
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anchor_lang::Discriminator;
+
+    // The swap item layout of proposals created before the collection asks
+    #[derive(AnchorSerialize)]
+    struct LegacySwapItem {
+        id: String,
+        mint_account: Pubkey,
+        amount: u64,
+        status: SwapItemStatus,
+        item_type: SwapItemType,
+        owner: Pubkey,
+    }
+
+    #[derive(AnchorSerialize)]
+    struct LegacySwapOption {
+        id: String,
+        asking_items: Vec<LegacySwapItem>,
+    }
+
+    // The proposal layout of the first program version, the accounts were allocated with a fixed size
+    #[derive(AnchorSerialize)]
+    struct LegacySwapProposal {
+        id: String,
+        bump: u8,
+        owner: Pubkey,
+        fulfilled_by: Pubkey,
+        fulfilled_with_option_id: String,
+        offered_items: Vec<LegacySwapItem>,
+        swap_options: Vec<LegacySwapOption>,
+        expired_at: u64,
+        status: SwapProposalStatus,
+    }
+
+    const LEGACY_PROPOSAL_SPACE: usize = 10240;
+
+    fn legacy_item(id: &str, mint_account: Pubkey, owner: Pubkey) -> LegacySwapItem {
+        return LegacySwapItem {
+            id: id.to_string(),
+            mint_account,
+            amount: 1,
+            status: SwapItemStatus::Created,
+            item_type: SwapItemType::NFT,
+            owner,
+        };
+    }
+
+    #[test]
+    fn loads_proposal_with_legacy_layout() {
+        let owner = Pubkey::new_unique();
+        let offered_mint = Pubkey::new_unique();
+        let asking_mint = Pubkey::new_unique();
+
+        let legacy_proposal = LegacySwapProposal {
+            id: "proposal".to_string(),
+            bump: 255,
+            owner,
+            fulfilled_by: Pubkey::default(),
+            fulfilled_with_option_id: String::default(),
+            offered_items: vec![legacy_item("offered", offered_mint, owner)],
+            swap_options: vec![LegacySwapOption {
+                id: "option".to_string(),
+                asking_items: vec![legacy_item("asking", asking_mint, Pubkey::default())],
+            }],
+            expired_at: 1_000,
+            status: SwapProposalStatus::Deposited,
+        };
+
+        let mut data = SwapProposal::DISCRIMINATOR.to_vec();
+        data.extend(legacy_proposal.try_to_vec().unwrap());
+        data.resize(LEGACY_PROPOSAL_SPACE, 0);
+
+        let proposal = SwapProposal::try_deserialize(&mut data.as_slice()).unwrap();
+        assert_eq!(proposal.id, "proposal");
+        assert_eq!(proposal.owner, owner);
+        assert_eq!(proposal.status, SwapProposalStatus::Deposited);
+        assert_eq!(proposal.offered_items.len(), 1);
+        assert_eq!(proposal.offered_items[0].mint_account, offered_mint);
+        assert_eq!(proposal.offered_items[0].owner, owner);
+        assert_eq!(proposal.swap_options[0].asking_items[0].mint_account, asking_mint);
+        assert_eq!(proposal.expired_at, 1_000);
+
+        // the fields appended since are read from the zeroed padding
+        assert_eq!(proposal.vault_mode, VaultMode::Shared);
+        assert_eq!(proposal.seed_version, ProposalSeedVersion::Legacy);
+        assert!(proposal.pending_refunds.is_empty());
+        assert!(proposal.allowed_participants.is_empty());
+        assert_eq!(proposal.open_counter_offers, 0);
+        assert!(proposal.collection_asks.is_empty());
+    }
+
+    #[test]
+    fn sizes_proposal_with_collection_asks() {
+        let collection_mint = Pubkey::new_unique();
+
+        let mut proposal = SwapProposal::default();
+        proposal.id = "proposal".to_string();
+        proposal.fulfilled_with_option_id = "option".to_string();
+        proposal.swap_options = vec![SwapOption {
+            id: "option".to_string(),
+            asking_items: vec![SwapItem {
+                id: "asking".to_string(),
+                mint_account: collection_mint,
+                amount: 1,
+                item_type: SwapItemType::Collection,
+                ..Default::default()
+            }],
+        }];
+        proposal.record_collection_asks();
+
+        assert_eq!(proposal.collection_asks.len(), 1);
+        assert_eq!(proposal.collection_asks[0].collection_mint, collection_mint);
+
+        let mut data = vec![];
+        proposal.try_serialize(&mut data).unwrap();
+        assert_eq!(data.len(), proposal.space());
+    }
+
+    fn serialized_len<T: AccountSerialize>(account: &T) -> usize {
+        let mut data = vec![];
+        account.try_serialize(&mut data).unwrap();
+        return data.len();
+    }
+
+    fn item(id: &str, item_type: SwapItemType) -> SwapItem {
+        return SwapItem {
+            id: id.to_string(),
+            mint_account: Pubkey::new_unique(),
+            amount: 1,
+            item_type,
+            owner: Pubkey::new_unique(),
+            ..Default::default()
+        };
+    }
+
+    #[test]
+    fn sizes_proposal_with_multiple_options() {
+        let mut proposal = SwapProposal::default();
+        proposal.id = "proposal-with-options".to_string();
+        proposal.offered_items = vec![item("offered-1", SwapItemType::NFT), item("offered-item-2", SwapItemType::Currency)];
+        proposal.swap_options = vec![
+            SwapOption {
+                id: "a".to_string(),
+                asking_items: vec![item("asking", SwapItemType::Currency)],
+            },
+            SwapOption {
+                id: "the-longest-option".to_string(),
+                asking_items: vec![
+                    item("asking-nft", SwapItemType::NFT),
+                    item("collection", SwapItemType::Collection),
+                ],
+            },
+            SwapOption {
+                id: "option".to_string(),
+                asking_items: vec![],
+            },
+        ];
+        proposal.pending_refunds = vec![item("refund", SwapItemType::NFT)];
+        proposal.allowed_participants = vec![Pubkey::new_unique(), Pubkey::new_unique()];
+        proposal.record_collection_asks();
+
+        // the space reserves the longest option id for the fulfilled option
+        let longest_option_id_len = "the-longest-option".len();
+        assert_eq!(serialized_len(&proposal) + longest_option_id_len, proposal.space());
+
+        for option_id in ["a", "option"] {
+            proposal.fulfilled_with_option_id = option_id.to_string();
+            assert!(serialized_len(&proposal) < proposal.space());
+        }
+
+        proposal.fulfilled_with_option_id = "the-longest-option".to_string();
+        assert_eq!(serialized_len(&proposal), proposal.space());
+    }
+
+    #[test]
+    fn sizes_registry_with_mints_and_fee_schedules() {
+        for (mint_count, fee_schedule_count) in [(0, 0), (1, 0), (3, 2)] {
+            let mut registry = SwapPlatformRegistry::default();
+            registry.allowed_mint_accounts = vec![MintInfo::default(); mint_count];
+            registry.mint_fee_schedules = vec![MintFeeSchedule::default(); fee_schedule_count];
+
+            assert_eq!(serialized_len(&registry), SwapPlatformRegistry::space(mint_count, fee_schedule_count));
+        }
+    }
+
+    #[test]
+    fn keeps_proposal_open_until_pending_refunds_are_withdrawn() {
+        let participant = Pubkey::new_unique();
+
+        let mut proposal = SwapProposal::default();
+        proposal.status = SwapProposalStatus::Canceled;
+        proposal.pending_refunds = vec![SwapItem {
+            id: "asking".to_string(),
+            amount: 1,
+            status: SwapItemStatus::Deposited,
+            owner: participant,
+            ..Default::default()
+        }];
+
+        assert!(!proposal.is_proposal_closable());
+        assert_eq!(proposal.find_pending_refund_of("asking", participant), Some(0));
+        assert_eq!(proposal.find_pending_refund_of("asking", Pubkey::new_unique()), None);
+
+        proposal.pending_refunds[0].status = SwapItemStatus::Withdrawn;
+        assert!(proposal.is_proposal_closable());
+        assert_eq!(proposal.find_pending_refund_of("asking", participant), None);
+    }
+}
@@ -0,0 +1,297 @@
+//! Token interface shim, anchor-spl 0.26 ships no token_interface.
+//! Token accounts and mints are read and moved the same way for the token program and the Token-2022 program.
+use crate::*;
+use anchor_lang::solana_program::instruction::{AccountMeta, Instruction};
+use anchor_lang::solana_program::program::invoke_signed;
+use spl_token_2022::extension::transfer_fee::TransferFeeAmount;
+use spl_token_2022::extension::{BaseStateWithExtensions, ExtensionType, StateWithExtensions};
+
+// Check whether the program is one of the supported token programs
+pub fn is_token_program(program_id: &Pubkey) -> bool {
+    return *program_id == spl_token::ID || *program_id == spl_token_2022::ID;
+}
+
+// Define the token account fields used by the program, read from either token program
+#[derive(Clone)]
+pub struct InterfaceTokenAccount<'info> {
+    pub info: AccountInfo<'info>,
+    pub mint: Pubkey,
+    pub owner: Pubkey,
+    pub amount: u64,
+    // The transfer fees withheld in the account, a Token-2022 account cannot be closed while holding any
+    pub withheld_amount: u64,
+}
+
+impl<'info> InterfaceTokenAccount<'info> {
+    // Load the token account, it must be owned by a supported token program
+    pub fn load(info: &AccountInfo<'info>) -> Result<InterfaceTokenAccount<'info>> {
+        if !is_token_program(info.owner) {
+            return Err(SwapError::InvalidTokenAccount.into());
+        }
+
+        let data = info.try_borrow_data()?;
+        let state = StateWithExtensions::<spl_token_2022::state::Account>::unpack(&data)?;
+        let withheld_amount = state
+            .get_extension::<TransferFeeAmount>()
+            .map(|extension| u64::from(extension.withheld_amount))
+            .unwrap_or(0);
+
+        return Ok(InterfaceTokenAccount {
+            info: info.clone(),
+            mint: state.base.mint,
+            owner: state.base.owner,
+            amount: state.base.amount,
+            withheld_amount,
+        });
+    }
+
+    // Reload the token account after a CPI
+    pub fn reload(&mut self) -> Result<()> {
+        *self = InterfaceTokenAccount::load(&self.info)?;
+
+        return Ok(());
+    }
+
+    pub fn key(&self) -> Pubkey {
+        return self.info.key();
+    }
+
+    pub fn to_account_info(&self) -> AccountInfo<'info> {
+        return self.info.clone();
+    }
+}
+
+// Read the decimals of a mint of either token program
+pub fn mint_decimals(mint_account: &AccountInfo) -> Result<u8> {
+    if !is_token_program(mint_account.owner) {
+        return Err(SwapError::InvalidTokenProgram.into());
+    }
+
+    let data = mint_account.try_borrow_data()?;
+    let state = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&data)?;
+
+    return Ok(state.base.decimals);
+}
+
+// Find the token program owning the mint.
+// Either token program can be passed as the token program of an instruction,
+// the other one is taken from the extra accounts when items of both token programs are mixed.
+pub fn token_program_of<'info>(
+    mint_account: &AccountInfo<'info>,
+    token_program: &AccountInfo<'info>,
+    extra_accounts: &[AccountInfo<'info>],
+) -> Result<AccountInfo<'info>> {
+    if *mint_account.owner == token_program.key() {
+        return Ok(token_program.clone());
+    }
+
+    if !is_token_program(mint_account.owner) {
+        return Err(SwapError::InvalidTokenProgram.into());
+    }
+
+    return extra_accounts
+        .iter()
+        .find(|account| account.key() == *mint_account.owner)
+        .cloned()
+        .ok_or(SwapError::InvalidTokenProgram.into());
+}
+
+// Define the accounts of a transfer_checked, following the TransferChecked accounts of the token interface
+#[derive(Clone)]
+pub struct TransferChecked<'info> {
+    pub token_program: AccountInfo<'info>,
+    pub from: AccountInfo<'info>,
+    pub mint: AccountInfo<'info>,
+    pub to: AccountInfo<'info>,
+    pub authority: AccountInfo<'info>,
+}
+
+// Transfer tokens with transfer_checked.
+// The extra accounts are forwarded to Token-2022 transfers so that transfer hooks can resolve their accounts.
+pub fn transfer_checked<'info>(
+    accounts: TransferChecked<'info>,
+    extra_accounts: &[AccountInfo<'info>],
+    amount: u64,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    let TransferChecked { token_program, from, mint, to, authority } = accounts;
+
+    let decimals = mint_decimals(&mint)?;
+    let mut instruction = spl_token_2022::instruction::transfer_checked(
+        token_program.key,
+        from.key,
+        mint.key,
+        to.key,
+        authority.key,
+        &[],
+        amount,
+        decimals,
+    )?;
+
+    let mut account_infos = vec![from, mint, to, authority, token_program.clone()];
+    if token_program.key() == spl_token_2022::ID {
+        for account in extra_accounts.iter().filter(|account| !is_token_program(account.key)) {
+            instruction.accounts.push(AccountMeta {
+                pubkey: account.key(),
+                is_signer: account.is_signer,
+                is_writable: account.is_writable,
+            });
+            account_infos.push(account.clone());
+        }
+    }
+
+    invoke_signed(&instruction, &account_infos, signer_seeds)?;
+
+    return Ok(());
+}
+
+// Close a token account of either token program
+pub fn close_account<'info>(
+    token_program: AccountInfo<'info>,
+    account: AccountInfo<'info>,
+    destination: AccountInfo<'info>,
+    authority: AccountInfo<'info>,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    let instruction = spl_token_2022::instruction::close_account(
+        token_program.key,
+        account.key,
+        destination.key,
+        authority.key,
+        &[],
+    )?;
+
+    invoke_signed(
+        &instruction,
+        &[account, destination, authority, token_program],
+        signer_seeds,
+    )?;
+
+    return Ok(());
+}
+
+// Create a token account at the program address and initialize it.
+// The account is sized for the extensions required by the extensions of the mint.
+pub fn create_token_account<'info>(
+    payer: AccountInfo<'info>,
+    account: AccountInfo<'info>,
+    mint_account: AccountInfo<'info>,
+    authority: Pubkey,
+    token_program: AccountInfo<'info>,
+    system_program: AccountInfo<'info>,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    let space = {
+        let data = mint_account.try_borrow_data()?;
+        let state = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&data)?;
+        let account_extensions = ExtensionType::get_required_init_account_extensions(&state.get_extension_types()?);
+
+        ExtensionType::get_account_len::<spl_token_2022::state::Account>(&account_extensions)
+    };
+
+    let lamports = Rent::get()?.minimum_balance(space);
+    if account.lamports() == 0 {
+        anchor_lang::system_program::create_account(
+            CpiContext::new_with_signer(
+                system_program,
+                anchor_lang::system_program::CreateAccount {
+                    from: payer,
+                    to: account.clone(),
+                },
+                signer_seeds,
+            ),
+            lamports,
+            space as u64,
+            token_program.key,
+        )?;
+    } else {
+        // The address already holds lamports, create_account would fail.
+        // Top up to the rent minimum, then allocate and assign the account, as the init constraint does.
+        let top_up = lamports.saturating_sub(account.lamports());
+        if top_up > 0 {
+            anchor_lang::system_program::transfer(
+                CpiContext::new(
+                    system_program.clone(),
+                    anchor_lang::system_program::Transfer {
+                        from: payer,
+                        to: account.clone(),
+                    },
+                ),
+                top_up,
+            )?;
+        }
+
+        anchor_lang::system_program::allocate(
+            CpiContext::new_with_signer(
+                system_program.clone(),
+                anchor_lang::system_program::Allocate {
+                    account_to_allocate: account.clone(),
+                },
+                signer_seeds,
+            ),
+            space as u64,
+        )?;
+
+        anchor_lang::system_program::assign(
+            CpiContext::new_with_signer(
+                system_program,
+                anchor_lang::system_program::Assign {
+                    account_to_assign: account.clone(),
+                },
+                signer_seeds,
+            ),
+            token_program.key,
+        )?;
+    }
+
+    let instruction = spl_token_2022::instruction::initialize_account3(
+        token_program.key,
+        account.key,
+        mint_account.key,
+        &authority,
+    )?;
+
+    invoke_signed(&instruction, &[account, mint_account, token_program], &[])?;
+
+    return Ok(());
+}
+
+// Derive the associated token account of the wallet for the token program of the mint
+pub fn get_associated_token_address_with_program_id(wallet: &Pubkey, mint_account: &Pubkey, token_program: &Pubkey) -> Pubkey {
+    return Pubkey::find_program_address(
+        &[wallet.as_ref(), token_program.as_ref(), mint_account.as_ref()],
+        &anchor_spl::associated_token::ID,
+    ).0;
+}
+
+// Create the associated token account of the wallet for the token program of the mint
+pub fn create_associated_token_account<'info>(
+    payer: AccountInfo<'info>,
+    associated_token: AccountInfo<'info>,
+    wallet: AccountInfo<'info>,
+    mint_account: AccountInfo<'info>,
+    system_program: AccountInfo<'info>,
+    token_program: AccountInfo<'info>,
+    associated_token_program: AccountInfo<'info>,
+) -> Result<()> {
+    let instruction = Instruction {
+        program_id: anchor_spl::associated_token::ID,
+        accounts: vec![
+            AccountMeta::new(payer.key(), true),
+            AccountMeta::new(associated_token.key(), false),
+            AccountMeta::new_readonly(wallet.key(), false),
+            AccountMeta::new_readonly(mint_account.key(), false),
+            AccountMeta::new_readonly(system_program.key(), false),
+            AccountMeta::new_readonly(token_program.key(), false),
+        ],
+        data: vec![0], // create
+    };
+
+    invoke_signed(
+        &instruction,
+        &[payer, associated_token, wallet, mint_account, system_program, token_program, associated_token_program],
+        &[],
+    )?;
+
+    return Ok(());
+}
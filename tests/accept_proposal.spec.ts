@@ -0,0 +1,166 @@
+import * as anchor from "@project-serum/anchor";
+import { AnchorError, BN, Program, web3 } from "@project-serum/anchor";
+import { Keypair, PublicKey } from "@solana/web3.js";
+import { getAccount } from "@solana/spl-token";
+import { expect } from "chai";
+
+import { Swap } from "../target/types/swap";
+import { airdrop, createProposal, itemAccounts, setupMint } from "./fixtures";
+
+describe("accept_proposal", async () => {
+  // Configure the client to use the local cluster.
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.Swap as Program<Swap>;
+
+  // find the swap account
+  const [swapRegistry] = await PublicKey.findProgramAddress(
+    [anchor.utils.bytes.utf8.encode("SEED::SWAP::PLATFORM")],
+    program.programId
+  );
+  const [swapTreasury] = await PublicKey.findProgramAddress(
+    [anchor.utils.bytes.utf8.encode("SEED::SWAP::TREASURY_SEED")],
+    program.programId
+  );
+
+  const proposalOwner = Keypair.generate();
+  const participant = Keypair.generate();
+  const proposalId = Keypair.generate().publicKey.toBase58().slice(0, 10);
+  const optionId = Keypair.generate().publicKey.toBase58().slice(0, 10);
+
+  let swapProposal;
+  let offeredMint;
+  let askingMint;
+
+  before(async () => {
+    // funding the users
+    await airdrop([proposalOwner, participant]);
+
+    offeredMint = await setupMint([proposalOwner, participant]);
+    askingMint = await setupMint([proposalOwner, participant]);
+
+    swapProposal = await createProposal(proposalOwner, {
+      id: proposalId,
+      swapOptions: [
+        {
+          id: optionId,
+          askingItems: [
+            {
+              id: Keypair.generate().publicKey.toBase58().slice(0, 10),
+              mintAccount: askingMint.mintAccount,
+              amount: new BN(web3.LAMPORTS_PER_SOL * 2),
+              itemType: { currency: {} },
+            },
+          ],
+        },
+      ],
+      offeredItems: [
+        {
+          id: Keypair.generate().publicKey.toBase58().slice(0, 10),
+          mintAccount: offeredMint.mintAccount,
+          amount: new BN(web3.LAMPORTS_PER_SOL),
+          itemType: { currency: {} },
+        },
+      ],
+    });
+
+    await program.methods
+      .depositAll({
+        proposalId,
+        swapTokenVaultBumps: Buffer.from([offeredMint.swapTokenVaultBump]),
+      })
+      .accounts({
+        signer: proposalOwner.publicKey,
+        swapProposal,
+        swapRegistry,
+      })
+      .remainingAccounts(
+        itemAccounts(
+          offeredMint,
+          offeredMint.tokenAccounts[proposalOwner.publicKey.toBase58()]
+        )
+      )
+      .signers([proposalOwner])
+      .rpc({ commitment: "confirmed" });
+  });
+
+  it("[accept_proposal] should: fail to accept an unknown option", async () => {
+    try {
+      await program.methods
+        .acceptProposal({
+          proposalId,
+          optionId: "unknown",
+          askingVaultBumps: Buffer.from([askingMint.swapTokenVaultBump]),
+          offeredVaultBumps: Buffer.from([offeredMint.swapTokenVaultBump]),
+          settleToOwner: false,
+        })
+        .accounts({
+          signer: participant.publicKey,
+          swapProposal,
+          swapRegistry,
+          swapTreasury,
+        })
+        .signers([participant])
+        .rpc({ commitment: "confirmed" });
+
+      throw new Error("Should be failed");
+    } catch (e) {
+      expect(e instanceof AnchorError).to.be.true;
+      expect(e.error.errorCode.code).equals("SwapOptionNotFound");
+    }
+  });
+
+  it("[accept_proposal] should: participant swaps atomically and the owner proceeds are pushed directly", async () => {
+    await program.methods
+      .acceptProposal({
+        proposalId,
+        optionId,
+        askingVaultBumps: Buffer.from([askingMint.swapTokenVaultBump]),
+        offeredVaultBumps: Buffer.from([offeredMint.swapTokenVaultBump]),
+        settleToOwner: true,
+      })
+      .accounts({
+        signer: participant.publicKey,
+        swapProposal,
+        swapRegistry,
+        swapTreasury,
+      })
+      .remainingAccounts([
+        ...itemAccounts(
+          askingMint,
+          askingMint.tokenAccounts[participant.publicKey.toBase58()]
+        ),
+        ...itemAccounts(
+          offeredMint,
+          offeredMint.tokenAccounts[participant.publicKey.toBase58()]
+        ),
+        ...itemAccounts(
+          askingMint,
+          askingMint.tokenAccounts[proposalOwner.publicKey.toBase58()]
+        ),
+      ])
+      .signers([participant])
+      .rpc({ commitment: "confirmed" });
+
+    const state = await program.account.swapProposal.fetch(swapProposal);
+    // @ts-ignore
+    expect(!!state.status.redeemed).to.be.true;
+    expect(state.fulfilledBy.equals(participant.publicKey)).to.be.true;
+    expect(state.fulfilledWithOptionId).eq(optionId);
+
+    const participantOfferedAccount = await getAccount(
+      provider.connection,
+      offeredMint.tokenAccounts[participant.publicKey.toBase58()]
+    );
+    expect(Number(participantOfferedAccount.amount)).eq(
+      web3.LAMPORTS_PER_SOL * 11
+    );
+
+    const ownerAskingAccount = await getAccount(
+      provider.connection,
+      askingMint.tokenAccounts[proposalOwner.publicKey.toBase58()]
+    );
+    expect(Number(ownerAskingAccount.amount)).eq(web3.LAMPORTS_PER_SOL * 12);
+  });
+});
@@ -0,0 +1,512 @@
+import * as anchor from "@project-serum/anchor";
+import { AnchorError, BN, Program, web3 } from "@project-serum/anchor";
+import { Keypair, PublicKey } from "@solana/web3.js";
+import {
+  createMint,
+  getAccount,
+  getOrCreateAssociatedTokenAccount,
+  mintTo,
+} from "@solana/spl-token";
+import { expect } from "chai";
+import { createHash } from "crypto";
+
+import { Swap } from "../target/types/swap";
+import { createProposal, createTokenVault } from "./fixtures";
+import { createNft, metadataAccount } from "./metadata";
+
+describe("collection_ask", async () => {
+  // Configure the client to use the local cluster.
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.Swap as Program<Swap>;
+  const deployer = provider.wallet as anchor.Wallet;
+
+  // find the swap account
+  const [swapRegistry] = await PublicKey.findProgramAddress(
+    [anchor.utils.bytes.utf8.encode("SEED::SWAP::PLATFORM")],
+    program.programId
+  );
+  const [swapTreasury] = await PublicKey.findProgramAddress(
+    [anchor.utils.bytes.utf8.encode("SEED::SWAP::TREASURY_SEED")],
+    program.programId
+  );
+
+  const proposalOwner = Keypair.generate();
+  const participant = Keypair.generate();
+  const proposalId = Keypair.generate().publicKey.toBase58().slice(0, 10);
+  const optionId = Keypair.generate().publicKey.toBase58().slice(0, 10);
+
+  let swapProposal;
+  let offeredMint;
+  let nftMint;
+  let collectionMint;
+  let collectionMembers;
+
+  const itemAccounts = (mint, tokenAccount) => [
+    { pubkey: mint.mintAccount, isSigner: false, isWritable: false },
+    { pubkey: tokenAccount, isSigner: false, isWritable: true },
+    { pubkey: mint.swapTokenVault, isSigner: false, isWritable: true },
+  ];
+
+  // create and whitelist a mint, then fund the owner and the participant
+  const setupMint = async (decimals: number, amount: number) => {
+    const mintAccount = await createMint(
+      provider.connection,
+      deployer.payer,
+      deployer.publicKey,
+      deployer.publicKey,
+      decimals
+    );
+
+    const tokenAccounts = {};
+    for (const user of [proposalOwner, participant]) {
+      const tokenAccount = await getOrCreateAssociatedTokenAccount(
+        provider.connection,
+        user,
+        mintAccount,
+        user.publicKey
+      );
+      await mintTo(
+        provider.connection,
+        deployer.payer,
+        mintAccount,
+        tokenAccount.address,
+        deployer.publicKey,
+        amount
+      );
+      tokenAccounts[user.publicKey.toBase58()] = tokenAccount.address;
+    }
+
+    const [swapTokenVault, swapTokenVaultBump] =
+      await PublicKey.findProgramAddress(
+        [
+          anchor.utils.bytes.utf8.encode("SEED::SWAP::TOKEN_VAULT_SEED"),
+          mintAccount.toBytes(),
+        ],
+        program.programId
+      );
+    await program.methods
+      .createTokenVault()
+      .accounts({
+        signer: deployer.publicKey,
+        mintAccount,
+        swapRegistry,
+        swapTokenVault,
+      })
+      .signers([deployer.payer])
+      .rpc({ commitment: "confirmed" });
+
+    return { mintAccount, tokenAccounts, swapTokenVault, swapTokenVaultBump };
+  };
+
+  const updateReservationWindow = async (reservationWindow) => {
+    const registry = await program.account.swapPlatformRegistry.fetch(
+      swapRegistry
+    );
+    await program.methods
+      .updateSwapRegistry({
+        maxAllowedItems: registry.maxAllowedItems,
+        maxAllowedOptions: registry.maxAllowedOptions,
+        reservationWindow: new BN(reservationWindow),
+      })
+      .accounts({
+        swapRegistry,
+        owner: deployer.publicKey,
+      })
+      .signers([deployer.payer])
+      .rpc({ commitment: "confirmed" });
+  };
+
+  // mint an NFT of the collection to the participant and whitelist it
+  const setupCollectionMember = async () => {
+    const nft = await createNft(participant, { collectionMint });
+    const { swapTokenVault, swapTokenVaultBump } = await createTokenVault(
+      nft.mintAccount
+    );
+
+    return { ...nft, swapTokenVault, swapTokenVaultBump };
+  };
+
+  before(async () => {
+    // funding the users
+    for (const user of [proposalOwner, participant]) {
+      const airdropSignature = await provider.connection.requestAirdrop(
+        user.publicKey,
+        web3.LAMPORTS_PER_SOL * 2
+      );
+      await provider.connection.confirmTransaction({
+        signature: airdropSignature,
+        blockhash: (await provider.connection.getLatestBlockhash()).blockhash,
+        lastValidBlockHeight: (
+          await provider.connection.getLatestBlockhash()
+        ).lastValidBlockHeight,
+      });
+    }
+
+    offeredMint = await setupMint(9, web3.LAMPORTS_PER_SOL * 10);
+    nftMint = await setupMint(0, 1);
+
+    // the collection is verified by the deployer on the NFTs of the participant
+    collectionMint = (await createNft(deployer.payer)).mintAccount;
+    collectionMembers = [
+      await setupCollectionMember(),
+      await setupCollectionMember(),
+    ];
+
+    [swapProposal] = await PublicKey.findProgramAddress(
+      [
+        anchor.utils.bytes.utf8.encode("SEED::SWAP::PROPOSAL_SEED"),
+        proposalOwner.publicKey.toBuffer(),
+        createHash("sha256").update(proposalId).digest(),
+      ],
+      program.programId
+    );
+  });
+
+  after(async () => {
+    await updateReservationWindow(0);
+  });
+
+  it("[collection_ask] should: fail to offer a collection item", async () => {
+    try {
+      await program.methods
+        .createProposal({
+          id: proposalId,
+          swapOptions: [
+            {
+              id: optionId,
+              askingItems: [
+                {
+                  id: Keypair.generate().publicKey.toBase58().slice(0, 10),
+                  mintAccount: offeredMint.mintAccount,
+                  amount: new BN(1),
+                  itemType: { currency: {} },
+                },
+              ],
+            },
+          ],
+          offeredItems: [
+            {
+              id: Keypair.generate().publicKey.toBase58().slice(0, 10),
+              mintAccount: collectionMint,
+              amount: new BN(1),
+              itemType: { collection: {} },
+            },
+          ],
+          expiredAt: new BN(new Date().getTime() + 1000 * 60 * 60 * 24 * 7),
+        })
+        .accounts({
+          proposalOwner: proposalOwner.publicKey,
+          swapRegistry,
+          swapTreasury,
+          swapProposal,
+        })
+        .signers([proposalOwner])
+        .rpc({ commitment: "confirmed" });
+
+      throw new Error("Should be failed");
+    } catch (e) {
+      expect(e instanceof AnchorError).to.be.true;
+      expect(e.error.errorCode.code).equals("InvalidValue");
+    }
+  });
+
+  it("[collection_ask] should: proposal owner asks for any NFT of a collection", async () => {
+    await program.methods
+      .createProposal({
+        id: proposalId,
+        swapOptions: [
+          {
+            id: optionId,
+            askingItems: [
+              {
+                id: Keypair.generate().publicKey.toBase58().slice(0, 10),
+                mintAccount: collectionMint,
+                amount: new BN(1),
+                itemType: { collection: {} },
+              },
+            ],
+          },
+        ],
+        offeredItems: [
+          {
+            id: Keypair.generate().publicKey.toBase58().slice(0, 10),
+            mintAccount: offeredMint.mintAccount,
+            amount: new BN(web3.LAMPORTS_PER_SOL),
+            itemType: { currency: {} },
+          },
+        ],
+        expiredAt: new BN(new Date().getTime() + 1000 * 60 * 60 * 24 * 7),
+      })
+      .accounts({
+        proposalOwner: proposalOwner.publicKey,
+        swapRegistry,
+        swapTreasury,
+        swapProposal,
+      })
+      .signers([proposalOwner])
+      .rpc({ commitment: "confirmed" });
+
+    await program.methods
+      .depositAll({
+        proposalId,
+        swapTokenVaultBumps: Buffer.from([offeredMint.swapTokenVaultBump]),
+      })
+      .accounts({
+        signer: proposalOwner.publicKey,
+        swapProposal,
+        swapRegistry,
+      })
+      .remainingAccounts(
+        itemAccounts(
+          offeredMint,
+          offeredMint.tokenAccounts[proposalOwner.publicKey.toBase58()]
+        )
+      )
+      .signers([proposalOwner])
+      .rpc({ commitment: "confirmed" });
+
+    const state = await program.account.swapProposal.fetch(swapProposal);
+    const askingItem = state.swapOptions[0].askingItems[0];
+    // @ts-ignore
+    expect(!!askingItem.itemType.collection).to.be.true;
+    expect(askingItem.mintAccount.equals(collectionMint)).to.be.true;
+    expect(state.collectionAsks.length).eq(1);
+    expect(state.collectionAsks[0].itemId).eq(askingItem.id);
+    expect(state.collectionAsks[0].collectionMint.equals(collectionMint)).to.be.true;
+  });
+
+  it("[collection_ask] should: fail to fulfill a collection ask without the metadata of the mint", async () => {
+    try {
+      await program.methods
+        .acceptProposal({
+          proposalId,
+          optionId,
+          askingVaultBumps: Buffer.from([nftMint.swapTokenVaultBump]),
+          offeredVaultBumps: Buffer.from([offeredMint.swapTokenVaultBump]),
+          settleToOwner: false,
+        })
+        .accounts({
+          signer: participant.publicKey,
+          swapProposal,
+          swapRegistry,
+          swapTreasury,
+        })
+        .remainingAccounts([
+          ...itemAccounts(
+            nftMint,
+            nftMint.tokenAccounts[participant.publicKey.toBase58()]
+          ),
+          ...itemAccounts(
+            offeredMint,
+            offeredMint.tokenAccounts[participant.publicKey.toBase58()]
+          ),
+        ])
+        .signers([participant])
+        .rpc({ commitment: "confirmed" });
+
+      throw new Error("Should be failed");
+    } catch (e) {
+      expect(e instanceof AnchorError).to.be.true;
+      expect(e.error.errorCode.code).equals("InvalidMetadata");
+    }
+  });
+
+  it("[collection_ask] should: participant fulfills the collection ask with a verified member and the proposal owner redeems it", async () => {
+    const member = collectionMembers[0];
+
+    await program.methods
+      .acceptProposal({
+        proposalId,
+        optionId,
+        askingVaultBumps: Buffer.from([member.swapTokenVaultBump]),
+        offeredVaultBumps: Buffer.from([offeredMint.swapTokenVaultBump]),
+        settleToOwner: false,
+      })
+      .accounts({
+        signer: participant.publicKey,
+        swapProposal,
+        swapRegistry,
+        swapTreasury,
+      })
+      .remainingAccounts([
+        ...itemAccounts(member, member.tokenAccount),
+        ...itemAccounts(
+          offeredMint,
+          offeredMint.tokenAccounts[participant.publicKey.toBase58()]
+        ),
+        await metadataAccount(member.mintAccount),
+      ])
+      .signers([participant])
+      .rpc({ commitment: "confirmed" });
+
+    // the asking item is resolved to the deposited mint, the collection mint is kept aside
+    let state = await program.account.swapProposal.fetch(swapProposal);
+    const askingItem = state.swapOptions[0].askingItems[0];
+    expect(askingItem.mintAccount.equals(member.mintAccount)).to.be.true;
+    // @ts-ignore
+    expect(!!askingItem.status.deposited).to.be.true;
+    expect(state.collectionAsks[0].collectionMint.equals(collectionMint)).to.be
+      .true;
+
+    const ownerTokenAccount = await getOrCreateAssociatedTokenAccount(
+      provider.connection,
+      proposalOwner,
+      member.mintAccount,
+      proposalOwner.publicKey
+    );
+    await program.methods
+      // @ts-ignore
+      .transferAssetsFromVault({
+        proposalId,
+        swapItemId: askingItem.id,
+        swapTokenVaultBump: member.swapTokenVaultBump,
+        actionType: { redeeming: {} },
+      })
+      .accounts({
+        signer: proposalOwner.publicKey,
+        signerTokenAccount: ownerTokenAccount.address,
+        swapProposal,
+        swapTokenVault: member.swapTokenVault,
+        swapRegistry,
+        mintAccount: member.mintAccount,
+      })
+      .signers([proposalOwner])
+      .rpc({ commitment: "confirmed" });
+
+    expect(
+      Number(
+        (await getAccount(provider.connection, ownerTokenAccount.address))
+          .amount
+      )
+    ).eq(1);
+
+    state = await program.account.swapProposal.fetch(swapProposal);
+    // @ts-ignore
+    expect(!!state.swapOptions[0].askingItems[0].status.redeemed).to.be.true;
+  });
+
+  it("[collection_ask] should: releasing a stale reservation restores the collection mint of the collection ask", async () => {
+    const member = collectionMembers[1];
+    const collectionItemId = Keypair.generate()
+      .publicKey.toBase58()
+      .slice(0, 10);
+    const releasedProposalId = Keypair.generate()
+      .publicKey.toBase58()
+      .slice(0, 10);
+
+    // the collection ask comes with a currency item so that the reservation stays partial
+    const releasedProposal = await createProposal(proposalOwner, {
+      id: releasedProposalId,
+      swapOptions: [
+        {
+          id: optionId,
+          askingItems: [
+            {
+              id: collectionItemId,
+              mintAccount: collectionMint,
+              amount: new BN(1),
+              itemType: { collection: {} },
+            },
+            {
+              id: Keypair.generate().publicKey.toBase58().slice(0, 10),
+              mintAccount: offeredMint.mintAccount,
+              amount: new BN(web3.LAMPORTS_PER_SOL),
+              itemType: { currency: {} },
+            },
+          ],
+        },
+      ],
+      offeredItems: [
+        {
+          id: Keypair.generate().publicKey.toBase58().slice(0, 10),
+          mintAccount: offeredMint.mintAccount,
+          amount: new BN(web3.LAMPORTS_PER_SOL),
+          itemType: { currency: {} },
+        },
+      ],
+    });
+
+    await program.methods
+      .depositAll({
+        proposalId: releasedProposalId,
+        swapTokenVaultBumps: Buffer.from([offeredMint.swapTokenVaultBump]),
+      })
+      .accounts({
+        signer: proposalOwner.publicKey,
+        swapProposal: releasedProposal,
+        swapRegistry,
+      })
+      .remainingAccounts(
+        itemAccounts(
+          offeredMint,
+          offeredMint.tokenAccounts[proposalOwner.publicKey.toBase58()]
+        )
+      )
+      .signers([proposalOwner])
+      .rpc({ commitment: "confirmed" });
+
+    await updateReservationWindow(5);
+
+    // the participant reserves the proposal with the collection member only
+    await program.methods
+      // @ts-ignore
+      .transferAssetsToVault({
+        proposalId: releasedProposalId,
+        swapItemId: collectionItemId,
+        swapTokenVaultBump: member.swapTokenVaultBump,
+        actionType: { fulfilling: {} },
+        optionId,
+      })
+      .accounts({
+        signer: participant.publicKey,
+        signerTokenAccount: member.tokenAccount,
+        swapProposal: releasedProposal,
+        swapTokenVault: member.swapTokenVault,
+        swapRegistry,
+        swapTreasury,
+        mintAccount: member.mintAccount,
+      })
+      .remainingAccounts([await metadataAccount(member.mintAccount)])
+      .signers([participant])
+      .rpc({ commitment: "confirmed" });
+
+    let state = await program.account.swapProposal.fetch(releasedProposal);
+    expect(
+      state.swapOptions[0].askingItems[0].mintAccount.equals(member.mintAccount)
+    ).to.be.true;
+
+    // wait for the cluster clock to pass the reservation window, then release the reservation
+    for (let attempt = 0; ; attempt++) {
+      await new Promise((resolve) => setTimeout(resolve, 2000));
+
+      try {
+        await program.methods
+          .releaseReservation({ proposalId: releasedProposalId })
+          .accounts({
+            signer: deployer.publicKey,
+            swapRegistry,
+            swapProposal: releasedProposal,
+          })
+          .signers([deployer.payer])
+          .rpc({ commitment: "confirmed" });
+        break;
+      } catch (e) {
+        if (attempt >= 15) throw e;
+      }
+    }
+
+    // the collection ask reopens for any member, the deposited member is refunded
+    state = await program.account.swapProposal.fetch(releasedProposal);
+    const askingItem = state.swapOptions[0].askingItems[0];
+    expect(askingItem.mintAccount.equals(collectionMint)).to.be.true;
+    // @ts-ignore
+    expect(!!askingItem.status.created).to.be.true;
+    expect(state.pendingRefunds.length).eq(1);
+    expect(state.pendingRefunds[0].mintAccount.equals(member.mintAccount)).to
+      .be.true;
+    expect(state.pendingRefunds[0].owner.equals(participant.publicKey)).to.be
+      .true;
+  });
+});
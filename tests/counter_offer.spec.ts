@@ -0,0 +1,330 @@
+import * as anchor from "@project-serum/anchor";
+import { AnchorError, BN, Program, web3 } from "@project-serum/anchor";
+import { Keypair, PublicKey } from "@solana/web3.js";
+import { getAccount } from "@solana/spl-token";
+import { expect } from "chai";
+
+import { Swap } from "../target/types/swap";
+import { airdrop, createProposal, itemAccounts, setupMint } from "./fixtures";
+
+describe("counter_offer", async () => {
+  // Configure the client to use the local cluster.
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.Swap as Program<Swap>;
+  const deployer = provider.wallet as anchor.Wallet;
+
+  // find the swap account
+  const [swapRegistry] = await PublicKey.findProgramAddress(
+    [anchor.utils.bytes.utf8.encode("SEED::SWAP::PLATFORM")],
+    program.programId
+  );
+  const [swapTreasury] = await PublicKey.findProgramAddress(
+    [anchor.utils.bytes.utf8.encode("SEED::SWAP::TREASURY_SEED")],
+    program.programId
+  );
+
+  const proposalOwner = Keypair.generate();
+  const participant = Keypair.generate();
+  const rejectedParticipant = Keypair.generate();
+  const proposalId = Keypair.generate().publicKey.toBase58().slice(0, 10);
+  const optionId = Keypair.generate().publicKey.toBase58().slice(0, 10);
+
+  let swapProposal;
+  let offeredMint;
+  let askingMint;
+  let counterMint;
+
+  before(async () => {
+    // funding the users
+    await airdrop([proposalOwner, participant, rejectedParticipant]);
+
+    offeredMint = await setupMint([
+      proposalOwner,
+      participant,
+      rejectedParticipant,
+    ]);
+    askingMint = await setupMint([
+      proposalOwner,
+      participant,
+      rejectedParticipant,
+    ]);
+    counterMint = await setupMint([
+      proposalOwner,
+      participant,
+      rejectedParticipant,
+    ]);
+
+    swapProposal = await createProposal(proposalOwner, {
+      id: proposalId,
+      swapOptions: [
+        {
+          id: optionId,
+          askingItems: [
+            {
+              id: Keypair.generate().publicKey.toBase58().slice(0, 10),
+              mintAccount: askingMint.mintAccount,
+              amount: new BN(web3.LAMPORTS_PER_SOL * 2),
+              itemType: { currency: {} },
+            },
+          ],
+        },
+      ],
+      offeredItems: [
+        {
+          id: Keypair.generate().publicKey.toBase58().slice(0, 10),
+          mintAccount: offeredMint.mintAccount,
+          amount: new BN(web3.LAMPORTS_PER_SOL),
+          itemType: { currency: {} },
+        },
+      ],
+    });
+
+    await program.methods
+      .depositAll({
+        proposalId,
+        swapTokenVaultBumps: Buffer.from([offeredMint.swapTokenVaultBump]),
+      })
+      .accounts({
+        signer: proposalOwner.publicKey,
+        swapProposal,
+        swapRegistry,
+      })
+      .remainingAccounts(
+        itemAccounts(
+          offeredMint,
+          offeredMint.tokenAccounts[proposalOwner.publicKey.toBase58()]
+        )
+      )
+      .signers([proposalOwner])
+      .rpc({ commitment: "confirmed" });
+  });
+
+  // find the counter offer of a taker
+  const findCounterOffer = async (taker: PublicKey) => {
+    const [counterOffer] = await PublicKey.findProgramAddress(
+      [
+        anchor.utils.bytes.utf8.encode("SEED::SWAP::COUNTER_OFFER_SEED"),
+        swapProposal.toBuffer(),
+        taker.toBuffer(),
+      ],
+      program.programId
+    );
+    return counterOffer;
+  };
+
+  // escrow a counter offer asking for the counter mint
+  const createCounterOffer = async (taker: Keypair, counterOptionId: string) =>
+    program.methods
+      .createCounterOffer({
+        proposalId,
+        swapOption: {
+          id: counterOptionId,
+          askingItems: [
+            {
+              id: Keypair.generate().publicKey.toBase58().slice(0, 10),
+              mintAccount: counterMint.mintAccount,
+              amount: new BN(web3.LAMPORTS_PER_SOL * 3),
+              itemType: { currency: {} },
+            },
+          ],
+        },
+        expiredAt: new BN(new Date().getTime() + 1000 * 60 * 60 * 24),
+        swapTokenVaultBumps: Buffer.from([counterMint.swapTokenVaultBump]),
+      })
+      .accounts({
+        signer: taker.publicKey,
+        swapRegistry,
+        swapProposal,
+        counterOffer: await findCounterOffer(taker.publicKey),
+      })
+      .remainingAccounts(
+        itemAccounts(
+          counterMint,
+          counterMint.tokenAccounts[taker.publicKey.toBase58()]
+        )
+      )
+      .signers([taker])
+      .rpc({ commitment: "confirmed" });
+
+  const updateMaxAllowedOptions = async (maxAllowedOptions: number) => {
+    const registry = await program.account.swapPlatformRegistry.fetch(
+      swapRegistry
+    );
+    await program.methods
+      .updateSwapRegistry({
+        maxAllowedItems: registry.maxAllowedItems,
+        maxAllowedOptions,
+      })
+      .accounts({
+        swapRegistry,
+        owner: deployer.publicKey,
+      })
+      .signers([deployer.payer])
+      .rpc({ commitment: "confirmed" });
+  };
+
+  const acceptCounterOffer = async () =>
+    program.methods
+      .acceptCounterOffer({ proposalId })
+      .accounts({
+        signer: proposalOwner.publicKey,
+        swapRegistry,
+        swapProposal,
+        counterOffer: await findCounterOffer(participant.publicKey),
+        taker: participant.publicKey,
+        swapTreasury,
+      })
+      .signers([proposalOwner])
+      .rpc({ commitment: "confirmed" });
+
+  it("[counter_offer] should: fail to counter an own proposal", async () => {
+    try {
+      await createCounterOffer(proposalOwner, "counter-owner");
+
+      throw new Error("Should be failed");
+    } catch (e) {
+      expect(e instanceof AnchorError).to.be.true;
+      expect(e.error.errorCode.code).equals("OnlyParticipant");
+    }
+  });
+
+  it("[counter_offer] should: fail to counter with an option id of the proposal", async () => {
+    try {
+      await createCounterOffer(participant, optionId);
+
+      throw new Error("Should be failed");
+    } catch (e) {
+      expect(e instanceof AnchorError).to.be.true;
+      expect(e.error.errorCode.code).equals("InvalidValue");
+    }
+  });
+
+  it("[counter_offer] should: takers escrow their counter offers up front", async () => {
+    await createCounterOffer(participant, "counter-accepted");
+    await createCounterOffer(rejectedParticipant, "counter-rejected");
+
+    const counterOffer = await program.account.counterOffer.fetch(
+      await findCounterOffer(participant.publicKey)
+    );
+    expect(counterOffer.taker.equals(participant.publicKey)).to.be.true;
+    expect(counterOffer.proposal.equals(swapProposal)).to.be.true;
+    expect(counterOffer.swapOption.id).eq("counter-accepted");
+    counterOffer.swapOption.askingItems.forEach((item) => {
+      // @ts-ignore
+      expect(!!item.status.deposited).to.be.true;
+      expect(item.owner.equals(participant.publicKey)).to.be.true;
+    });
+
+    const state = await program.account.swapProposal.fetch(swapProposal);
+    expect(state.openCounterOffers).eq(2);
+
+    const participantAccount = await getAccount(
+      provider.connection,
+      counterMint.tokenAccounts[participant.publicKey.toBase58()]
+    );
+    expect(Number(participantAccount.amount)).eq(web3.LAMPORTS_PER_SOL * 7);
+  });
+
+  it("[counter_offer] should: fail to withdraw a counter offer before its expiry", async () => {
+    try {
+      await program.methods
+        .withdrawCounterOffer({
+          proposalId,
+          swapTokenVaultBumps: Buffer.from([counterMint.swapTokenVaultBump]),
+        })
+        .accounts({
+          signer: rejectedParticipant.publicKey,
+          swapRegistry,
+          swapProposal,
+          counterOffer: await findCounterOffer(rejectedParticipant.publicKey),
+          taker: rejectedParticipant.publicKey,
+        })
+        .remainingAccounts(
+          itemAccounts(
+            counterMint,
+            counterMint.tokenAccounts[rejectedParticipant.publicKey.toBase58()]
+          )
+        )
+        .signers([rejectedParticipant])
+        .rpc({ commitment: "confirmed" });
+
+      throw new Error("Should be failed");
+    } catch (e) {
+      expect(e instanceof AnchorError).to.be.true;
+      expect(e.error.errorCode.code).equals("CounterOfferCannotBeWithdrawn");
+    }
+  });
+
+  it("[counter_offer] should: proposal owner rejects a counter offer and the escrow is released", async () => {
+    const counterOffer = await findCounterOffer(rejectedParticipant.publicKey);
+
+    await program.methods
+      .rejectCounterOffer({
+        proposalId,
+        swapTokenVaultBumps: Buffer.from([counterMint.swapTokenVaultBump]),
+      })
+      .accounts({
+        signer: proposalOwner.publicKey,
+        swapRegistry,
+        swapProposal,
+        counterOffer,
+        taker: rejectedParticipant.publicKey,
+      })
+      .remainingAccounts(
+        itemAccounts(
+          counterMint,
+          counterMint.tokenAccounts[rejectedParticipant.publicKey.toBase58()]
+        )
+      )
+      .signers([proposalOwner])
+      .rpc({ commitment: "confirmed" });
+
+    expect(await provider.connection.getAccountInfo(counterOffer)).to.be.null;
+
+    const state = await program.account.swapProposal.fetch(swapProposal);
+    expect(state.openCounterOffers).eq(1);
+
+    const rejectedAccount = await getAccount(
+      provider.connection,
+      counterMint.tokenAccounts[rejectedParticipant.publicKey.toBase58()]
+    );
+    expect(Number(rejectedAccount.amount)).eq(web3.LAMPORTS_PER_SOL * 10);
+  });
+
+  it("[counter_offer] should: fail to accept a counter offer beyond the allowed options", async () => {
+    const registry = await program.account.swapPlatformRegistry.fetch(
+      swapRegistry
+    );
+
+    // the proposal already holds as many options as allowed
+    await updateMaxAllowedOptions(1);
+    try {
+      await acceptCounterOffer();
+
+      throw new Error("Should be failed");
+    } catch (e) {
+      expect(e instanceof AnchorError).to.be.true;
+      expect(e.error.errorCode.code).equals("InvalidValue");
+    } finally {
+      await updateMaxAllowedOptions(registry.maxAllowedOptions);
+    }
+  });
+
+  it("[counter_offer] should: proposal owner accepts a counter offer as the fulfilled option", async () => {
+    const counterOffer = await findCounterOffer(participant.publicKey);
+
+    await acceptCounterOffer();
+
+    expect(await provider.connection.getAccountInfo(counterOffer)).to.be.null;
+
+    const state = await program.account.swapProposal.fetch(swapProposal);
+    // @ts-ignore
+    expect(!!state.status.fulfilled).to.be.true;
+    expect(state.fulfilledBy.equals(participant.publicKey)).to.be.true;
+    expect(state.fulfilledWithOptionId).eq("counter-accepted");
+    expect(state.openCounterOffers).eq(0);
+    expect(state.swapOptions.length).eq(2);
+  });
+});
@@ -0,0 +1,447 @@
+import * as anchor from "@project-serum/anchor";
+import { AnchorError, BN, Program, web3 } from "@project-serum/anchor";
+import { Keypair, PublicKey } from "@solana/web3.js";
+import { getAccount, getAssociatedTokenAddress } from "@solana/spl-token";
+import { expect } from "chai";
+
+import { Swap } from "../target/types/swap";
+import { airdrop, createProposal, itemAccounts, setupMint } from "./fixtures";
+
+describe("crank_settle", async () => {
+  // Configure the client to use the local cluster.
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.Swap as Program<Swap>;
+  const deployer = provider.wallet as anchor.Wallet;
+
+  // find the swap account
+  const [swapRegistry] = await PublicKey.findProgramAddress(
+    [anchor.utils.bytes.utf8.encode("SEED::SWAP::PLATFORM")],
+    program.programId
+  );
+  const [swapTreasury] = await PublicKey.findProgramAddress(
+    [anchor.utils.bytes.utf8.encode("SEED::SWAP::TREASURY_SEED")],
+    program.programId
+  );
+
+  const proposalOwner = Keypair.generate();
+  const participant = Keypair.generate();
+  const keeper = Keypair.generate();
+  const crankBounty = new BN(1_000_000);
+  const proposalId = Keypair.generate().publicKey.toBase58().slice(0, 10);
+
+  let swapProposal;
+  let offeredMint;
+  let askingMint;
+  let offeredItemId;
+  let askingItemId;
+  let optionId;
+
+  // create and whitelist a mint, only the holder has a token account
+  const setupHolderMint = async (holder) => {
+    const mint = await setupMint([holder]);
+
+    return {
+      ...mint,
+      tokenAccount: mint.tokenAccounts[holder.publicKey.toBase58()],
+    };
+  };
+
+  const crankSettle = (
+    remainingAccounts,
+    swapTokenVaultBumps,
+    settled = {
+      proposalId,
+      swapProposal,
+      participant: participant.publicKey,
+    }
+  ) =>
+    program.methods
+      .crankSettle({
+        proposalId: settled.proposalId,
+        swapTokenVaultBumps: Buffer.from(swapTokenVaultBumps),
+      })
+      .accounts({
+        signer: keeper.publicKey,
+        swapRegistry,
+        swapProposal: settled.swapProposal,
+        proposalOwner: proposalOwner.publicKey,
+        participant: settled.participant,
+      })
+      .remainingAccounts(remainingAccounts)
+      .signers([keeper])
+      .rpc({ commitment: "confirmed" });
+
+  // create a proposal asking for two items of the asking mint and deposit the offered item,
+  // the participant optionally reserves the proposal by depositing the first asking item
+  const createDepositedProposal = async (expiredAt, withTaker) => {
+    const id = Keypair.generate().publicKey.toBase58().slice(0, 10);
+    const itemId = Keypair.generate().publicKey.toBase58().slice(0, 10);
+    const askingItemIds = [0, 1].map(() =>
+      Keypair.generate().publicKey.toBase58().slice(0, 10)
+    );
+    const option = Keypair.generate().publicKey.toBase58().slice(0, 10);
+
+    const proposal = await createProposal(proposalOwner, {
+      id,
+      swapOptions: [
+        {
+          id: option,
+          askingItems: askingItemIds.map((askingId) => ({
+            id: askingId,
+            mintAccount: askingMint.mintAccount,
+            amount: new BN(web3.LAMPORTS_PER_SOL),
+            itemType: { currency: {} },
+          })),
+        },
+      ],
+      offeredItems: [
+        {
+          id: itemId,
+          mintAccount: offeredMint.mintAccount,
+          amount: new BN(web3.LAMPORTS_PER_SOL),
+          itemType: { currency: {} },
+        },
+      ],
+      expiredAt,
+    });
+
+    await program.methods
+      .depositAll({
+        proposalId: id,
+        swapTokenVaultBumps: Buffer.from([offeredMint.swapTokenVaultBump]),
+      })
+      .accounts({
+        signer: proposalOwner.publicKey,
+        swapProposal: proposal,
+        swapRegistry,
+      })
+      .remainingAccounts(itemAccounts(offeredMint, offeredMint.tokenAccount))
+      .signers([proposalOwner])
+      .rpc({ commitment: "confirmed" });
+
+    if (withTaker) {
+      await program.methods
+        // @ts-ignore
+        .transferAssetsToVault({
+          proposalId: id,
+          swapItemId: askingItemIds[0],
+          swapTokenVaultBump: askingMint.swapTokenVaultBump,
+          actionType: { fulfilling: {} },
+          optionId: option,
+        })
+        .accounts({
+          signer: participant.publicKey,
+          signerTokenAccount: askingMint.tokenAccount,
+          swapProposal: proposal,
+          swapTokenVault: askingMint.swapTokenVault,
+          swapRegistry,
+          swapTreasury,
+          mintAccount: askingMint.mintAccount,
+        })
+        .signers([participant])
+        .rpc({ commitment: "confirmed" });
+    }
+
+    return {
+      proposalId: id,
+      swapProposal: proposal,
+      // the participant is the default key until a taker reserves the proposal
+      participant: withTaker ? participant.publicKey : PublicKey.default,
+    };
+  };
+
+  const cancelProposal = (settled) =>
+    program.methods
+      .cancelProposal({ id: settled.proposalId })
+      .accounts({
+        swapProposal: settled.swapProposal,
+        signer: proposalOwner.publicKey,
+      })
+      .signers([proposalOwner])
+      .rpc({ commitment: "confirmed" });
+
+  // wait for the cluster clock to pass the expiry, then expire the proposal
+  const expireProposal = async (settled) => {
+    for (let attempt = 0; ; attempt++) {
+      await new Promise((resolve) => setTimeout(resolve, 2000));
+
+      try {
+        await program.methods
+          .expireProposal({ id: settled.proposalId })
+          .accounts({
+            signer: keeper.publicKey,
+            swapProposal: settled.swapProposal,
+          })
+          .signers([keeper])
+          .rpc({ commitment: "confirmed" });
+        break;
+      } catch (e) {
+        if (attempt >= 15) throw e;
+      }
+    }
+  };
+
+  const expiresSoon = async () => {
+    const { blockTime } = await provider.connection.getBlock(
+      await provider.connection.getSlot("confirmed")
+    );
+    return new BN(blockTime + 6);
+  };
+
+  const expiresLater = () =>
+    new BN(new Date().getTime() + 1000 * 60 * 60 * 24 * 7);
+
+  // settle the withdrawn proposal and check the items are back to their owners
+  const settleWithdrawnProposal = async (settled) => {
+    const offeredBalance = await getAccount(
+      provider.connection,
+      offeredMint.tokenAccount
+    );
+    const askingBalance = await getAccount(
+      provider.connection,
+      askingMint.tokenAccount
+    );
+
+    const withTaker = !settled.participant.equals(PublicKey.default);
+    await crankSettle(
+      [
+        ...itemAccounts(offeredMint, offeredMint.tokenAccount),
+        ...(withTaker ? itemAccounts(askingMint, askingMint.tokenAccount) : []),
+      ],
+      withTaker
+        ? [offeredMint.swapTokenVaultBump, askingMint.swapTokenVaultBump]
+        : [offeredMint.swapTokenVaultBump],
+      settled
+    );
+
+    expect(
+      Number((await getAccount(provider.connection, offeredMint.tokenAccount)).amount)
+    ).eq(Number(offeredBalance.amount) + web3.LAMPORTS_PER_SOL);
+    expect(
+      Number((await getAccount(provider.connection, askingMint.tokenAccount)).amount)
+    ).eq(
+      Number(askingBalance.amount) + (withTaker ? web3.LAMPORTS_PER_SOL : 0)
+    );
+    expect(await provider.connection.getAccountInfo(settled.swapProposal)).to.be
+      .null;
+  };
+
+  before(async () => {
+    // funding the users
+    await airdrop([proposalOwner, participant, keeper]);
+
+    offeredMint = await setupHolderMint(proposalOwner);
+    askingMint = await setupHolderMint(participant);
+
+    // configure the crank bounty, keeping the current fees
+    const registry = await program.account.swapPlatformRegistry.fetch(
+      swapRegistry
+    );
+    await program.methods
+      .updateFeeConfig({
+        feeRecipient: registry.feeConfig.feeRecipient,
+        proposalCreationFee: registry.feeConfig.proposalCreationFee,
+        fulfillmentFee: registry.feeConfig.fulfillmentFee,
+        crankBounty,
+      })
+      .accounts({
+        swapRegistry,
+        swapTreasury,
+        owner: deployer.publicKey,
+      })
+      .signers([deployer.payer])
+      .rpc({ commitment: "confirmed" });
+
+    offeredItemId = Keypair.generate().publicKey.toBase58().slice(0, 10);
+    askingItemId = Keypair.generate().publicKey.toBase58().slice(0, 10);
+    optionId = Keypair.generate().publicKey.toBase58().slice(0, 10);
+
+    swapProposal = await createProposal(proposalOwner, {
+      id: proposalId,
+      swapOptions: [
+        {
+          id: optionId,
+          askingItems: [
+            {
+              id: askingItemId,
+              mintAccount: askingMint.mintAccount,
+              amount: new BN(web3.LAMPORTS_PER_SOL * 2),
+              itemType: { currency: {} },
+            },
+          ],
+        },
+      ],
+      offeredItems: [
+        {
+          id: offeredItemId,
+          mintAccount: offeredMint.mintAccount,
+          amount: new BN(web3.LAMPORTS_PER_SOL),
+          itemType: { currency: {} },
+        },
+      ],
+    });
+
+    await program.methods
+      .depositAll({
+        proposalId,
+        swapTokenVaultBumps: Buffer.from([offeredMint.swapTokenVaultBump]),
+      })
+      .accounts({
+        signer: proposalOwner.publicKey,
+        swapProposal,
+        swapRegistry,
+      })
+      .remainingAccounts(
+        itemAccounts(offeredMint, offeredMint.tokenAccount)
+      )
+      .signers([proposalOwner])
+      .rpc({ commitment: "confirmed" });
+  });
+
+  it("[crank_settle] should: fail to settle a proposal still open for participants", async () => {
+    try {
+      await crankSettle([], []);
+
+      throw new Error("Should be failed");
+    } catch (e) {
+      expect(e instanceof AnchorError).to.be.true;
+      expect(e.error.errorCode.code).equals("ProposalCannotBeSettled");
+    }
+  });
+
+  it("[crank_settle] should: keeper pushes the items of a fulfilled proposal to new associated token accounts", async () => {
+    await program.methods
+      // @ts-ignore
+      .transferAssetsToVault({
+        proposalId,
+        swapItemId: askingItemId,
+        swapTokenVaultBump: askingMint.swapTokenVaultBump,
+        actionType: { fulfilling: {} },
+        optionId,
+      })
+      .accounts({
+        signer: participant.publicKey,
+        signerTokenAccount: askingMint.tokenAccount,
+        swapProposal,
+        swapTokenVault: askingMint.swapTokenVault,
+        swapRegistry,
+        swapTreasury,
+        mintAccount: askingMint.mintAccount,
+      })
+      .signers([participant])
+      .rpc({ commitment: "confirmed" });
+
+    const participantTokenAccount = await getAssociatedTokenAddress(
+      offeredMint.mintAccount,
+      participant.publicKey
+    );
+    const ownerTokenAccount = await getAssociatedTokenAddress(
+      askingMint.mintAccount,
+      proposalOwner.publicKey
+    );
+    const ownerBalance = await provider.connection.getBalance(
+      proposalOwner.publicKey
+    );
+    const proposalRent = await provider.connection.getBalance(swapProposal);
+
+    await crankSettle(
+      [
+        ...itemAccounts(offeredMint, participantTokenAccount),
+        ...itemAccounts(askingMint, ownerTokenAccount),
+      ],
+      [offeredMint.swapTokenVaultBump, askingMint.swapTokenVaultBump]
+    );
+
+    const participantAccount = await getAccount(
+      provider.connection,
+      participantTokenAccount
+    );
+    expect(Number(participantAccount.amount)).eq(web3.LAMPORTS_PER_SOL);
+    const ownerAccount = await getAccount(
+      provider.connection,
+      ownerTokenAccount
+    );
+    expect(Number(ownerAccount.amount)).eq(web3.LAMPORTS_PER_SOL * 2);
+
+    // the proposal is closed, the bounty is taken out of the rent
+    expect(await provider.connection.getAccountInfo(swapProposal)).to.be.null;
+    expect(await provider.connection.getBalance(proposalOwner.publicKey)).eq(
+      ownerBalance + proposalRent - crankBounty.toNumber()
+    );
+  });
+
+  it("[crank_settle] should: keeper returns the offered items of a canceled proposal without a taker", async () => {
+    const settled = await createDepositedProposal(expiresLater(), false);
+    await cancelProposal(settled);
+
+    await settleWithdrawnProposal(settled);
+  });
+
+  it("[crank_settle] should: keeper returns the items of a canceled proposal reserved by a taker", async () => {
+    const settled = await createDepositedProposal(expiresLater(), true);
+    await cancelProposal(settled);
+
+    await settleWithdrawnProposal(settled);
+  });
+
+  it("[crank_settle] should: fail to settle a reserved proposal with a read-only participant", async () => {
+    const settled = await createDepositedProposal(expiresLater(), true);
+    await cancelProposal(settled);
+
+    try {
+      await program.methods
+        .crankSettle({
+          proposalId: settled.proposalId,
+          swapTokenVaultBumps: Buffer.from([
+            offeredMint.swapTokenVaultBump,
+            askingMint.swapTokenVaultBump,
+          ]),
+        })
+        .accounts({
+          signer: keeper.publicKey,
+          swapRegistry,
+          swapProposal: settled.swapProposal,
+          proposalOwner: proposalOwner.publicKey,
+          participant: settled.participant,
+        })
+        .remainingAccounts([
+          ...itemAccounts(offeredMint, offeredMint.tokenAccount),
+          ...itemAccounts(askingMint, askingMint.tokenAccount),
+        ])
+        .instruction()
+        .then((instruction) => {
+          // demote the participant to read-only
+          instruction.keys
+            .filter((key) => key.pubkey.equals(settled.participant))
+            .forEach((key) => (key.isWritable = false));
+          return provider.sendAndConfirm(
+            new web3.Transaction().add(instruction),
+            [keeper],
+            { commitment: "confirmed" }
+          );
+        });
+
+      throw new Error("Should be failed");
+    } catch (e) {
+      expect(e.message).to.contain("0x7d0");
+    }
+
+    await settleWithdrawnProposal(settled);
+  });
+
+  it("[crank_settle] should: keeper returns the offered items of an expired proposal without a taker", async () => {
+    const settled = await createDepositedProposal(await expiresSoon(), false);
+    await expireProposal(settled);
+
+    await settleWithdrawnProposal(settled);
+  });
+
+  it("[crank_settle] should: keeper returns the items of an expired proposal reserved by a taker", async () => {
+    const settled = await createDepositedProposal(await expiresSoon(), true);
+    await expireProposal(settled);
+
+    await settleWithdrawnProposal(settled);
+  });
+});
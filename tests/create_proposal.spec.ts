@@ -1,13 +1,15 @@
 import * as anchor from "@project-serum/anchor";
 import {
+  AnchorError,
   BN,
   BorshCoder,
   EventParser,
   Program,
   web3,
 } from "@project-serum/anchor";
-import { Keypair, PublicKey, SendTransactionError } from "@solana/web3.js";
+import { Keypair, PublicKey } from "@solana/web3.js";
 import { expect } from "chai";
+import { createHash } from "crypto";
 import {
   createMint,
   getAccount,
@@ -30,6 +32,10 @@ describe("create_proposal", async () => {
     [anchor.utils.bytes.utf8.encode("SEED::SWAP::PLATFORM")],
     program.programId
   );
+  const [swapTreasury] = await PublicKey.findProgramAddress(
+    [anchor.utils.bytes.utf8.encode("SEED::SWAP::TREASURY_SEED")],
+    program.programId
+  );
 
   let mintNormalPublicKey;
   let swapTokenVault;
@@ -81,7 +87,8 @@ describe("create_proposal", async () => {
     [swapProposal] = await PublicKey.findProgramAddress(
       [
         anchor.utils.bytes.utf8.encode("SEED::SWAP::PROPOSAL_SEED"),
-        anchor.utils.bytes.utf8.encode(proposalId),
+        proposalOwner.publicKey.toBuffer(),
+        createHash("sha256").update(proposalId).digest(),
       ],
       program.programId
     );
@@ -162,7 +169,8 @@ describe("create_proposal", async () => {
     const [swapProposal] = await PublicKey.findProgramAddress(
       [
         anchor.utils.bytes.utf8.encode("SEED::SWAP::PROPOSAL_SEED"),
-        anchor.utils.bytes.utf8.encode(id),
+        proposalOwner.publicKey.toBuffer(),
+        createHash("sha256").update(id).digest(),
       ],
       program.programId
     );
@@ -179,6 +187,7 @@ describe("create_proposal", async () => {
         .accounts({
           proposalOwner: proposalOwner.publicKey,
           swapRegistry,
+          swapTreasury,
           swapProposal: swapProposal,
         })
         .signers([proposalOwner])
@@ -186,7 +195,8 @@ describe("create_proposal", async () => {
 
       throw new Error("Should failed");
     } catch (e) {
-      expect(e instanceof SendTransactionError).to.be.true;
+      expect(e instanceof AnchorError).to.be.true;
+      expect(e.error.errorCode.code).equals("UnAllowedMintToken");
     }
   });
 
@@ -217,6 +227,7 @@ describe("create_proposal", async () => {
       .accounts({
         proposalOwner: proposalOwner.publicKey,
         swapRegistry,
+        swapTreasury,
         swapProposal: swapProposal,
       })
       .signers([proposalOwner])
@@ -304,6 +315,38 @@ describe("create_proposal", async () => {
     expect(event.data.expiredAt.eq(new BN(expiredAt))).to.be.true;
   });
 
+  it("[create_proposal] should: create a proposal with an id longer than 32 bytes", async () => {
+    const id = Keypair.generate().publicKey.toBase58().repeat(2);
+    const [longIdProposal] = await PublicKey.findProgramAddress(
+      [
+        anchor.utils.bytes.utf8.encode("SEED::SWAP::PROPOSAL_SEED"),
+        proposalOwner.publicKey.toBuffer(),
+        createHash("sha256").update(id).digest(),
+      ],
+      program.programId
+    );
+
+    await program.methods
+      .createProposal({
+        id,
+        swapOptions,
+        offeredItems,
+        expiredAt: new BN(new Date().getTime() + 1000 * 60 * 60 * 24 * 7),
+      })
+      .accounts({
+        proposalOwner: proposalOwner.publicKey,
+        swapRegistry,
+        swapTreasury,
+        swapProposal: longIdProposal,
+      })
+      .signers([proposalOwner])
+      .rpc({ commitment: "confirmed" });
+
+    const state = await program.account.swapProposal.fetch(longIdProposal);
+    expect(state.id).eq(id);
+    expect(state.owner.equals(proposalOwner.publicKey)).to.be.true;
+  });
+
   it("[cancel_proposal] should: participants can cancel proposal anytime when proposal isn't fulfilled", async () => {
     // try depositing some items
     const depositInstructions = await Promise.all(
@@ -325,6 +368,8 @@ describe("create_proposal", async () => {
               signerTokenAccount: proposalOwnerTokenAccount.address,
               swapProposal,
               swapTokenVault,
+              swapRegistry,
+              swapTreasury,
               mintAccount: mintNormalPublicKey,
             })
             .signers([proposalOwner])
@@ -437,4 +482,85 @@ describe("create_proposal", async () => {
       web3.LAMPORTS_PER_SOL * 0
     );
   });
+
+  it("[close_proposal] should: proposal owner closes a withdrawn proposal and reclaims the rent", async () => {
+    const balanceBefore = await provider.connection.getBalance(
+      proposalOwner.publicKey
+    );
+
+    const tx = await program.methods
+      .closeProposal({ id: proposalId })
+      .accounts({
+        swapProposal,
+        proposalOwner: proposalOwner.publicKey,
+      })
+      .signers([proposalOwner])
+      .rpc({ commitment: "confirmed" });
+
+    expect(await provider.connection.getAccountInfo(swapProposal)).to.be.null;
+    expect(
+      await provider.connection.getBalance(proposalOwner.publicKey)
+    ).gt(balanceBefore);
+
+    // expect log
+    const transaction = await provider.connection.getParsedTransaction(tx, {
+      commitment: "confirmed",
+    });
+    const eventParser = new EventParser(
+      program.programId,
+      new BorshCoder(program.idl)
+    );
+    const [event] = eventParser.parseLogs(transaction.meta.logMessages);
+    expect(event.name).equals("ProposalClosed");
+    expect(event.data.id).equals(proposalId);
+  });
+
+  it("[close_proposal] should: proposal owner closes a canceled proposal whose items were never deposited", async () => {
+    const id = Keypair.generate().publicKey.toBase58().slice(0, 10);
+    const [canceledProposal] = await PublicKey.findProgramAddress(
+      [
+        anchor.utils.bytes.utf8.encode("SEED::SWAP::PROPOSAL_SEED"),
+        proposalOwner.publicKey.toBuffer(),
+        createHash("sha256").update(id).digest(),
+      ],
+      program.programId
+    );
+
+    await program.methods
+      .createProposal({
+        id,
+        swapOptions,
+        offeredItems,
+        expiredAt: new BN(new Date().getTime() + 1000 * 60 * 60 * 24 * 7),
+      })
+      .accounts({
+        proposalOwner: proposalOwner.publicKey,
+        swapRegistry,
+        swapTreasury,
+        swapProposal: canceledProposal,
+      })
+      .signers([proposalOwner])
+      .rpc({ commitment: "confirmed" });
+
+    await program.methods
+      .cancelProposal({ id })
+      .accounts({
+        swapProposal: canceledProposal,
+        signer: proposalOwner.publicKey,
+      })
+      .signers([proposalOwner])
+      .rpc({ commitment: "confirmed" });
+
+    await program.methods
+      .closeProposal({ id })
+      .accounts({
+        swapProposal: canceledProposal,
+        proposalOwner: proposalOwner.publicKey,
+      })
+      .signers([proposalOwner])
+      .rpc({ commitment: "confirmed" });
+
+    expect(await provider.connection.getAccountInfo(canceledProposal)).to.be
+      .null;
+  });
 });
@@ -1,10 +1,10 @@
 import * as anchor from "@project-serum/anchor";
-import { Program } from "@project-serum/anchor";
+import { Program, web3 } from "@project-serum/anchor";
 import { Keypair, PublicKey, SendTransactionError } from "@solana/web3.js";
 import { expect } from "chai";
 
 import { Swap } from "../target/types/swap";
-import { createMint } from "@solana/spl-token";
+import { createMint, getAccount } from "@solana/spl-token";
 
 describe("create_token_vault", async () => {
   // Configure the client to use the local cluster.
@@ -108,4 +108,128 @@ describe("create_token_vault", async () => {
       expect(e instanceof SendTransactionError).to.be.true;
     }
   });
+
+  it("[create_token_vault] should: deployer creates a token vault at an address already holding lamports", async () => {
+    const mintPublicKey = await createMint(
+      provider.connection, // conneciton
+      deployer.payer, // fee payer
+      deployer.publicKey, // mint authority
+      deployer.publicKey, // freeze authority
+      8 // decimals
+    );
+    const [tokenVault] = await PublicKey.findProgramAddress(
+      [
+        anchor.utils.bytes.utf8.encode("SEED::SWAP::TOKEN_VAULT_SEED"),
+        mintPublicKey.toBytes(),
+      ],
+      program.programId
+    );
+
+    // dust the vault address before it is created
+    await provider.sendAndConfirm(
+      new web3.Transaction().add(
+        web3.SystemProgram.transfer({
+          fromPubkey: deployer.publicKey,
+          toPubkey: tokenVault,
+          lamports: 1,
+        })
+      ),
+      [],
+      { commitment: "confirmed" }
+    );
+
+    await program.methods
+      .createTokenVault()
+      .accounts({
+        mintAccount: mintPublicKey,
+        swapRegistry,
+        swapTokenVault: tokenVault,
+        signer: deployer.publicKey,
+      })
+      .signers([deployer.payer])
+      .rpc({ commitment: "confirmed" });
+
+    const vault = await getAccount(
+      provider.connection,
+      tokenVault,
+      "confirmed"
+    );
+    expect(vault.mint.equals(mintPublicKey)).to.be.true;
+    expect(vault.owner.equals(swapRegistry)).to.be.true;
+  });
+
+  it("[create_token_vault] should: non-deployer creates a disabled token vault when approval is required", async () => {
+    // funding the other user
+    const airdropSignature = await provider.connection.requestAirdrop(
+      otherUser.publicKey,
+      web3.LAMPORTS_PER_SOL
+    );
+    await provider.connection.confirmTransaction({
+      signature: airdropSignature,
+      blockhash: (await provider.connection.getLatestBlockhash()).blockhash,
+      lastValidBlockHeight: (
+        await provider.connection.getLatestBlockhash()
+      ).lastValidBlockHeight,
+    });
+
+    const mintPublicKey = await createMint(
+      provider.connection, // conneciton
+      deployer.payer, // fee payer
+      deployer.publicKey, // mint authority
+      deployer.publicKey, // freeze authority
+      8 // decimals
+    );
+    const [tokenVault] = await PublicKey.findProgramAddress(
+      [
+        anchor.utils.bytes.utf8.encode("SEED::SWAP::TOKEN_VAULT_SEED"),
+        mintPublicKey.toBytes(),
+      ],
+      program.programId
+    );
+
+    await program.methods
+      .updateVaultCreationPolicy({
+        vaultCreationPolicy: { permissionlessWithApproval: {} },
+        vaultCreator: PublicKey.default,
+      })
+      .accounts({
+        swapRegistry,
+        owner: deployer.publicKey,
+      })
+      .signers([deployer.payer])
+      .rpc({ commitment: "confirmed" });
+
+    await program.methods
+      .createTokenVault()
+      .accounts({
+        mintAccount: mintPublicKey,
+        swapRegistry,
+        swapTokenVault: tokenVault,
+        signer: otherUser.publicKey,
+      })
+      .signers([otherUser])
+      .rpc({ commitment: "confirmed" });
+
+    const state = await program.account.swapPlatformRegistry.fetch(
+      swapRegistry
+    );
+    // @ts-ignore
+    const mintInfo = state.allowedMintAccounts.find((info) =>
+      info.mintAccount.equals(mintPublicKey)
+    );
+    expect(mintInfo.isEnabled).equals(false);
+
+    // restore the default policy for the next specs
+    await program.methods
+      .updateVaultCreationPolicy({
+        vaultCreationPolicy: { ownerOnly: {} },
+        vaultCreator: PublicKey.default,
+      })
+      .accounts({
+        swapRegistry,
+        owner: deployer.publicKey,
+      })
+      .signers([deployer.payer])
+      .rpc({ commitment: "confirmed" });
+  });
 });
@@ -0,0 +1,219 @@
+import * as anchor from "@project-serum/anchor";
+import { AnchorError, BN, Program, web3 } from "@project-serum/anchor";
+import { Keypair, PublicKey } from "@solana/web3.js";
+import {
+  createMint,
+  getAccount,
+  getOrCreateAssociatedTokenAccount,
+  mintTo,
+} from "@solana/spl-token";
+import { expect } from "chai";
+import { createHash } from "crypto";
+
+import { Swap } from "../target/types/swap";
+
+describe("deposit_all", async () => {
+  // Configure the client to use the local cluster.
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.Swap as Program<Swap>;
+  const deployer = provider.wallet as anchor.Wallet;
+
+  // find the swap account
+  const [swapRegistry] = await PublicKey.findProgramAddress(
+    [anchor.utils.bytes.utf8.encode("SEED::SWAP::PLATFORM")],
+    program.programId
+  );
+  const [swapTreasury] = await PublicKey.findProgramAddress(
+    [anchor.utils.bytes.utf8.encode("SEED::SWAP::TREASURY_SEED")],
+    program.programId
+  );
+
+  const proposalOwner = Keypair.generate();
+  const proposalId = Keypair.generate().publicKey.toBase58().slice(0, 10);
+
+  let swapProposal;
+  let offeredItems;
+  let itemAccounts = [];
+
+  before(async () => {
+    // funding proposal owner
+    const airdropSignature = await provider.connection.requestAirdrop(
+      proposalOwner.publicKey,
+      web3.LAMPORTS_PER_SOL
+    );
+    await provider.connection.confirmTransaction({
+      signature: airdropSignature,
+      blockhash: (await provider.connection.getLatestBlockhash()).blockhash,
+      lastValidBlockHeight: (
+        await provider.connection.getLatestBlockhash()
+      ).lastValidBlockHeight,
+    });
+
+    // create and whitelist two mints
+    for (let i = 0; i < 2; i++) {
+      const mintAccount = await createMint(
+        provider.connection,
+        deployer.payer,
+        deployer.publicKey,
+        deployer.publicKey,
+        9
+      );
+
+      const tokenAccount = await getOrCreateAssociatedTokenAccount(
+        provider.connection,
+        proposalOwner,
+        mintAccount,
+        proposalOwner.publicKey
+      );
+      await mintTo(
+        provider.connection,
+        deployer.payer,
+        mintAccount,
+        tokenAccount.address,
+        deployer.publicKey,
+        web3.LAMPORTS_PER_SOL * 10
+      );
+
+      const [swapTokenVault, swapTokenVaultBump] =
+        await PublicKey.findProgramAddress(
+          [
+            anchor.utils.bytes.utf8.encode("SEED::SWAP::TOKEN_VAULT_SEED"),
+            mintAccount.toBytes(),
+          ],
+          program.programId
+        );
+      await program.methods
+        .createTokenVault()
+        .accounts({
+          signer: deployer.publicKey,
+          mintAccount,
+          swapRegistry,
+          swapTokenVault,
+        })
+        .signers([deployer.payer])
+        .rpc({ commitment: "confirmed" });
+
+      itemAccounts.push({
+        mintAccount,
+        tokenAccount: tokenAccount.address,
+        swapTokenVault,
+        swapTokenVaultBump,
+      });
+    }
+
+    [swapProposal] = await PublicKey.findProgramAddress(
+      [
+        anchor.utils.bytes.utf8.encode("SEED::SWAP::PROPOSAL_SEED"),
+        proposalOwner.publicKey.toBuffer(),
+        createHash("sha256").update(proposalId).digest(),
+      ],
+      program.programId
+    );
+
+    offeredItems = itemAccounts.map(({ mintAccount }) => ({
+      id: Keypair.generate().publicKey.toBase58().slice(0, 10),
+      mintAccount,
+      amount: new BN(web3.LAMPORTS_PER_SOL),
+      itemType: { currency: {} },
+    }));
+
+    await program.methods
+      .createProposal({
+        id: proposalId,
+        swapOptions: [
+          {
+            id: Keypair.generate().publicKey.toBase58().slice(0, 10),
+            askingItems: [
+              {
+                id: Keypair.generate().publicKey.toBase58().slice(0, 10),
+                mintAccount: itemAccounts[0].mintAccount,
+                amount: new BN(web3.LAMPORTS_PER_SOL * 2),
+                itemType: { currency: {} },
+              },
+            ],
+          },
+        ],
+        offeredItems,
+        expiredAt: new BN(new Date().getTime() + 1000 * 60 * 60 * 24 * 7),
+      })
+      .accounts({
+        proposalOwner: proposalOwner.publicKey,
+        swapRegistry,
+        swapTreasury,
+        swapProposal,
+      })
+      .signers([proposalOwner])
+      .rpc({ commitment: "confirmed" });
+  });
+
+  it("[deposit_all] should: fail when the item accounts are missing", async () => {
+    try {
+      await program.methods
+        .depositAll({
+          proposalId,
+          swapTokenVaultBumps: Buffer.from(
+            itemAccounts.map((x) => x.swapTokenVaultBump)
+          ),
+        })
+        .accounts({
+          signer: proposalOwner.publicKey,
+          swapProposal,
+          swapRegistry,
+        })
+        .remainingAccounts(
+          [itemAccounts[0]].flatMap((x) => [
+            { pubkey: x.mintAccount, isSigner: false, isWritable: false },
+            { pubkey: x.tokenAccount, isSigner: false, isWritable: true },
+            { pubkey: x.swapTokenVault, isSigner: false, isWritable: true },
+          ])
+        )
+        .signers([proposalOwner])
+        .rpc({ commitment: "confirmed" });
+
+      throw new Error("Should be failed");
+    } catch (e) {
+      expect(e instanceof AnchorError).to.be.true;
+      expect(e.error.errorCode.code).equals("InvalidTokenAccount");
+    }
+  });
+
+  it("[deposit_all] should: proposal owner deposits every offered item in one instruction", async () => {
+    await program.methods
+      .depositAll({
+        proposalId,
+        swapTokenVaultBumps: Buffer.from(
+          itemAccounts.map((x) => x.swapTokenVaultBump)
+        ),
+      })
+      .accounts({
+        signer: proposalOwner.publicKey,
+        swapProposal,
+        swapRegistry,
+      })
+      .remainingAccounts(
+        itemAccounts.flatMap((x) => [
+          { pubkey: x.mintAccount, isSigner: false, isWritable: false },
+          { pubkey: x.tokenAccount, isSigner: false, isWritable: true },
+          { pubkey: x.swapTokenVault, isSigner: false, isWritable: true },
+        ])
+      )
+      .signers([proposalOwner])
+      .rpc({ commitment: "confirmed" });
+
+    const state = await program.account.swapProposal.fetch(swapProposal);
+    // @ts-ignore
+    expect(!!state.status.deposited).to.be.true;
+    state.offeredItems.forEach((item) => {
+      // @ts-ignore
+      expect(!!item.status.deposited).to.be.true;
+      expect(item.owner.equals(proposalOwner.publicKey)).to.be.true;
+    });
+
+    for (const { tokenAccount } of itemAccounts) {
+      const account = await getAccount(provider.connection, tokenAccount);
+      expect(Number(account.amount)).eq(web3.LAMPORTS_PER_SOL * 9);
+    }
+  });
+});
@@ -1,7 +1,26 @@
 require("./initialize_swap_program.spec");
 require("./update_swap_registry.spec");
+require("./transfer_ownership.spec");
 require("./create_token_vault.spec");
+require("./mint_management.spec");
+require("./platform_fee.spec");
 require("./create_proposal.spec");
+require("./update_proposal.spec");
 require("./transfer_assets.spec");
+require("./proposal_vault.spec");
+require("./deposit_all.spec");
+require("./accept_proposal.spec");
+require("./transfer_all_assets.spec");
+require("./expire_proposal.spec");
+require("./crank_settle.spec");
+require("./release_reservation.spec");
+require("./withdraw_fulfillment.spec");
+require("./private_proposal.spec");
+require("./counter_offer.spec");
+require("./native_sol.spec");
+require("./token_2022.spec");
+require("./collection_ask.spec");
+require("./royalty.spec");
+require("./programmable_nft.spec");
 require("./lookup_table.spec");
 require("./modify_lookup_table.spec");
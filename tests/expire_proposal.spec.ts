@@ -0,0 +1,157 @@
+import * as anchor from "@project-serum/anchor";
+import { AnchorError, BN, Program, web3 } from "@project-serum/anchor";
+import { Keypair, PublicKey } from "@solana/web3.js";
+import { getAccount } from "@solana/spl-token";
+import { expect } from "chai";
+
+import { Swap } from "../target/types/swap";
+import { airdrop, createProposal, itemAccounts, setupMint } from "./fixtures";
+
+describe("expire_proposal", async () => {
+  // Configure the client to use the local cluster.
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.Swap as Program<Swap>;
+
+  // find the swap account
+  const [swapRegistry] = await PublicKey.findProgramAddress(
+    [anchor.utils.bytes.utf8.encode("SEED::SWAP::PLATFORM")],
+    program.programId
+  );
+
+  const proposalOwner = Keypair.generate();
+  const participant = Keypair.generate();
+  const proposalId = Keypair.generate().publicKey.toBase58().slice(0, 10);
+
+  let swapProposal;
+  let offeredMint;
+  let askingMint;
+
+  const expireProposal = () =>
+    program.methods
+      .expireProposal({ id: proposalId })
+      .accounts({
+        signer: participant.publicKey,
+        swapProposal,
+      })
+      .signers([participant])
+      .rpc({ commitment: "confirmed" });
+
+  before(async () => {
+    // funding the users
+    await airdrop([proposalOwner, participant]);
+
+    offeredMint = await setupMint([proposalOwner, participant]);
+    askingMint = await setupMint([proposalOwner, participant]);
+
+    // the proposal expires a few seconds from now
+    const { blockTime } = await provider.connection.getBlock(
+      await provider.connection.getSlot("confirmed")
+    );
+    swapProposal = await createProposal(proposalOwner, {
+      id: proposalId,
+      swapOptions: [
+        {
+          id: Keypair.generate().publicKey.toBase58().slice(0, 10),
+          askingItems: [
+            {
+              id: Keypair.generate().publicKey.toBase58().slice(0, 10),
+              mintAccount: askingMint.mintAccount,
+              amount: new BN(web3.LAMPORTS_PER_SOL * 2),
+              itemType: { currency: {} },
+            },
+          ],
+        },
+      ],
+      offeredItems: [
+        {
+          id: Keypair.generate().publicKey.toBase58().slice(0, 10),
+          mintAccount: offeredMint.mintAccount,
+          amount: new BN(web3.LAMPORTS_PER_SOL),
+          itemType: { currency: {} },
+        },
+      ],
+      expiredAt: new BN(blockTime + 10),
+    });
+
+    await program.methods
+      .depositAll({
+        proposalId,
+        swapTokenVaultBumps: Buffer.from([offeredMint.swapTokenVaultBump]),
+      })
+      .accounts({
+        signer: proposalOwner.publicKey,
+        swapProposal,
+        swapRegistry,
+      })
+      .remainingAccounts(
+        itemAccounts(
+          offeredMint,
+          offeredMint.tokenAccounts[proposalOwner.publicKey.toBase58()]
+        )
+      )
+      .signers([proposalOwner])
+      .rpc({ commitment: "confirmed" });
+  });
+
+  it("[expire_proposal] should: fail to expire a proposal still in its time window", async () => {
+    try {
+      await expireProposal();
+
+      throw new Error("Should be failed");
+    } catch (e) {
+      expect(e instanceof AnchorError).to.be.true;
+      expect(e.error.errorCode.code).equals("ProposalCannotBeExpired");
+    }
+  });
+
+  it("[expire_proposal] should: anyone expires the proposal once it passed its expiry", async () => {
+    // wait for the cluster clock to pass the expiry
+    for (let attempt = 0; ; attempt++) {
+      await new Promise((resolve) => setTimeout(resolve, 2000));
+
+      try {
+        await expireProposal();
+        break;
+      } catch (e) {
+        if (attempt >= 15) throw e;
+      }
+    }
+
+    const state = await program.account.swapProposal.fetch(swapProposal);
+    // @ts-ignore
+    expect(!!state.status.expired).to.be.true;
+  });
+
+  it("[withdraw_all] should: proposal owner withdraws the offered items of an expired proposal", async () => {
+    await program.methods
+      .withdrawAll({
+        proposalId,
+        swapTokenVaultBumps: Buffer.from([offeredMint.swapTokenVaultBump]),
+      })
+      .accounts({
+        signer: proposalOwner.publicKey,
+        swapRegistry,
+        swapProposal,
+      })
+      .remainingAccounts(
+        itemAccounts(
+          offeredMint,
+          offeredMint.tokenAccounts[proposalOwner.publicKey.toBase58()]
+        )
+      )
+      .signers([proposalOwner])
+      .rpc({ commitment: "confirmed" });
+
+    const state = await program.account.swapProposal.fetch(swapProposal);
+    // @ts-ignore
+    expect(!!state.status.withdrawn).to.be.true;
+
+    const ownerAccount = await getAccount(
+      provider.connection,
+      offeredMint.tokenAccounts[proposalOwner.publicKey.toBase58()]
+    );
+    expect(Number(ownerAccount.amount)).eq(web3.LAMPORTS_PER_SOL * 10);
+  });
+});
@@ -0,0 +1,164 @@
+import * as anchor from "@project-serum/anchor";
+import { BN, Program, web3 } from "@project-serum/anchor";
+import { Keypair, PublicKey } from "@solana/web3.js";
+import {
+  createMint,
+  getOrCreateAssociatedTokenAccount,
+  mintTo,
+} from "@solana/spl-token";
+import { createHash } from "crypto";
+
+import { Swap } from "../target/types/swap";
+
+// The fixtures shared by the specs, run against the provider configured by the spec
+const getProgram = () => anchor.workspace.Swap as Program<Swap>;
+const getProvider = () => anchor.getProvider() as anchor.AnchorProvider;
+
+export const findSwapRegistry = async () =>
+  (
+    await PublicKey.findProgramAddress(
+      [anchor.utils.bytes.utf8.encode("SEED::SWAP::PLATFORM")],
+      getProgram().programId
+    )
+  )[0];
+
+export const findSwapTreasury = async () =>
+  (
+    await PublicKey.findProgramAddress(
+      [anchor.utils.bytes.utf8.encode("SEED::SWAP::TREASURY_SEED")],
+      getProgram().programId
+    )
+  )[0];
+
+export const findSwapProposal = async (owner: PublicKey, proposalId: string) =>
+  (
+    await PublicKey.findProgramAddress(
+      [
+        anchor.utils.bytes.utf8.encode("SEED::SWAP::PROPOSAL_SEED"),
+        owner.toBuffer(),
+        createHash("sha256").update(proposalId).digest(),
+      ],
+      getProgram().programId
+    )
+  )[0];
+
+// swap items are passed as [mint_account, token_account, swap_token_vault]
+export const itemAccounts = (mint, tokenAccount) => [
+  { pubkey: mint.mintAccount, isSigner: false, isWritable: false },
+  { pubkey: tokenAccount, isSigner: false, isWritable: true },
+  { pubkey: mint.swapTokenVault, isSigner: false, isWritable: true },
+];
+
+// fund the users with lamports
+export const airdrop = async (
+  users: Keypair[],
+  lamports = web3.LAMPORTS_PER_SOL
+) => {
+  const provider = getProvider();
+
+  for (const user of users) {
+    const airdropSignature = await provider.connection.requestAirdrop(
+      user.publicKey,
+      lamports
+    );
+    await provider.connection.confirmTransaction({
+      signature: airdropSignature,
+      blockhash: (await provider.connection.getLatestBlockhash()).blockhash,
+      lastValidBlockHeight: (
+        await provider.connection.getLatestBlockhash()
+      ).lastValidBlockHeight,
+    });
+  }
+};
+
+// create the token vault of a mint, which whitelists the mint
+export const createTokenVault = async (mintAccount: PublicKey) => {
+  const program = getProgram();
+  const deployer = getProvider().wallet as anchor.Wallet;
+
+  const [swapTokenVault, swapTokenVaultBump] =
+    await PublicKey.findProgramAddress(
+      [
+        anchor.utils.bytes.utf8.encode("SEED::SWAP::TOKEN_VAULT_SEED"),
+        mintAccount.toBytes(),
+      ],
+      program.programId
+    );
+  await program.methods
+    .createTokenVault()
+    .accounts({
+      signer: deployer.publicKey,
+      mintAccount,
+      swapRegistry: await findSwapRegistry(),
+      swapTokenVault,
+    })
+    .signers([deployer.payer])
+    .rpc({ commitment: "confirmed" });
+
+  return { swapTokenVault, swapTokenVaultBump };
+};
+
+// create and whitelist a mint, then fund the holders
+export const setupMint = async (
+  holders: Keypair[],
+  decimals = 9,
+  amount = web3.LAMPORTS_PER_SOL * 10
+) => {
+  const provider = getProvider();
+  const deployer = provider.wallet as anchor.Wallet;
+
+  const mintAccount = await createMint(
+    provider.connection,
+    deployer.payer,
+    deployer.publicKey,
+    deployer.publicKey,
+    decimals
+  );
+
+  const tokenAccounts = {};
+  for (const user of holders) {
+    const tokenAccount = await getOrCreateAssociatedTokenAccount(
+      provider.connection,
+      user,
+      mintAccount,
+      user.publicKey
+    );
+    await mintTo(
+      provider.connection,
+      deployer.payer,
+      mintAccount,
+      tokenAccount.address,
+      deployer.publicKey,
+      amount
+    );
+    tokenAccounts[user.publicKey.toBase58()] = tokenAccount.address;
+  }
+
+  const { swapTokenVault, swapTokenVaultBump } = await createTokenVault(
+    mintAccount
+  );
+
+  return { mintAccount, tokenAccounts, swapTokenVault, swapTokenVaultBump };
+};
+
+// create a proposal of the owner, expiring in a week unless specified, and return its address
+export const createProposal = async (owner: Keypair, params) => {
+  const program = getProgram();
+  const swapProposal = await findSwapProposal(owner.publicKey, params.id);
+
+  await program.methods
+    .createProposal({
+      expiredAt: new BN(new Date().getTime() + 1000 * 60 * 60 * 24 * 7),
+      ...params,
+    })
+    .accounts({
+      proposalOwner: owner.publicKey,
+      swapRegistry: await findSwapRegistry(),
+      swapTreasury: await findSwapTreasury(),
+      swapProposal,
+    })
+    .signers([owner])
+    .rpc({ commitment: "confirmed" });
+
+  return swapProposal;
+};
@@ -0,0 +1,311 @@
+import * as anchor from "@project-serum/anchor";
+import {
+  ComputeBudgetProgram,
+  Keypair,
+  PublicKey,
+  SYSVAR_INSTRUCTIONS_PUBKEY,
+  Transaction,
+} from "@solana/web3.js";
+import {
+  ASSOCIATED_TOKEN_PROGRAM_ID,
+  TOKEN_PROGRAM_ID,
+  createMint,
+  getAssociatedTokenAddress,
+  getOrCreateAssociatedTokenAccount,
+  mintTo,
+} from "@solana/spl-token";
+import {
+  PROGRAM_ID as TOKEN_METADATA_PROGRAM_ID,
+  TokenStandard,
+  createCreateInstruction,
+  createCreateMasterEditionV3Instruction,
+  createCreateMetadataAccountV3Instruction,
+  createMintInstruction,
+  createSignMetadataInstruction,
+  createVerifyCollectionInstruction,
+} from "@metaplex-foundation/mpl-token-metadata";
+
+// The Token Metadata fixtures, the program is loaded at genesis by Anchor.toml
+const getProvider = () => anchor.getProvider() as anchor.AnchorProvider;
+
+// programmable NFT transfers exceed the default compute budget
+export const programmableComputeBudget = () =>
+  ComputeBudgetProgram.setComputeUnitLimit({ units: 400_000 });
+
+export const findMetadataAddress = async (mint: PublicKey) =>
+  (
+    await PublicKey.findProgramAddress(
+      [
+        Buffer.from("metadata"),
+        TOKEN_METADATA_PROGRAM_ID.toBuffer(),
+        mint.toBuffer(),
+      ],
+      TOKEN_METADATA_PROGRAM_ID
+    )
+  )[0];
+
+export const findMasterEditionAddress = async (mint: PublicKey) =>
+  (
+    await PublicKey.findProgramAddress(
+      [
+        Buffer.from("metadata"),
+        TOKEN_METADATA_PROGRAM_ID.toBuffer(),
+        mint.toBuffer(),
+        Buffer.from("edition"),
+      ],
+      TOKEN_METADATA_PROGRAM_ID
+    )
+  )[0];
+
+export const findTokenRecordAddress = async (
+  mint: PublicKey,
+  tokenAccount: PublicKey
+) =>
+  (
+    await PublicKey.findProgramAddress(
+      [
+        Buffer.from("metadata"),
+        TOKEN_METADATA_PROGRAM_ID.toBuffer(),
+        mint.toBuffer(),
+        Buffer.from("token_record"),
+        tokenAccount.toBuffer(),
+      ],
+      TOKEN_METADATA_PROGRAM_ID
+    )
+  )[0];
+
+// the metadata account of a mint, passed to look up royalties and collections
+export const metadataAccount = async (mint: PublicKey) => ({
+  pubkey: await findMetadataAddress(mint),
+  isSigner: false,
+  isWritable: false,
+});
+
+// the Token Metadata transfer accounts of a programmable NFT moving from the source to the destination token account
+export const programmableAccounts = async (
+  mint: PublicKey,
+  source: PublicKey,
+  destination: PublicKey
+) => [
+  {
+    pubkey: await findMetadataAddress(mint),
+    isSigner: false,
+    isWritable: true,
+  },
+  {
+    pubkey: await findMasterEditionAddress(mint),
+    isSigner: false,
+    isWritable: false,
+  },
+  {
+    pubkey: await findTokenRecordAddress(mint, source),
+    isSigner: false,
+    isWritable: true,
+  },
+  {
+    pubkey: await findTokenRecordAddress(mint, destination),
+    isSigner: false,
+    isWritable: true,
+  },
+  { pubkey: SYSVAR_INSTRUCTIONS_PUBKEY, isSigner: false, isWritable: false },
+  {
+    pubkey: ASSOCIATED_TOKEN_PROGRAM_ID,
+    isSigner: false,
+    isWritable: false,
+  },
+  { pubkey: TOKEN_METADATA_PROGRAM_ID, isSigner: false, isWritable: false },
+];
+
+// mint an NFT with its metadata and master edition to the holder,
+// the creators sign the metadata and the collection is verified by the deployer
+export const createNft = async (
+  holder: Keypair,
+  {
+    sellerFeeBasisPoints = 0,
+    creators = [],
+    collectionMint = null,
+  }: {
+    sellerFeeBasisPoints?: number;
+    creators?: { creator: Keypair; share: number }[];
+    collectionMint?: PublicKey;
+  } = {}
+) => {
+  const provider = getProvider();
+  const deployer = provider.wallet as anchor.Wallet;
+
+  const mintAccount = await createMint(
+    provider.connection,
+    deployer.payer,
+    deployer.publicKey,
+    deployer.publicKey,
+    0
+  );
+  const tokenAccount = await getOrCreateAssociatedTokenAccount(
+    provider.connection,
+    holder,
+    mintAccount,
+    holder.publicKey
+  );
+  await mintTo(
+    provider.connection,
+    deployer.payer,
+    mintAccount,
+    tokenAccount.address,
+    deployer.publicKey,
+    1
+  );
+
+  const metadata = await findMetadataAddress(mintAccount);
+  const transaction = new Transaction().add(
+    createCreateMetadataAccountV3Instruction(
+      {
+        metadata,
+        mint: mintAccount,
+        mintAuthority: deployer.publicKey,
+        payer: deployer.publicKey,
+        updateAuthority: deployer.publicKey,
+      },
+      {
+        createMetadataAccountArgsV3: {
+          data: {
+            name: "Hamster",
+            symbol: "HAM",
+            uri: "https://hamsterswap.xyz",
+            sellerFeeBasisPoints,
+            creators: creators.length
+              ? creators.map(({ creator, share }) => ({
+                  address: creator.publicKey,
+                  verified: false,
+                  share,
+                }))
+              : null,
+            collection: collectionMint
+              ? { verified: false, key: collectionMint }
+              : null,
+            uses: null,
+          },
+          isMutable: true,
+          collectionDetails: null,
+        },
+      }
+    ),
+    createCreateMasterEditionV3Instruction(
+      {
+        edition: await findMasterEditionAddress(mintAccount),
+        mint: mintAccount,
+        updateAuthority: deployer.publicKey,
+        mintAuthority: deployer.publicKey,
+        payer: deployer.publicKey,
+        metadata,
+      },
+      { createMasterEditionArgs: { maxSupply: 0 } }
+    ),
+    ...creators.map(({ creator }) =>
+      createSignMetadataInstruction({ metadata, creator: creator.publicKey })
+    )
+  );
+
+  if (collectionMint) {
+    transaction.add(
+      createVerifyCollectionInstruction({
+        metadata,
+        collectionAuthority: deployer.publicKey,
+        payer: deployer.publicKey,
+        collectionMint,
+        collection: await findMetadataAddress(collectionMint),
+        collectionMasterEditionAccount: await findMasterEditionAddress(
+          collectionMint
+        ),
+      })
+    );
+  }
+
+  await provider.sendAndConfirm(
+    transaction,
+    creators.map(({ creator }) => creator),
+    { commitment: "confirmed" }
+  );
+
+  return { mintAccount, tokenAccount: tokenAccount.address };
+};
+
+// mint a programmable NFT without rule set to the holder, the token account is frozen by the Token Metadata program
+export const createProgrammableNft = async (holder: Keypair) => {
+  const provider = getProvider();
+  const deployer = provider.wallet as anchor.Wallet;
+
+  const mint = Keypair.generate();
+  const metadata = await findMetadataAddress(mint.publicKey);
+  const masterEdition = await findMasterEditionAddress(mint.publicKey);
+  const tokenAccount = await getAssociatedTokenAddress(
+    mint.publicKey,
+    holder.publicKey
+  );
+
+  const createInstruction = createCreateInstruction(
+    {
+      metadata,
+      masterEdition,
+      mint: mint.publicKey,
+      authority: deployer.publicKey,
+      payer: deployer.publicKey,
+      updateAuthority: deployer.publicKey,
+      sysvarInstructions: SYSVAR_INSTRUCTIONS_PUBKEY,
+      splTokenProgram: TOKEN_PROGRAM_ID,
+    },
+    {
+      createArgs: {
+        __kind: "V1",
+        assetData: {
+          name: "Hamster",
+          symbol: "HAM",
+          uri: "https://hamsterswap.xyz",
+          sellerFeeBasisPoints: 0,
+          creators: null,
+          primarySaleHappened: false,
+          isMutable: true,
+          tokenStandard: TokenStandard.ProgrammableNonFungible,
+          collection: null,
+          uses: null,
+          collectionDetails: null,
+          ruleSet: null,
+        },
+        decimals: 0,
+        printSupply: { __kind: "Zero" },
+      },
+    }
+  );
+  // the mint is initialized by the create instruction
+  createInstruction.keys
+    .filter((key) => key.pubkey.equals(mint.publicKey))
+    .forEach((key) => {
+      key.isSigner = true;
+      key.isWritable = true;
+    });
+
+  const transaction = new Transaction().add(
+    programmableComputeBudget(),
+    createInstruction,
+    createMintInstruction(
+      {
+        token: tokenAccount,
+        tokenOwner: holder.publicKey,
+        metadata,
+        masterEdition,
+        tokenRecord: await findTokenRecordAddress(mint.publicKey, tokenAccount),
+        mint: mint.publicKey,
+        authority: deployer.publicKey,
+        payer: deployer.publicKey,
+        sysvarInstructions: SYSVAR_INSTRUCTIONS_PUBKEY,
+        splTokenProgram: TOKEN_PROGRAM_ID,
+        splAtaProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+      },
+      { mintArgs: { __kind: "V1", amount: 1, authorizationData: null } }
+    )
+  );
+  await provider.sendAndConfirm(transaction, [mint], {
+    commitment: "confirmed",
+  });
+
+  return { mintAccount: mint.publicKey, tokenAccount };
+};
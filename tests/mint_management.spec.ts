@@ -0,0 +1,133 @@
+import * as anchor from "@project-serum/anchor";
+import { AnchorError, Program } from "@project-serum/anchor";
+import { Keypair, PublicKey } from "@solana/web3.js";
+import { expect } from "chai";
+
+import { Swap } from "../target/types/swap";
+import { createMint } from "@solana/spl-token";
+
+describe("mint_management", async () => {
+  // Configure the client to use the local cluster.
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.Swap as Program<Swap>;
+  const deployer = provider.wallet as anchor.Wallet;
+  const otherUser = Keypair.generate();
+
+  // find the swap account
+  const [swapRegistry] = await PublicKey.findProgramAddress(
+    [anchor.utils.bytes.utf8.encode("SEED::SWAP::PLATFORM")],
+    program.programId
+  );
+
+  let mintNormalPublicKey;
+  let swapTokenVault;
+
+  const findMintInfo = async () => {
+    const state = await program.account.swapPlatformRegistry.fetch(
+      swapRegistry
+    );
+    // @ts-ignore
+    return state.allowedMintAccounts.find((mintInfo) =>
+      mintInfo.mintAccount.equals(mintNormalPublicKey)
+    );
+  };
+
+  before(async () => {
+    // now we try to create token vault for the mint token
+    mintNormalPublicKey = await createMint(
+      provider.connection, // conneciton
+      deployer.payer, // fee payer
+      deployer.publicKey, // mint authority
+      deployer.publicKey, // freeze authority
+      8 // decimals
+    );
+
+    [swapTokenVault] = await PublicKey.findProgramAddress(
+      [
+        anchor.utils.bytes.utf8.encode("SEED::SWAP::TOKEN_VAULT_SEED"),
+        mintNormalPublicKey.toBytes(),
+      ],
+      program.programId
+    );
+
+    await program.methods
+      .createTokenVault()
+      .accounts({
+        mintAccount: mintNormalPublicKey,
+        swapRegistry,
+        swapTokenVault,
+        signer: deployer.publicKey,
+      })
+      .signers([deployer.payer])
+      .rpc({ commitment: "confirmed" });
+  });
+
+  it("[mint_management] should: non-owner fails to disable a mint", async () => {
+    try {
+      await program.methods
+        .setMintEnabled({
+          mintAccount: mintNormalPublicKey,
+          isEnabled: false,
+        })
+        .accounts({
+          swapRegistry,
+          owner: otherUser.publicKey,
+        })
+        .signers([otherUser])
+        .rpc({ commitment: "confirmed" });
+
+      throw new Error("Should be failed");
+    } catch (e) {
+      expect(e instanceof AnchorError).to.be.true;
+    }
+  });
+
+  it("[mint_management] should: deployer disables and re-enables a mint successfully", async () => {
+    await program.methods
+      .setMintEnabled({
+        mintAccount: mintNormalPublicKey,
+        isEnabled: false,
+      })
+      .accounts({
+        swapRegistry,
+        owner: deployer.publicKey,
+      })
+      .signers([deployer.payer])
+      .rpc({ commitment: "confirmed" });
+
+    expect((await findMintInfo()).isEnabled).equals(false);
+
+    await program.methods
+      .setMintEnabled({
+        mintAccount: mintNormalPublicKey,
+        isEnabled: true,
+      })
+      .accounts({
+        swapRegistry,
+        owner: deployer.publicKey,
+      })
+      .signers([deployer.payer])
+      .rpc({ commitment: "confirmed" });
+
+    expect((await findMintInfo()).isEnabled).equals(true);
+  });
+
+  it("[mint_management] should: deployer removes a mint with an empty vault successfully", async () => {
+    await program.methods
+      .removeMint()
+      .accounts({
+        mintAccount: mintNormalPublicKey,
+        swapRegistry,
+        swapTokenVault,
+        owner: deployer.publicKey,
+      })
+      .signers([deployer.payer])
+      .rpc({ commitment: "confirmed" });
+
+    expect(await findMintInfo()).to.be.undefined;
+    expect(await provider.connection.getAccountInfo(swapTokenVault)).to.be
+      .null;
+  });
+});
@@ -0,0 +1,330 @@
+import * as anchor from "@project-serum/anchor";
+import { AnchorError, BN, Program, web3 } from "@project-serum/anchor";
+import { Keypair, PublicKey } from "@solana/web3.js";
+import { expect } from "chai";
+
+import { Swap } from "../target/types/swap";
+import {
+  airdrop,
+  createProposal,
+  findSwapProposal,
+  itemAccounts,
+  setupMint,
+} from "./fixtures";
+
+describe("native_sol", async () => {
+  // Configure the client to use the local cluster.
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.Swap as Program<Swap>;
+  const deployer = provider.wallet as anchor.Wallet;
+
+  // find the swap account
+  const [swapRegistry] = await PublicKey.findProgramAddress(
+    [anchor.utils.bytes.utf8.encode("SEED::SWAP::PLATFORM")],
+    program.programId
+  );
+  const [swapTreasury] = await PublicKey.findProgramAddress(
+    [anchor.utils.bytes.utf8.encode("SEED::SWAP::TREASURY_SEED")],
+    program.programId
+  );
+
+  const proposalOwner = Keypair.generate();
+  const participant = Keypair.generate();
+  const proposalId = Keypair.generate().publicKey.toBase58().slice(0, 10);
+  const optionId = Keypair.generate().publicKey.toBase58().slice(0, 10);
+
+  let swapProposal;
+  let askingMint;
+  let nativeVault;
+  let nativeVaultBump;
+
+  // native SOL items are passed as [system_program, wallet, native_vault]
+  const nativeItemAccounts = (wallet: PublicKey) => [
+    {
+      pubkey: web3.SystemProgram.programId,
+      isSigner: false,
+      isWritable: false,
+    },
+    { pubkey: wallet, isSigner: false, isWritable: true },
+    { pubkey: nativeVault, isSigner: false, isWritable: true },
+  ];
+
+  before(async () => {
+    // funding the users
+    await airdrop([proposalOwner, participant], web3.LAMPORTS_PER_SOL * 2);
+
+    askingMint = await setupMint([proposalOwner, participant]);
+
+    swapProposal = await findSwapProposal(proposalOwner.publicKey, proposalId);
+    [nativeVault, nativeVaultBump] = await PublicKey.findProgramAddress(
+      [
+        anchor.utils.bytes.utf8.encode("SEED::SWAP::NATIVE_VAULT_SEED"),
+        swapProposal.toBuffer(),
+      ],
+      program.programId
+    );
+
+    await createProposal(proposalOwner, {
+      id: proposalId,
+      swapOptions: [
+        {
+          id: optionId,
+          askingItems: [
+            {
+              id: Keypair.generate().publicKey.toBase58().slice(0, 10),
+              mintAccount: askingMint.mintAccount,
+              amount: new BN(web3.LAMPORTS_PER_SOL * 2),
+              itemType: { currency: {} },
+            },
+          ],
+        },
+      ],
+      offeredItems: [
+        {
+          id: Keypair.generate().publicKey.toBase58().slice(0, 10),
+          mintAccount: PublicKey.default,
+          amount: new BN(web3.LAMPORTS_PER_SOL / 2),
+          itemType: { nativeSol: {} },
+        },
+      ],
+    });
+  });
+
+  it("[native_sol] should: fail to deposit into a vault of another proposal", async () => {
+    try {
+      await program.methods
+        .depositAll({
+          proposalId,
+          swapTokenVaultBumps: Buffer.from([askingMint.swapTokenVaultBump]),
+        })
+        .accounts({
+          signer: proposalOwner.publicKey,
+          swapProposal,
+          swapRegistry,
+        })
+        .remainingAccounts([
+          {
+            pubkey: web3.SystemProgram.programId,
+            isSigner: false,
+            isWritable: false,
+          },
+          {
+            pubkey: proposalOwner.publicKey,
+            isSigner: false,
+            isWritable: true,
+          },
+          {
+            pubkey: askingMint.swapTokenVault,
+            isSigner: false,
+            isWritable: true,
+          },
+        ])
+        .signers([proposalOwner])
+        .rpc({ commitment: "confirmed" });
+
+      throw new Error("Should be failed");
+    } catch (e) {
+      expect(e instanceof AnchorError).to.be.true;
+      expect(e.error.errorCode.code).equals("InvalidVault");
+    }
+  });
+
+  it("[native_sol] should: proposal owner escrows native SOL without wrapping", async () => {
+    await program.methods
+      .depositAll({
+        proposalId,
+        swapTokenVaultBumps: Buffer.from([nativeVaultBump]),
+      })
+      .accounts({
+        signer: proposalOwner.publicKey,
+        swapProposal,
+        swapRegistry,
+      })
+      .remainingAccounts(nativeItemAccounts(proposalOwner.publicKey))
+      .signers([proposalOwner])
+      .rpc({ commitment: "confirmed" });
+
+    const state = await program.account.swapProposal.fetch(swapProposal);
+    // @ts-ignore
+    expect(!!state.status.deposited).to.be.true;
+
+    const rentMinimum =
+      await provider.connection.getMinimumBalanceForRentExemption(0);
+    expect(await provider.connection.getBalance(nativeVault)).eq(
+      web3.LAMPORTS_PER_SOL / 2 + rentMinimum
+    );
+  });
+
+  it("[native_sol] should: participant redeems the native SOL leg and the vault is emptied", async () => {
+    const balanceBefore = await provider.connection.getBalance(
+      participant.publicKey
+    );
+    const proposalBalanceBefore = await provider.connection.getBalance(
+      swapProposal
+    );
+
+    await program.methods
+      .acceptProposal({
+        proposalId,
+        optionId,
+        askingVaultBumps: Buffer.from([askingMint.swapTokenVaultBump]),
+        offeredVaultBumps: Buffer.from([nativeVaultBump]),
+        settleToOwner: false,
+      })
+      .accounts({
+        signer: participant.publicKey,
+        swapProposal,
+        swapRegistry,
+        swapTreasury,
+      })
+      .remainingAccounts([
+        ...itemAccounts(
+          askingMint,
+          askingMint.tokenAccounts[participant.publicKey.toBase58()]
+        ),
+        ...nativeItemAccounts(participant.publicKey),
+      ])
+      .signers([participant])
+      .rpc({ commitment: "confirmed" });
+
+    const state = await program.account.swapProposal.fetch(swapProposal);
+    // @ts-ignore
+    expect(!!state.offeredItems[0].status.redeemed).to.be.true;
+
+    // the participant receives the SOL leg
+    const balanceAfter = await provider.connection.getBalance(
+      participant.publicKey
+    );
+    expect(balanceAfter).gt(balanceBefore + web3.LAMPORTS_PER_SOL / 4);
+    expect(await provider.connection.getBalance(nativeVault)).eq(0);
+
+    // the rent of the emptied vault is held by the proposal for the proposal owner
+    const rentMinimum =
+      await provider.connection.getMinimumBalanceForRentExemption(0);
+    expect(await provider.connection.getBalance(swapProposal)).eq(
+      proposalBalanceBefore + rentMinimum
+    );
+  });
+
+  it("[native_sol] should: proposal owner deposits into a dusted native vault and withdraws native SOL one item at a time", async () => {
+    const id = Keypair.generate().publicKey.toBase58().slice(0, 10);
+    const itemId = Keypair.generate().publicKey.toBase58().slice(0, 10);
+    const singleItemProposal = await findSwapProposal(
+      proposalOwner.publicKey,
+      id
+    );
+    const [singleItemVault, singleItemVaultBump] =
+      await PublicKey.findProgramAddress(
+        [
+          anchor.utils.bytes.utf8.encode("SEED::SWAP::NATIVE_VAULT_SEED"),
+          singleItemProposal.toBuffer(),
+        ],
+        program.programId
+      );
+
+    await createProposal(proposalOwner, {
+      id,
+      swapOptions: [
+        {
+          id: Keypair.generate().publicKey.toBase58().slice(0, 10),
+          askingItems: [
+            {
+              id: Keypair.generate().publicKey.toBase58().slice(0, 10),
+              mintAccount: askingMint.mintAccount,
+              amount: new BN(web3.LAMPORTS_PER_SOL),
+              itemType: { currency: {} },
+            },
+          ],
+        },
+      ],
+      offeredItems: [
+        {
+          id: itemId,
+          mintAccount: PublicKey.default,
+          amount: new BN(web3.LAMPORTS_PER_SOL / 4),
+          itemType: { nativeSol: {} },
+        },
+      ],
+    });
+
+    // dust the native vault before the first deposit, the deposit only tops it up to its rent
+    await provider.sendAndConfirm(
+      new web3.Transaction().add(
+        web3.SystemProgram.transfer({
+          fromPubkey: deployer.publicKey,
+          toPubkey: singleItemVault,
+          lamports: 1,
+        })
+      ),
+      [],
+      { commitment: "confirmed" }
+    );
+
+    // native SOL items take [system_program, wallet, native_vault] as the mint, the token account and the vault
+    await program.methods
+      // @ts-ignore
+      .transferAssetsToVault({
+        proposalId: id,
+        swapItemId: itemId,
+        swapTokenVaultBump: singleItemVaultBump,
+        actionType: { depositing: {} },
+        optionId: "",
+      })
+      .accounts({
+        signer: proposalOwner.publicKey,
+        signerTokenAccount: proposalOwner.publicKey,
+        swapProposal: singleItemProposal,
+        swapTokenVault: singleItemVault,
+        swapRegistry,
+        swapTreasury,
+        mintAccount: web3.SystemProgram.programId,
+      })
+      .signers([proposalOwner])
+      .rpc({ commitment: "confirmed" });
+
+    let state = await program.account.swapProposal.fetch(singleItemProposal);
+    // @ts-ignore
+    expect(!!state.status.deposited).to.be.true;
+
+    const rentMinimum =
+      await provider.connection.getMinimumBalanceForRentExemption(0);
+    expect(await provider.connection.getBalance(singleItemVault)).eq(
+      web3.LAMPORTS_PER_SOL / 4 + rentMinimum
+    );
+
+    await program.methods
+      .cancelProposal({ id })
+      .accounts({
+        swapProposal: singleItemProposal,
+        signer: proposalOwner.publicKey,
+      })
+      .signers([proposalOwner])
+      .rpc({ commitment: "confirmed" });
+
+    await program.methods
+      // @ts-ignore
+      .transferAssetsFromVault({
+        proposalId: id,
+        swapItemId: itemId,
+        swapTokenVaultBump: singleItemVaultBump,
+        actionType: { withdrawing: {} },
+      })
+      .accounts({
+        signer: proposalOwner.publicKey,
+        signerTokenAccount: proposalOwner.publicKey,
+        swapProposal: singleItemProposal,
+        swapTokenVault: singleItemVault,
+        swapRegistry,
+        mintAccount: web3.SystemProgram.programId,
+      })
+      .signers([proposalOwner])
+      .rpc({ commitment: "confirmed" });
+
+    state = await program.account.swapProposal.fetch(singleItemProposal);
+    // @ts-ignore
+    expect(!!state.status.withdrawn).to.be.true;
+    expect(await provider.connection.getBalance(singleItemVault)).eq(0);
+  });
+});
@@ -0,0 +1,100 @@
+import * as anchor from "@project-serum/anchor";
+import { AnchorError, BN, Program, web3 } from "@project-serum/anchor";
+import { Keypair, PublicKey } from "@solana/web3.js";
+import { expect } from "chai";
+
+import { Swap } from "../target/types/swap";
+
+describe("platform_fee", async () => {
+  // Configure the client to use the local cluster.
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.Swap as Program<Swap>;
+  const deployer = provider.wallet as anchor.Wallet;
+  const otherUser = Keypair.generate();
+
+  // find the swap account
+  const [swapRegistry] = await PublicKey.findProgramAddress(
+    [anchor.utils.bytes.utf8.encode("SEED::SWAP::PLATFORM")],
+    program.programId
+  );
+  const [swapTreasury] = await PublicKey.findProgramAddress(
+    [anchor.utils.bytes.utf8.encode("SEED::SWAP::TREASURY_SEED")],
+    program.programId
+  );
+
+  it("[platform_fee] should: non-owner fails to update the fee config", async () => {
+    try {
+      await program.methods
+        .updateFeeConfig({
+          feeRecipient: otherUser.publicKey,
+          proposalCreationFee: new BN(web3.LAMPORTS_PER_SOL),
+          fulfillmentFee: new BN(web3.LAMPORTS_PER_SOL),
+        })
+        .accounts({
+          swapRegistry,
+          swapTreasury,
+          owner: otherUser.publicKey,
+        })
+        .signers([otherUser])
+        .rpc({ commitment: "confirmed" });
+
+      throw new Error("Should be failed");
+    } catch (e) {
+      expect(e instanceof AnchorError).to.be.true;
+    }
+  });
+
+  it("[platform_fee] should: deployer updates the fee config successfully", async () => {
+    await program.methods
+      .updateFeeConfig({
+        feeRecipient: deployer.publicKey,
+        proposalCreationFee: new BN(0),
+        fulfillmentFee: new BN(0),
+      })
+      .accounts({
+        swapRegistry,
+        swapTreasury,
+        owner: deployer.publicKey,
+      })
+      .signers([deployer.payer])
+      .rpc({ commitment: "confirmed" });
+
+    const state = await program.account.swapPlatformRegistry.fetch(
+      swapRegistry
+    );
+    expect(state.feeConfig.feeRecipient.equals(deployer.publicKey)).to.be.true;
+    expect(state.feeConfig.proposalCreationFee.eq(new BN(0))).to.be.true;
+    expect(state.feeConfig.fulfillmentFee.eq(new BN(0))).to.be.true;
+
+    // the treasury must be rent exempt
+    const rentExemptMinimum =
+      await provider.connection.getMinimumBalanceForRentExemption(0);
+    expect(await provider.connection.getBalance(swapTreasury)).gte(
+      rentExemptMinimum
+    );
+  });
+
+  it("[platform_fee] should: deployer fails to withdraw more than the collected fees", async () => {
+    try {
+      await program.methods
+        .withdrawPlatformFees({
+          amount: new BN(web3.LAMPORTS_PER_SOL * 1000),
+        })
+        .accounts({
+          swapRegistry,
+          swapTreasury,
+          feeRecipient: deployer.publicKey,
+          owner: deployer.publicKey,
+        })
+        .signers([deployer.payer])
+        .rpc({ commitment: "confirmed" });
+
+      throw new Error("Should be failed");
+    } catch (e) {
+      expect(e instanceof AnchorError).to.be.true;
+      expect(e.error.errorCode.code).equals("InsufficientAccruedFees");
+    }
+  });
+});
@@ -0,0 +1,283 @@
+import * as anchor from "@project-serum/anchor";
+import { BN, Program, web3 } from "@project-serum/anchor";
+import { Keypair, PublicKey } from "@solana/web3.js";
+import {
+  getAccount,
+  getOrCreateAssociatedTokenAccount,
+} from "@solana/spl-token";
+import { expect } from "chai";
+
+import { Swap } from "../target/types/swap";
+import {
+  airdrop,
+  createProposal,
+  createTokenVault,
+  itemAccounts,
+  setupMint,
+} from "./fixtures";
+import {
+  createProgrammableNft,
+  findTokenRecordAddress,
+  programmableAccounts,
+  programmableComputeBudget,
+} from "./metadata";
+
+describe("programmable_nft", async () => {
+  // Configure the client to use the local cluster.
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.Swap as Program<Swap>;
+
+  // find the swap account
+  const [swapRegistry] = await PublicKey.findProgramAddress(
+    [anchor.utils.bytes.utf8.encode("SEED::SWAP::PLATFORM")],
+    program.programId
+  );
+  const [swapTreasury] = await PublicKey.findProgramAddress(
+    [anchor.utils.bytes.utf8.encode("SEED::SWAP::TREASURY_SEED")],
+    program.programId
+  );
+
+  const proposalOwner = Keypair.generate();
+  const participant = Keypair.generate();
+  const optionId = Keypair.generate().publicKey.toBase58().slice(0, 10);
+
+  let currencyMint;
+
+  // mint a programmable NFT to the proposal owner and whitelist it
+  const setupProgrammableNft = async () => {
+    const nft = await createProgrammableNft(proposalOwner);
+    const { swapTokenVault, swapTokenVaultBump } = await createTokenVault(
+      nft.mintAccount
+    );
+
+    return { ...nft, swapTokenVault, swapTokenVaultBump };
+  };
+
+  // the proposal owner offers a programmable NFT for a currency leg
+  const createNftProposal = async (nft) => {
+    const proposalId = Keypair.generate().publicKey.toBase58().slice(0, 10);
+    const nftItemId = Keypair.generate().publicKey.toBase58().slice(0, 10);
+    const currencyItemId = Keypair.generate().publicKey.toBase58().slice(0, 10);
+
+    const swapProposal = await createProposal(proposalOwner, {
+      id: proposalId,
+      swapOptions: [
+        {
+          id: optionId,
+          askingItems: [
+            {
+              id: currencyItemId,
+              mintAccount: currencyMint.mintAccount,
+              amount: new BN(web3.LAMPORTS_PER_SOL),
+              itemType: { currency: {} },
+            },
+          ],
+        },
+      ],
+      offeredItems: [
+        {
+          id: nftItemId,
+          mintAccount: nft.mintAccount,
+          amount: new BN(1),
+          itemType: { nft: {} },
+        },
+      ],
+    });
+
+    return { proposalId, swapProposal, nftItemId, currencyItemId };
+  };
+
+  // move the programmable NFT out of the vault to the token account of the signer
+  const transferNftFromVault = async (
+    nft,
+    proposal,
+    signer: Keypair,
+    actionType
+  ) => {
+    const signerTokenAccount = await getOrCreateAssociatedTokenAccount(
+      provider.connection,
+      signer,
+      nft.mintAccount,
+      signer.publicKey
+    );
+
+    await program.methods
+      // @ts-ignore
+      .transferAssetsFromVault({
+        proposalId: proposal.proposalId,
+        swapItemId: proposal.nftItemId,
+        swapTokenVaultBump: nft.swapTokenVaultBump,
+        actionType,
+      })
+      .accounts({
+        signer: signer.publicKey,
+        signerTokenAccount: signerTokenAccount.address,
+        swapProposal: proposal.swapProposal,
+        swapTokenVault: nft.swapTokenVault,
+        swapRegistry,
+        mintAccount: nft.mintAccount,
+      })
+      .remainingAccounts(
+        await programmableAccounts(
+          nft.mintAccount,
+          nft.swapTokenVault,
+          signerTokenAccount.address
+        )
+      )
+      .preInstructions([programmableComputeBudget()])
+      .signers([signer])
+      .rpc({ commitment: "confirmed" });
+
+    return signerTokenAccount.address;
+  };
+
+  before(async () => {
+    await airdrop([proposalOwner, participant], web3.LAMPORTS_PER_SOL * 2);
+
+    currencyMint = await setupMint([participant]);
+  });
+
+  it("[programmable_nft] should: proposal owner deposits a programmable NFT and the participant redeems it", async () => {
+    const nft = await setupProgrammableNft();
+    const proposal = await createNftProposal(nft);
+
+    await program.methods
+      // @ts-ignore
+      .transferAssetsToVault({
+        proposalId: proposal.proposalId,
+        swapItemId: proposal.nftItemId,
+        swapTokenVaultBump: nft.swapTokenVaultBump,
+        actionType: { depositing: {} },
+        optionId: "",
+      })
+      .accounts({
+        signer: proposalOwner.publicKey,
+        signerTokenAccount: nft.tokenAccount,
+        swapProposal: proposal.swapProposal,
+        swapTokenVault: nft.swapTokenVault,
+        swapRegistry,
+        swapTreasury,
+        mintAccount: nft.mintAccount,
+      })
+      .remainingAccounts(
+        await programmableAccounts(
+          nft.mintAccount,
+          nft.tokenAccount,
+          nft.swapTokenVault
+        )
+      )
+      .preInstructions([programmableComputeBudget()])
+      .signers([proposalOwner])
+      .rpc({ commitment: "confirmed" });
+
+    // the vault holds the programmable NFT, frozen with its token record
+    expect(
+      Number((await getAccount(provider.connection, nft.swapTokenVault)).amount)
+    ).eq(1);
+    expect(
+      await provider.connection.getAccountInfo(
+        await findTokenRecordAddress(nft.mintAccount, nft.swapTokenVault)
+      )
+    ).to.not.be.null;
+
+    await program.methods
+      // @ts-ignore
+      .transferAssetsToVault({
+        proposalId: proposal.proposalId,
+        swapItemId: proposal.currencyItemId,
+        swapTokenVaultBump: currencyMint.swapTokenVaultBump,
+        actionType: { fulfilling: {} },
+        optionId,
+      })
+      .accounts({
+        signer: participant.publicKey,
+        signerTokenAccount:
+          currencyMint.tokenAccounts[participant.publicKey.toBase58()],
+        swapProposal: proposal.swapProposal,
+        swapTokenVault: currencyMint.swapTokenVault,
+        swapRegistry,
+        swapTreasury,
+        mintAccount: currencyMint.mintAccount,
+      })
+      .signers([participant])
+      .rpc({ commitment: "confirmed" });
+
+    const participantTokenAccount = await transferNftFromVault(
+      nft,
+      proposal,
+      participant,
+      { redeeming: {} }
+    );
+
+    expect(
+      Number(
+        (await getAccount(provider.connection, participantTokenAccount)).amount
+      )
+    ).eq(1);
+    expect(
+      Number((await getAccount(provider.connection, nft.swapTokenVault)).amount)
+    ).eq(0);
+
+    const state = await program.account.swapProposal.fetch(
+      proposal.swapProposal
+    );
+    // @ts-ignore
+    expect(!!state.offeredItems[0].status.redeemed).to.be.true;
+  });
+
+  it("[programmable_nft] should: proposal owner deposits all and withdraws a programmable NFT", async () => {
+    const nft = await setupProgrammableNft();
+    const proposal = await createNftProposal(nft);
+
+    await program.methods
+      .depositAll({
+        proposalId: proposal.proposalId,
+        swapTokenVaultBumps: Buffer.from([nft.swapTokenVaultBump]),
+      })
+      .accounts({
+        signer: proposalOwner.publicKey,
+        swapProposal: proposal.swapProposal,
+        swapRegistry,
+      })
+      .remainingAccounts([
+        ...itemAccounts(nft, nft.tokenAccount),
+        ...(await programmableAccounts(
+          nft.mintAccount,
+          nft.tokenAccount,
+          nft.swapTokenVault
+        )),
+      ])
+      .preInstructions([programmableComputeBudget()])
+      .signers([proposalOwner])
+      .rpc({ commitment: "confirmed" });
+
+    expect(
+      Number((await getAccount(provider.connection, nft.tokenAccount)).amount)
+    ).eq(0);
+
+    await program.methods
+      .cancelProposal({ id: proposal.proposalId })
+      .accounts({
+        swapProposal: proposal.swapProposal,
+        signer: proposalOwner.publicKey,
+      })
+      .signers([proposalOwner])
+      .rpc({ commitment: "confirmed" });
+
+    await transferNftFromVault(nft, proposal, proposalOwner, {
+      withdrawing: {},
+    });
+
+    expect(
+      Number((await getAccount(provider.connection, nft.tokenAccount)).amount)
+    ).eq(1);
+
+    const state = await program.account.swapProposal.fetch(
+      proposal.swapProposal
+    );
+    // @ts-ignore
+    expect(!!state.offeredItems[0].status.withdrawn).to.be.true;
+  });
+});
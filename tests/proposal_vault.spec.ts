@@ -0,0 +1,297 @@
+import * as anchor from "@project-serum/anchor";
+import { AnchorError, BN, Program, web3 } from "@project-serum/anchor";
+import { Keypair, PublicKey } from "@solana/web3.js";
+import {
+  createMint,
+  getAccount,
+  getOrCreateAssociatedTokenAccount,
+  mintTo,
+} from "@solana/spl-token";
+import { expect } from "chai";
+import { createHash } from "crypto";
+
+import { Swap } from "../target/types/swap";
+
+describe("proposal_vault", async () => {
+  // Configure the client to use the local cluster.
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.Swap as Program<Swap>;
+  const deployer = provider.wallet as anchor.Wallet;
+
+  // find the swap account
+  const [swapRegistry] = await PublicKey.findProgramAddress(
+    [anchor.utils.bytes.utf8.encode("SEED::SWAP::PLATFORM")],
+    program.programId
+  );
+  const [swapTreasury] = await PublicKey.findProgramAddress(
+    [anchor.utils.bytes.utf8.encode("SEED::SWAP::TREASURY_SEED")],
+    program.programId
+  );
+
+  const proposalOwner = Keypair.generate();
+  const proposalId = Keypair.generate().publicKey.toBase58().slice(0, 10);
+
+  let mintNormalPublicKey;
+  let swapProposal;
+  let proposalTokenVault;
+  let proposalTokenVaultBump;
+  let proposalOwnerTokenAccount;
+  let offeredItems;
+
+  before(async () => {
+    // funding proposal owner
+    const airdropSignature = await provider.connection.requestAirdrop(
+      proposalOwner.publicKey,
+      web3.LAMPORTS_PER_SOL
+    );
+    await provider.connection.confirmTransaction({
+      signature: airdropSignature,
+      blockhash: (await provider.connection.getLatestBlockhash()).blockhash,
+      lastValidBlockHeight: (
+        await provider.connection.getLatestBlockhash()
+      ).lastValidBlockHeight,
+    });
+
+    mintNormalPublicKey = await createMint(
+      provider.connection, // conneciton
+      deployer.payer, // fee payer
+      deployer.publicKey, // mint authority
+      deployer.publicKey, // freeze authority
+      9 // decimals
+    );
+
+    proposalOwnerTokenAccount = await getOrCreateAssociatedTokenAccount(
+      provider.connection,
+      proposalOwner,
+      mintNormalPublicKey,
+      proposalOwner.publicKey
+    );
+    await mintTo(
+      provider.connection,
+      deployer.payer,
+      mintNormalPublicKey,
+      proposalOwnerTokenAccount.address, // destination
+      deployer.publicKey, // authority
+      web3.LAMPORTS_PER_SOL * 100
+    );
+
+    // whitelist the mint
+    const [swapTokenVault] = await PublicKey.findProgramAddress(
+      [
+        anchor.utils.bytes.utf8.encode("SEED::SWAP::TOKEN_VAULT_SEED"),
+        mintNormalPublicKey.toBytes(),
+      ],
+      program.programId
+    );
+    await program.methods
+      .createTokenVault()
+      .accounts({
+        signer: deployer.publicKey,
+        mintAccount: mintNormalPublicKey,
+        swapRegistry,
+        swapTokenVault,
+      })
+      .signers([deployer.payer])
+      .rpc({ commitment: "confirmed" });
+
+    [swapProposal] = await PublicKey.findProgramAddress(
+      [
+        anchor.utils.bytes.utf8.encode("SEED::SWAP::PROPOSAL_SEED"),
+        proposalOwner.publicKey.toBuffer(),
+        createHash("sha256").update(proposalId).digest(),
+      ],
+      program.programId
+    );
+    [proposalTokenVault, proposalTokenVaultBump] =
+      await PublicKey.findProgramAddress(
+        [
+          anchor.utils.bytes.utf8.encode("SEED::SWAP::PROPOSAL_VAULT_SEED"),
+          swapProposal.toBytes(),
+          mintNormalPublicKey.toBytes(),
+        ],
+        program.programId
+      );
+
+    offeredItems = [
+      {
+        id: Keypair.generate().publicKey.toBase58().slice(0, 10),
+        mintAccount: mintNormalPublicKey,
+        amount: new BN(web3.LAMPORTS_PER_SOL),
+        itemType: { currency: {} },
+      },
+    ];
+  });
+
+  it("[proposal_vault] should: proposal owner escrows offered items in a dedicated vault", async () => {
+    await program.methods
+      .createProposal({
+        id: proposalId,
+        swapOptions: [
+          {
+            id: Keypair.generate().publicKey.toBase58().slice(0, 10),
+            askingItems: [
+              {
+                id: Keypair.generate().publicKey.toBase58().slice(0, 10),
+                mintAccount: mintNormalPublicKey,
+                amount: new BN(web3.LAMPORTS_PER_SOL * 2),
+                itemType: { currency: {} },
+              },
+            ],
+          },
+        ],
+        offeredItems,
+        expiredAt: new BN(new Date().getTime() + 1000 * 60 * 60 * 24 * 7),
+        vaultMode: { perProposal: {} },
+      })
+      .accounts({
+        proposalOwner: proposalOwner.publicKey,
+        swapRegistry,
+        swapTreasury,
+        swapProposal,
+      })
+      .signers([proposalOwner])
+      .rpc({ commitment: "confirmed" });
+
+    await program.methods
+      .createProposalVault()
+      .accounts({
+        signer: proposalOwner.publicKey,
+        swapRegistry,
+        swapProposal,
+        mintAccount: mintNormalPublicKey,
+        proposalTokenVault,
+      })
+      .signers([proposalOwner])
+      .rpc({ commitment: "confirmed" });
+
+    await program.methods
+      // @ts-ignore
+      .transferAssetsToVault({
+        proposalId,
+        swapItemId: offeredItems[0].id,
+        swapTokenVaultBump: proposalTokenVaultBump,
+        actionType: { depositing: {} },
+        optionId: "",
+      })
+      .accounts({
+        signer: proposalOwner.publicKey,
+        signerTokenAccount: proposalOwnerTokenAccount.address,
+        swapProposal,
+        swapTokenVault: proposalTokenVault,
+        swapRegistry,
+        swapTreasury,
+        mintAccount: mintNormalPublicKey,
+      })
+      .signers([proposalOwner])
+      .rpc({ commitment: "confirmed" });
+
+    const state = await program.account.swapProposal.fetch(swapProposal);
+    // @ts-ignore
+    expect(!!state.vaultMode.perProposal).to.be.true;
+    // @ts-ignore
+    expect(!!state.status.deposited).to.be.true;
+
+    const vaultAccount = await getAccount(
+      provider.connection,
+      proposalTokenVault
+    );
+    expect(Number(vaultAccount.amount)).eq(web3.LAMPORTS_PER_SOL);
+    expect(vaultAccount.owner.equals(swapProposal)).to.be.true;
+  });
+
+  it("[proposal_vault] should: proposal owner fails to close a proposal that is not settled", async () => {
+    try {
+      await program.methods
+        .closeProposal({ id: proposalId })
+        .accounts({
+          swapProposal,
+          proposalOwner: proposalOwner.publicKey,
+        })
+        .signers([proposalOwner])
+        .rpc({ commitment: "confirmed" });
+
+      throw new Error("Should be failed");
+    } catch (e) {
+      expect(e instanceof AnchorError).to.be.true;
+      expect(e.error.errorCode.code).equals("ProposalCannotBeClosed");
+    }
+  });
+
+  it("[proposal_vault] should: dedicated vault is closed once the last item is withdrawn", async () => {
+    await program.methods
+      .cancelProposal({ id: proposalId })
+      .accounts({
+        swapProposal,
+        signer: proposalOwner.publicKey,
+      })
+      .signers([proposalOwner])
+      .rpc({ commitment: "confirmed" });
+
+    const vaultRent = await provider.connection.getBalance(proposalTokenVault);
+    const proposalBalanceBefore = await provider.connection.getBalance(
+      swapProposal
+    );
+
+    await program.methods
+      // @ts-ignore
+      .transferAssetsFromVault({
+        proposalId,
+        swapItemId: offeredItems[0].id,
+        swapTokenVaultBump: proposalTokenVaultBump,
+        actionType: { withdrawing: {} },
+      })
+      .accounts({
+        signer: proposalOwner.publicKey,
+        signerTokenAccount: proposalOwnerTokenAccount.address,
+        swapProposal,
+        swapTokenVault: proposalTokenVault,
+        swapRegistry,
+        mintAccount: mintNormalPublicKey,
+      })
+      .signers([proposalOwner])
+      .rpc({ commitment: "confirmed" });
+
+    const state = await program.account.swapProposal.fetch(swapProposal);
+    // @ts-ignore
+    expect(!!state.status.withdrawn).to.be.true;
+    expect(await provider.connection.getAccountInfo(proposalTokenVault)).to.be
+      .null;
+
+    // the vault rent is held by the proposal until the proposal is closed
+    expect(await provider.connection.getBalance(swapProposal)).eq(
+      proposalBalanceBefore + vaultRent
+    );
+
+    const ownerTokenAccount = await getAccount(
+      provider.connection,
+      proposalOwnerTokenAccount.address
+    );
+    expect(Number(ownerTokenAccount.amount)).eq(web3.LAMPORTS_PER_SOL * 100);
+  });
+
+  it("[proposal_vault] should: proposal owner receives the vault rent when closing the proposal", async () => {
+    const proposalBalance = await provider.connection.getBalance(swapProposal);
+    const ownerBalanceBefore = await provider.connection.getBalance(
+      proposalOwner.publicKey
+    );
+
+    const signature = await program.methods
+      .closeProposal({ id: proposalId })
+      .accounts({
+        swapProposal,
+        proposalOwner: proposalOwner.publicKey,
+      })
+      .signers([proposalOwner])
+      .rpc({ commitment: "confirmed" });
+    const transaction = await provider.connection.getTransaction(signature, {
+      commitment: "confirmed",
+    });
+
+    expect(await provider.connection.getAccountInfo(swapProposal)).to.be.null;
+    expect(await provider.connection.getBalance(proposalOwner.publicKey)).eq(
+      ownerBalanceBefore + proposalBalance - transaction.meta.fee
+    );
+  });
+});
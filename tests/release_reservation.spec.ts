@@ -0,0 +1,312 @@
+import * as anchor from "@project-serum/anchor";
+import { AnchorError, BN, Program, web3 } from "@project-serum/anchor";
+import { Keypair, PublicKey } from "@solana/web3.js";
+import { getAccount } from "@solana/spl-token";
+import { expect } from "chai";
+
+import { Swap } from "../target/types/swap";
+import { airdrop, createProposal, itemAccounts, setupMint } from "./fixtures";
+
+describe("release_reservation", async () => {
+  // Configure the client to use the local cluster.
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.Swap as Program<Swap>;
+  const deployer = provider.wallet as anchor.Wallet;
+
+  // find the swap account
+  const [swapRegistry] = await PublicKey.findProgramAddress(
+    [anchor.utils.bytes.utf8.encode("SEED::SWAP::PLATFORM")],
+    program.programId
+  );
+  const [swapTreasury] = await PublicKey.findProgramAddress(
+    [anchor.utils.bytes.utf8.encode("SEED::SWAP::TREASURY_SEED")],
+    program.programId
+  );
+
+  const proposalOwner = Keypair.generate();
+  const participant = Keypair.generate();
+  const proposalId = Keypair.generate().publicKey.toBase58().slice(0, 10);
+  const optionId = Keypair.generate().publicKey.toBase58().slice(0, 10);
+
+  const reservationWindow = 5;
+
+  let swapProposal;
+  let offeredMint;
+  let askingMint;
+  let askingItemIds;
+
+  const updateReservationWindow = async (reservationWindow) => {
+    const registry = await program.account.swapPlatformRegistry.fetch(
+      swapRegistry
+    );
+    await program.methods
+      .updateSwapRegistry({
+        maxAllowedItems: registry.maxAllowedItems,
+        maxAllowedOptions: registry.maxAllowedOptions,
+        reservationWindow: new BN(reservationWindow),
+      })
+      .accounts({
+        swapRegistry,
+        owner: deployer.publicKey,
+      })
+      .signers([deployer.payer])
+      .rpc({ commitment: "confirmed" });
+  };
+
+  const releaseReservation = () =>
+    program.methods
+      .releaseReservation({ proposalId })
+      .accounts({
+        signer: deployer.publicKey,
+        swapRegistry,
+        swapProposal,
+      })
+      .signers([deployer.payer])
+      .rpc({ commitment: "confirmed" });
+
+  // the participant reserves the proposal with the first asking item
+  const reserve = () =>
+    program.methods
+      // @ts-ignore
+      .transferAssetsToVault({
+        proposalId,
+        swapItemId: askingItemIds[0],
+        swapTokenVaultBump: askingMint.swapTokenVaultBump,
+        actionType: { fulfilling: {} },
+        optionId,
+      })
+      .accounts({
+        signer: participant.publicKey,
+        signerTokenAccount:
+          askingMint.tokenAccounts[participant.publicKey.toBase58()],
+        swapProposal,
+        swapTokenVault: askingMint.swapTokenVault,
+        swapRegistry,
+        swapTreasury,
+        mintAccount: askingMint.mintAccount,
+      })
+      .signers([participant])
+      .rpc({ commitment: "confirmed" });
+
+  // wait for the cluster clock to pass the reservation window, then release the reservation
+  const releaseStaleReservation = async () => {
+    for (let attempt = 0; ; attempt++) {
+      await new Promise((resolve) => setTimeout(resolve, 2000));
+
+      try {
+        await releaseReservation();
+        break;
+      } catch (e) {
+        if (attempt >= 15) throw e;
+      }
+    }
+  };
+
+  const participantBalance = async () =>
+    Number(
+      (
+        await getAccount(
+          provider.connection,
+          askingMint.tokenAccounts[participant.publicKey.toBase58()]
+        )
+      ).amount
+    );
+
+  before(async () => {
+    // funding the users
+    await airdrop([proposalOwner, participant]);
+
+    offeredMint = await setupMint([proposalOwner, participant]);
+    askingMint = await setupMint([proposalOwner, participant]);
+    await updateReservationWindow(reservationWindow);
+
+    askingItemIds = [0, 1].map(() =>
+      Keypair.generate().publicKey.toBase58().slice(0, 10)
+    );
+    swapProposal = await createProposal(proposalOwner, {
+      id: proposalId,
+      swapOptions: [
+        {
+          id: optionId,
+          askingItems: askingItemIds.map((id) => ({
+            id,
+            mintAccount: askingMint.mintAccount,
+            amount: new BN(web3.LAMPORTS_PER_SOL),
+            itemType: { currency: {} },
+          })),
+        },
+      ],
+      offeredItems: [
+        {
+          id: Keypair.generate().publicKey.toBase58().slice(0, 10),
+          mintAccount: offeredMint.mintAccount,
+          amount: new BN(web3.LAMPORTS_PER_SOL),
+          itemType: { currency: {} },
+        },
+      ],
+    });
+
+    await program.methods
+      .depositAll({
+        proposalId,
+        swapTokenVaultBumps: Buffer.from([offeredMint.swapTokenVaultBump]),
+      })
+      .accounts({
+        signer: proposalOwner.publicKey,
+        swapProposal,
+        swapRegistry,
+      })
+      .remainingAccounts(
+        itemAccounts(
+          offeredMint,
+          offeredMint.tokenAccounts[proposalOwner.publicKey.toBase58()]
+        )
+      )
+      .signers([proposalOwner])
+      .rpc({ commitment: "confirmed" });
+
+    // the participant reserves the proposal with a single asking item
+    await reserve();
+  });
+
+  after(async () => {
+    await updateReservationWindow(0);
+  });
+
+  it("[release_reservation] should: fail to release a reservation within the reservation window", async () => {
+    try {
+      await releaseReservation();
+
+      throw new Error("Should be failed");
+    } catch (e) {
+      expect(e instanceof AnchorError).to.be.true;
+      expect(e.error.errorCode.code).equals("ReservationIsNotStale");
+    }
+  });
+
+  it("[release_reservation] should: anyone releases a stale reservation and the proposal reopens", async () => {
+    await releaseStaleReservation();
+
+    const state = await program.account.swapProposal.fetch(swapProposal);
+    // @ts-ignore
+    expect(!!state.status.deposited).to.be.true;
+    expect(state.fulfilledBy.equals(PublicKey.default)).to.be.true;
+    expect(state.fulfilledWithOptionId).eq("");
+    expect(state.pendingRefunds.length).eq(1);
+    expect(state.pendingRefunds[0].owner.equals(participant.publicKey)).to.be
+      .true;
+    state.swapOptions[0].askingItems.forEach((item) => {
+      // @ts-ignore
+      expect(!!item.status.created).to.be.true;
+    });
+  });
+
+  it("[withdraw_all] should: participant withdraws the items of the released reservation", async () => {
+    await program.methods
+      .withdrawAll({
+        proposalId,
+        swapTokenVaultBumps: Buffer.from([askingMint.swapTokenVaultBump]),
+      })
+      .accounts({
+        signer: participant.publicKey,
+        swapRegistry,
+        swapProposal,
+      })
+      .remainingAccounts(
+        itemAccounts(
+          askingMint,
+          askingMint.tokenAccounts[participant.publicKey.toBase58()]
+        )
+      )
+      .signers([participant])
+      .rpc({ commitment: "confirmed" });
+
+    const state = await program.account.swapProposal.fetch(swapProposal);
+    // @ts-ignore
+    expect(!!state.pendingRefunds[0].status.withdrawn).to.be.true;
+
+    expect(await participantBalance()).eq(web3.LAMPORTS_PER_SOL * 10);
+  });
+
+  it("[transfer_assets_from_vault] should: participant withdraws an item of a released reservation alone", async () => {
+    await reserve();
+    await releaseStaleReservation();
+
+    await program.methods
+      // @ts-ignore
+      .transferAssetsFromVault({
+        proposalId,
+        swapItemId: askingItemIds[0],
+        swapTokenVaultBump: askingMint.swapTokenVaultBump,
+        actionType: { withdrawing: {} },
+      })
+      .accounts({
+        signer: participant.publicKey,
+        signerTokenAccount:
+          askingMint.tokenAccounts[participant.publicKey.toBase58()],
+        swapProposal,
+        swapTokenVault: askingMint.swapTokenVault,
+        swapRegistry,
+        mintAccount: askingMint.mintAccount,
+      })
+      .signers([participant])
+      .rpc({ commitment: "confirmed" });
+
+    const state = await program.account.swapProposal.fetch(swapProposal);
+    expect(state.pendingRefunds.length).eq(2);
+    // @ts-ignore
+    expect(!!state.pendingRefunds[1].status.withdrawn).to.be.true;
+    // @ts-ignore
+    expect(!!state.status.deposited).to.be.true;
+
+    expect(await participantBalance()).eq(web3.LAMPORTS_PER_SOL * 10);
+  });
+
+  it("[crank_settle] should: anyone pushes the pending refunds back and closes the settled proposal", async () => {
+    await reserve();
+    await releaseStaleReservation();
+
+    await program.methods
+      .cancelProposal({ id: proposalId })
+      .accounts({
+        swapProposal,
+        signer: proposalOwner.publicKey,
+      })
+      .signers([proposalOwner])
+      .rpc({ commitment: "confirmed" });
+
+    // the offered item goes back to the owner, the pending refund to the participant
+    await program.methods
+      .crankSettle({
+        proposalId,
+        swapTokenVaultBumps: Buffer.from([
+          offeredMint.swapTokenVaultBump,
+          askingMint.swapTokenVaultBump,
+        ]),
+      })
+      .accounts({
+        signer: deployer.publicKey,
+        swapRegistry,
+        swapProposal,
+        proposalOwner: proposalOwner.publicKey,
+        participant: PublicKey.default,
+      })
+      .remainingAccounts([
+        ...itemAccounts(
+          offeredMint,
+          offeredMint.tokenAccounts[proposalOwner.publicKey.toBase58()]
+        ),
+        ...itemAccounts(
+          askingMint,
+          askingMint.tokenAccounts[participant.publicKey.toBase58()]
+        ),
+      ])
+      .signers([deployer.payer])
+      .rpc({ commitment: "confirmed" });
+
+    expect(await participantBalance()).eq(web3.LAMPORTS_PER_SOL * 10);
+    expect(await provider.connection.getAccountInfo(swapProposal)).to.be.null;
+  });
+});
@@ -0,0 +1,437 @@
+import * as anchor from "@project-serum/anchor";
+import {
+  AnchorError,
+  BN,
+  BorshCoder,
+  EventParser,
+  Program,
+  web3,
+} from "@project-serum/anchor";
+import { Keypair, PublicKey } from "@solana/web3.js";
+import {
+  createMint,
+  getAccount,
+  getOrCreateAssociatedTokenAccount,
+  mintTo,
+} from "@solana/spl-token";
+import { expect } from "chai";
+import { createHash } from "crypto";
+
+import { Swap } from "../target/types/swap";
+import { createProposal, createTokenVault } from "./fixtures";
+import { createNft, metadataAccount } from "./metadata";
+
+describe("royalty", async () => {
+  // Configure the client to use the local cluster.
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.Swap as Program<Swap>;
+  const deployer = provider.wallet as anchor.Wallet;
+
+  // find the swap account
+  const [swapRegistry] = await PublicKey.findProgramAddress(
+    [anchor.utils.bytes.utf8.encode("SEED::SWAP::PLATFORM")],
+    program.programId
+  );
+  const [swapTreasury] = await PublicKey.findProgramAddress(
+    [anchor.utils.bytes.utf8.encode("SEED::SWAP::TREASURY_SEED")],
+    program.programId
+  );
+
+  const proposalOwner = Keypair.generate();
+  const participant = Keypair.generate();
+  const proposalId = Keypair.generate().publicKey.toBase58().slice(0, 10);
+  const optionId = Keypair.generate().publicKey.toBase58().slice(0, 10);
+
+  let swapProposal;
+  let nftMint;
+  let currencyMint;
+
+  const itemAccounts = (mint, tokenAccount) => [
+    { pubkey: mint.mintAccount, isSigner: false, isWritable: false },
+    { pubkey: tokenAccount, isSigner: false, isWritable: true },
+    { pubkey: mint.swapTokenVault, isSigner: false, isWritable: true },
+  ];
+
+  // create and whitelist a mint, then fund the owner and the participant
+  const setupMint = async (decimals: number, amount: number) => {
+    const mintAccount = await createMint(
+      provider.connection,
+      deployer.payer,
+      deployer.publicKey,
+      deployer.publicKey,
+      decimals
+    );
+
+    const tokenAccounts = {};
+    for (const user of [proposalOwner, participant]) {
+      const tokenAccount = await getOrCreateAssociatedTokenAccount(
+        provider.connection,
+        user,
+        mintAccount,
+        user.publicKey
+      );
+      await mintTo(
+        provider.connection,
+        deployer.payer,
+        mintAccount,
+        tokenAccount.address,
+        deployer.publicKey,
+        amount
+      );
+      tokenAccounts[user.publicKey.toBase58()] = tokenAccount.address;
+    }
+
+    const [swapTokenVault, swapTokenVaultBump] =
+      await PublicKey.findProgramAddress(
+        [
+          anchor.utils.bytes.utf8.encode("SEED::SWAP::TOKEN_VAULT_SEED"),
+          mintAccount.toBytes(),
+        ],
+        program.programId
+      );
+    await program.methods
+      .createTokenVault()
+      .accounts({
+        signer: deployer.publicKey,
+        mintAccount,
+        swapRegistry,
+        swapTokenVault,
+      })
+      .signers([deployer.payer])
+      .rpc({ commitment: "confirmed" });
+
+    return { mintAccount, tokenAccounts, swapTokenVault, swapTokenVaultBump };
+  };
+
+  const updateRoyaltyMode = async (royaltyMode) => {
+    const registry = await program.account.swapPlatformRegistry.fetch(
+      swapRegistry
+    );
+    await program.methods
+      .updateSwapRegistry({
+        maxAllowedItems: registry.maxAllowedItems,
+        maxAllowedOptions: registry.maxAllowedOptions,
+        royaltyMode,
+      })
+      .accounts({
+        swapRegistry,
+        owner: deployer.publicKey,
+      })
+      .signers([deployer.payer])
+      .rpc({ commitment: "confirmed" });
+  };
+
+  // the participant pays the currency leg and both legs are redeemed at once
+  const acceptProposal = () =>
+    program.methods
+      .acceptProposal({
+        proposalId,
+        optionId,
+        askingVaultBumps: Buffer.from([currencyMint.swapTokenVaultBump]),
+        offeredVaultBumps: Buffer.from([nftMint.swapTokenVaultBump]),
+        settleToOwner: true,
+      })
+      .accounts({
+        signer: participant.publicKey,
+        swapProposal,
+        swapRegistry,
+        swapTreasury,
+      })
+      .remainingAccounts([
+        ...itemAccounts(
+          currencyMint,
+          currencyMint.tokenAccounts[participant.publicKey.toBase58()]
+        ),
+        ...itemAccounts(
+          nftMint,
+          nftMint.tokenAccounts[participant.publicKey.toBase58()]
+        ),
+        ...itemAccounts(
+          currencyMint,
+          currencyMint.tokenAccounts[proposalOwner.publicKey.toBase58()]
+        ),
+      ])
+      .signers([participant])
+      .rpc({ commitment: "confirmed" });
+
+  before(async () => {
+    // funding the users
+    for (const user of [proposalOwner, participant]) {
+      const airdropSignature = await provider.connection.requestAirdrop(
+        user.publicKey,
+        web3.LAMPORTS_PER_SOL * 2
+      );
+      await provider.connection.confirmTransaction({
+        signature: airdropSignature,
+        blockhash: (await provider.connection.getLatestBlockhash()).blockhash,
+        lastValidBlockHeight: (
+          await provider.connection.getLatestBlockhash()
+        ).lastValidBlockHeight,
+      });
+    }
+
+    nftMint = await setupMint(0, 1);
+    currencyMint = await setupMint(9, web3.LAMPORTS_PER_SOL * 10);
+
+    [swapProposal] = await PublicKey.findProgramAddress(
+      [
+        anchor.utils.bytes.utf8.encode("SEED::SWAP::PROPOSAL_SEED"),
+        proposalOwner.publicKey.toBuffer(),
+        createHash("sha256").update(proposalId).digest(),
+      ],
+      program.programId
+    );
+
+    // the proposal owner sells an NFT for a currency leg
+    await program.methods
+      .createProposal({
+        id: proposalId,
+        swapOptions: [
+          {
+            id: optionId,
+            askingItems: [
+              {
+                id: Keypair.generate().publicKey.toBase58().slice(0, 10),
+                mintAccount: currencyMint.mintAccount,
+                amount: new BN(web3.LAMPORTS_PER_SOL),
+                itemType: { currency: {} },
+              },
+            ],
+          },
+        ],
+        offeredItems: [
+          {
+            id: Keypair.generate().publicKey.toBase58().slice(0, 10),
+            mintAccount: nftMint.mintAccount,
+            amount: new BN(1),
+            itemType: { nft: {} },
+          },
+        ],
+        expiredAt: new BN(new Date().getTime() + 1000 * 60 * 60 * 24 * 7),
+      })
+      .accounts({
+        proposalOwner: proposalOwner.publicKey,
+        swapRegistry,
+        swapTreasury,
+        swapProposal,
+      })
+      .signers([proposalOwner])
+      .rpc({ commitment: "confirmed" });
+
+    await program.methods
+      .depositAll({
+        proposalId,
+        swapTokenVaultBumps: Buffer.from([nftMint.swapTokenVaultBump]),
+      })
+      .accounts({
+        signer: proposalOwner.publicKey,
+        swapProposal,
+        swapRegistry,
+      })
+      .remainingAccounts(
+        itemAccounts(
+          nftMint,
+          nftMint.tokenAccounts[proposalOwner.publicKey.toBase58()]
+        )
+      )
+      .signers([proposalOwner])
+      .rpc({ commitment: "confirmed" });
+  });
+
+  after(async () => {
+    await updateRoyaltyMode({ off: {} });
+  });
+
+  it("[royalty] should: deployer updates the royalty mode", async () => {
+    await updateRoyaltyMode({ enforced: {} });
+
+    const state = await program.account.swapPlatformRegistry.fetch(
+      swapRegistry
+    );
+    // @ts-ignore
+    expect(!!state.royaltyMode.enforced).to.be.true;
+  });
+
+  it("[royalty] should: fail to redeem the currency leg without the NFT metadata when royalties are enforced", async () => {
+    try {
+      await acceptProposal();
+
+      throw new Error("Should be failed");
+    } catch (e) {
+      expect(e instanceof AnchorError).to.be.true;
+      expect(e.error.errorCode.code).equals("InvalidMetadata");
+    }
+  });
+
+  it("[royalty] should: redeem the full currency leg when optional royalties cannot be paid", async () => {
+    await updateRoyaltyMode({ optional: {} });
+
+    await acceptProposal();
+
+    const state = await program.account.swapProposal.fetch(swapProposal);
+    // @ts-ignore
+    expect(!!state.status.redeemed).to.be.true;
+
+    const ownerTokenAccount = await getAccount(
+      provider.connection,
+      currencyMint.tokenAccounts[proposalOwner.publicKey.toBase58()]
+    );
+    expect(Number(ownerTokenAccount.amount)).eq(web3.LAMPORTS_PER_SOL * 11);
+  });
+
+  it("[royalty] should: pay the verified creators out of the currency leg when royalties are enforced", async () => {
+    await updateRoyaltyMode({ enforced: {} });
+
+    // the NFT pays a 5% seller fee shared 70/30 between two verified creators
+    const creators = [
+      { creator: Keypair.generate(), share: 70 },
+      { creator: Keypair.generate(), share: 30 },
+    ];
+    const nft = await createNft(proposalOwner, {
+      sellerFeeBasisPoints: 500,
+      creators,
+    });
+    const royaltyNft = {
+      ...nft,
+      ...(await createTokenVault(nft.mintAccount)),
+    };
+    const participantNftAccount = await getOrCreateAssociatedTokenAccount(
+      provider.connection,
+      participant,
+      nft.mintAccount,
+      participant.publicKey
+    );
+
+    // the creators receive the royalties on their associated token accounts
+    const creatorTokenAccounts = [];
+    for (const { creator } of creators) {
+      const creatorTokenAccount = await getOrCreateAssociatedTokenAccount(
+        provider.connection,
+        deployer.payer,
+        currencyMint.mintAccount,
+        creator.publicKey
+      );
+      creatorTokenAccounts.push(creatorTokenAccount.address);
+    }
+
+    const royaltyProposalId = Keypair.generate()
+      .publicKey.toBase58()
+      .slice(0, 10);
+    const royaltyProposal = await createProposal(proposalOwner, {
+      id: royaltyProposalId,
+      swapOptions: [
+        {
+          id: optionId,
+          askingItems: [
+            {
+              id: Keypair.generate().publicKey.toBase58().slice(0, 10),
+              mintAccount: currencyMint.mintAccount,
+              amount: new BN(web3.LAMPORTS_PER_SOL),
+              itemType: { currency: {} },
+            },
+          ],
+        },
+      ],
+      offeredItems: [
+        {
+          id: Keypair.generate().publicKey.toBase58().slice(0, 10),
+          mintAccount: nft.mintAccount,
+          amount: new BN(1),
+          itemType: { nft: {} },
+        },
+      ],
+    });
+
+    await program.methods
+      .depositAll({
+        proposalId: royaltyProposalId,
+        swapTokenVaultBumps: Buffer.from([royaltyNft.swapTokenVaultBump]),
+      })
+      .accounts({
+        signer: proposalOwner.publicKey,
+        swapProposal: royaltyProposal,
+        swapRegistry,
+      })
+      .remainingAccounts(itemAccounts(royaltyNft, nft.tokenAccount))
+      .signers([proposalOwner])
+      .rpc({ commitment: "confirmed" });
+
+    const ownerTokenAccount =
+      currencyMint.tokenAccounts[proposalOwner.publicKey.toBase58()];
+    const ownerBalance = Number(
+      (await getAccount(provider.connection, ownerTokenAccount)).amount
+    );
+
+    const tx = await program.methods
+      .acceptProposal({
+        proposalId: royaltyProposalId,
+        optionId,
+        askingVaultBumps: Buffer.from([currencyMint.swapTokenVaultBump]),
+        offeredVaultBumps: Buffer.from([royaltyNft.swapTokenVaultBump]),
+        settleToOwner: true,
+      })
+      .accounts({
+        signer: participant.publicKey,
+        swapProposal: royaltyProposal,
+        swapRegistry,
+        swapTreasury,
+      })
+      .remainingAccounts([
+        ...itemAccounts(
+          currencyMint,
+          currencyMint.tokenAccounts[participant.publicKey.toBase58()]
+        ),
+        ...itemAccounts(royaltyNft, participantNftAccount.address),
+        ...itemAccounts(currencyMint, ownerTokenAccount),
+        await metadataAccount(nft.mintAccount),
+        ...creatorTokenAccounts.map((pubkey) => ({
+          pubkey,
+          isSigner: false,
+          isWritable: true,
+        })),
+      ])
+      .signers([participant])
+      .rpc({ commitment: "confirmed" });
+
+    // 5% of the currency leg is shared between the creators
+    const creatorBalances = [];
+    for (const creatorTokenAccount of creatorTokenAccounts) {
+      creatorBalances.push(
+        Number(
+          (await getAccount(provider.connection, creatorTokenAccount)).amount
+        )
+      );
+    }
+    expect(creatorBalances).deep.eq([35_000_000, 15_000_000]);
+
+    // the proposal owner redeems the currency leg net of the royalties
+    const ownerAccount = await getAccount(
+      provider.connection,
+      ownerTokenAccount
+    );
+    expect(Number(ownerAccount.amount) - ownerBalance).eq(
+      web3.LAMPORTS_PER_SOL - 50_000_000
+    );
+
+    // one event per paid creator
+    const transaction = await provider.connection.getParsedTransaction(tx, {
+      commitment: "confirmed",
+    });
+    const eventParser = new EventParser(
+      program.programId,
+      new BorshCoder(program.idl)
+    );
+    const events = [
+      ...eventParser.parseLogs(transaction.meta.logMessages),
+    ].filter((event) => event.name === "RoyaltyPaid");
+    expect(events.length).eq(2);
+    expect(events.map((event) => event.data.creator.toBase58())).deep.eq(
+      creators.map(({ creator }) => creator.publicKey.toBase58())
+    );
+    expect(events.map((event) => Number(event.data.amount))).deep.eq([
+      35_000_000, 15_000_000,
+    ]);
+  });
+});
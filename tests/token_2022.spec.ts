@@ -0,0 +1,928 @@
+import * as anchor from "@project-serum/anchor";
+import { AnchorError, BN, Program, web3 } from "@project-serum/anchor";
+import { Keypair, PublicKey } from "@solana/web3.js";
+import {
+  createInitializeMintInstruction,
+  createInitializeTransferFeeConfigInstruction,
+  createMint,
+  ExtensionType,
+  getAccount,
+  getMintLen,
+  getOrCreateAssociatedTokenAccount,
+  mintTo,
+  TOKEN_2022_PROGRAM_ID,
+  TOKEN_PROGRAM_ID,
+} from "@solana/spl-token";
+import { expect } from "chai";
+import { createHash } from "crypto";
+
+import { Swap } from "../target/types/swap";
+
+describe("token_2022", async () => {
+  // Configure the client to use the local cluster.
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.Swap as Program<Swap>;
+  const deployer = provider.wallet as anchor.Wallet;
+
+  // find the swap account
+  const [swapRegistry] = await PublicKey.findProgramAddress(
+    [anchor.utils.bytes.utf8.encode("SEED::SWAP::PLATFORM")],
+    program.programId
+  );
+  const [swapTreasury] = await PublicKey.findProgramAddress(
+    [anchor.utils.bytes.utf8.encode("SEED::SWAP::TREASURY_SEED")],
+    program.programId
+  );
+
+  const proposalOwner = Keypair.generate();
+  const participant = Keypair.generate();
+  const proposalId = Keypair.generate().publicKey.toBase58().slice(0, 10);
+
+  // the transfer fee withheld by the transfer-fee mint, and the platform fee taken on its currency legs
+  const TRANSFER_FEE_BPS = 100;
+  const PLATFORM_FEE_BPS = 500;
+
+  let swapProposal;
+  let mintAccount;
+  let ownerTokenAccount;
+  let swapTokenVault;
+  let swapTokenVaultBump;
+  let feeMintAccount;
+  let feeTokenAccounts = {};
+  let feeTokenVault;
+  let feeTokenVaultBump;
+  let feeProposal;
+  let feeProposalId;
+  let feeOptionId;
+  let feeOfferedItemId;
+  let feeAskingItemId;
+
+  // create a Token-2022 mint withholding a transfer fee on every transfer
+  const createTransferFeeMint = async () => {
+    const mintKeypair = Keypair.generate();
+    const mintLen = getMintLen([ExtensionType.TransferFeeConfig]);
+    const lamports =
+      await provider.connection.getMinimumBalanceForRentExemption(mintLen);
+
+    await provider.sendAndConfirm(
+      new web3.Transaction().add(
+        web3.SystemProgram.createAccount({
+          fromPubkey: deployer.publicKey,
+          newAccountPubkey: mintKeypair.publicKey,
+          space: mintLen,
+          lamports,
+          programId: TOKEN_2022_PROGRAM_ID,
+        }),
+        createInitializeTransferFeeConfigInstruction(
+          mintKeypair.publicKey,
+          deployer.publicKey,
+          deployer.publicKey,
+          TRANSFER_FEE_BPS,
+          BigInt(1_000_000_000),
+          TOKEN_2022_PROGRAM_ID
+        ),
+        createInitializeMintInstruction(
+          mintKeypair.publicKey,
+          6,
+          deployer.publicKey,
+          null,
+          TOKEN_2022_PROGRAM_ID
+        )
+      ),
+      [mintKeypair],
+      { commitment: "confirmed" }
+    );
+
+    return mintKeypair.publicKey;
+  };
+
+  // find the accrued platform fee of the transfer-fee mint
+  const findAccruedFee = async () => {
+    const state = await program.account.swapPlatformRegistry.fetch(
+      swapRegistry
+    );
+    // @ts-ignore
+    return state.mintFeeSchedules
+      .find((feeSchedule) => feeSchedule.mintAccount.equals(feeMintAccount))
+      .accruedAmount.toNumber();
+  };
+
+  const getFeeBalance = async (tokenAccount: PublicKey) =>
+    Number(
+      (
+        await getAccount(
+          provider.connection,
+          tokenAccount,
+          "confirmed",
+          TOKEN_2022_PROGRAM_ID
+        )
+      ).amount
+    );
+
+  before(async () => {
+    // funding the proposal owner and the participant
+    for (const user of [proposalOwner, participant]) {
+      const airdropSignature = await provider.connection.requestAirdrop(
+        user.publicKey,
+        web3.LAMPORTS_PER_SOL * 2
+      );
+      await provider.connection.confirmTransaction({
+        signature: airdropSignature,
+        blockhash: (await provider.connection.getLatestBlockhash()).blockhash,
+        lastValidBlockHeight: (
+          await provider.connection.getLatestBlockhash()
+        ).lastValidBlockHeight,
+      });
+    }
+
+    // create a Token-2022 mint and fund the proposal owner
+    mintAccount = await createMint(
+      provider.connection,
+      deployer.payer,
+      deployer.publicKey,
+      deployer.publicKey,
+      6,
+      undefined,
+      undefined,
+      TOKEN_2022_PROGRAM_ID
+    );
+    ownerTokenAccount = (
+      await getOrCreateAssociatedTokenAccount(
+        provider.connection,
+        proposalOwner,
+        mintAccount,
+        proposalOwner.publicKey,
+        false,
+        undefined,
+        undefined,
+        TOKEN_2022_PROGRAM_ID
+      )
+    ).address;
+    await mintTo(
+      provider.connection,
+      deployer.payer,
+      mintAccount,
+      ownerTokenAccount,
+      deployer.publicKey,
+      1_000_000,
+      [],
+      undefined,
+      TOKEN_2022_PROGRAM_ID
+    );
+
+    [swapTokenVault, swapTokenVaultBump] = await PublicKey.findProgramAddress(
+      [
+        anchor.utils.bytes.utf8.encode("SEED::SWAP::TOKEN_VAULT_SEED"),
+        mintAccount.toBytes(),
+      ],
+      program.programId
+    );
+
+    [swapProposal] = await PublicKey.findProgramAddress(
+      [
+        anchor.utils.bytes.utf8.encode("SEED::SWAP::PROPOSAL_SEED"),
+        proposalOwner.publicKey.toBuffer(),
+        createHash("sha256").update(proposalId).digest(),
+      ],
+      program.programId
+    );
+
+    // create a transfer-fee mint, fund the users and whitelist it with a platform fee
+    feeMintAccount = await createTransferFeeMint();
+    for (const user of [proposalOwner, participant]) {
+      const tokenAccount = (
+        await getOrCreateAssociatedTokenAccount(
+          provider.connection,
+          user,
+          feeMintAccount,
+          user.publicKey,
+          false,
+          undefined,
+          undefined,
+          TOKEN_2022_PROGRAM_ID
+        )
+      ).address;
+      await mintTo(
+        provider.connection,
+        deployer.payer,
+        feeMintAccount,
+        tokenAccount,
+        deployer.publicKey,
+        1_000_000,
+        [],
+        undefined,
+        TOKEN_2022_PROGRAM_ID
+      );
+      feeTokenAccounts[user.publicKey.toBase58()] = tokenAccount;
+    }
+
+    [feeTokenVault, feeTokenVaultBump] = await PublicKey.findProgramAddress(
+      [
+        anchor.utils.bytes.utf8.encode("SEED::SWAP::TOKEN_VAULT_SEED"),
+        feeMintAccount.toBytes(),
+      ],
+      program.programId
+    );
+    await program.methods
+      .createTokenVault()
+      .accounts({
+        signer: deployer.publicKey,
+        mintAccount: feeMintAccount,
+        swapRegistry,
+        swapTokenVault: feeTokenVault,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+      })
+      .signers([deployer.payer])
+      .rpc({ commitment: "confirmed" });
+
+    await program.methods
+      .setMintFeeSchedule({
+        mintAccount: feeMintAccount,
+        feeBps: PLATFORM_FEE_BPS,
+      })
+      .accounts({
+        owner: deployer.publicKey,
+        swapRegistry,
+      })
+      .signers([deployer.payer])
+      .rpc({ commitment: "confirmed" });
+  });
+
+  it("[token_2022] should: fail to create a vault with a token program not owning the mint", async () => {
+    try {
+      await program.methods
+        .createTokenVault()
+        .accounts({
+          signer: deployer.publicKey,
+          mintAccount,
+          swapRegistry,
+          swapTokenVault,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([deployer.payer])
+        .rpc({ commitment: "confirmed" });
+
+      throw new Error("Should be failed");
+    } catch (e) {
+      expect(e instanceof AnchorError).to.be.true;
+      expect(e.error.errorCode.code).equals("InvalidTokenProgram");
+    }
+  });
+
+  it("[token_2022] should: deployer creates a vault owned by the Token-2022 program", async () => {
+    await program.methods
+      .createTokenVault()
+      .accounts({
+        signer: deployer.publicKey,
+        mintAccount,
+        swapRegistry,
+        swapTokenVault,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+      })
+      .signers([deployer.payer])
+      .rpc({ commitment: "confirmed" });
+
+    const vault = await getAccount(
+      provider.connection,
+      swapTokenVault,
+      "confirmed",
+      TOKEN_2022_PROGRAM_ID
+    );
+    expect(vault.mint.equals(mintAccount)).to.be.true;
+    expect(vault.owner.equals(swapRegistry)).to.be.true;
+  });
+
+  it("[token_2022] should: proposal owner deposits a Token-2022 item", async () => {
+    await program.methods
+      .createProposal({
+        id: proposalId,
+        swapOptions: [
+          {
+            id: Keypair.generate().publicKey.toBase58().slice(0, 10),
+            askingItems: [
+              {
+                id: Keypair.generate().publicKey.toBase58().slice(0, 10),
+                mintAccount,
+                amount: new BN(1_000),
+                itemType: { currency: {} },
+              },
+            ],
+          },
+        ],
+        offeredItems: [
+          {
+            id: Keypair.generate().publicKey.toBase58().slice(0, 10),
+            mintAccount,
+            amount: new BN(500_000),
+            itemType: { currency: {} },
+          },
+        ],
+        expiredAt: new BN(new Date().getTime() + 1000 * 60 * 60 * 24 * 7),
+      })
+      .accounts({
+        proposalOwner: proposalOwner.publicKey,
+        swapRegistry,
+        swapTreasury,
+        swapProposal,
+      })
+      .signers([proposalOwner])
+      .rpc({ commitment: "confirmed" });
+
+    // the Token-2022 program follows the item accounts
+    await program.methods
+      .depositAll({
+        proposalId,
+        swapTokenVaultBumps: Buffer.from([swapTokenVaultBump]),
+      })
+      .accounts({
+        signer: proposalOwner.publicKey,
+        swapProposal,
+        swapRegistry,
+      })
+      .remainingAccounts([
+        { pubkey: mintAccount, isSigner: false, isWritable: false },
+        { pubkey: ownerTokenAccount, isSigner: false, isWritable: true },
+        { pubkey: swapTokenVault, isSigner: false, isWritable: true },
+        { pubkey: TOKEN_2022_PROGRAM_ID, isSigner: false, isWritable: false },
+      ])
+      .signers([proposalOwner])
+      .rpc({ commitment: "confirmed" });
+
+    const state = await program.account.swapProposal.fetch(swapProposal);
+    // @ts-ignore
+    expect(!!state.status.deposited).to.be.true;
+    expect(state.offeredItems[0].amount.toNumber()).eq(500_000);
+
+    const vault = await getAccount(
+      provider.connection,
+      swapTokenVault,
+      "confirmed",
+      TOKEN_2022_PROGRAM_ID
+    );
+    expect(Number(vault.amount)).eq(500_000);
+  });
+
+  it("[token_2022] should: proposal owner deposits all items with the Token-2022 program as the token program", async () => {
+    const id = Keypair.generate().publicKey.toBase58().slice(0, 10);
+    const [batchProposal] = await PublicKey.findProgramAddress(
+      [
+        anchor.utils.bytes.utf8.encode("SEED::SWAP::PROPOSAL_SEED"),
+        proposalOwner.publicKey.toBuffer(),
+        createHash("sha256").update(id).digest(),
+      ],
+      program.programId
+    );
+
+    await program.methods
+      .createProposal({
+        id,
+        swapOptions: [
+          {
+            id: Keypair.generate().publicKey.toBase58().slice(0, 10),
+            askingItems: [
+              {
+                id: Keypair.generate().publicKey.toBase58().slice(0, 10),
+                mintAccount,
+                amount: new BN(1_000),
+                itemType: { currency: {} },
+              },
+            ],
+          },
+        ],
+        offeredItems: [
+          {
+            id: Keypair.generate().publicKey.toBase58().slice(0, 10),
+            mintAccount,
+            amount: new BN(50_000),
+            itemType: { currency: {} },
+          },
+        ],
+        expiredAt: new BN(new Date().getTime() + 1000 * 60 * 60 * 24 * 7),
+      })
+      .accounts({
+        proposalOwner: proposalOwner.publicKey,
+        swapRegistry,
+        swapTreasury,
+        swapProposal: batchProposal,
+      })
+      .signers([proposalOwner])
+      .rpc({ commitment: "confirmed" });
+
+    // no token program follows the item accounts
+    await program.methods
+      .depositAll({
+        proposalId: id,
+        swapTokenVaultBumps: Buffer.from([swapTokenVaultBump]),
+      })
+      .accounts({
+        signer: proposalOwner.publicKey,
+        swapProposal: batchProposal,
+        swapRegistry,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+      })
+      .remainingAccounts([
+        { pubkey: mintAccount, isSigner: false, isWritable: false },
+        { pubkey: ownerTokenAccount, isSigner: false, isWritable: true },
+        { pubkey: swapTokenVault, isSigner: false, isWritable: true },
+      ])
+      .signers([proposalOwner])
+      .rpc({ commitment: "confirmed" });
+
+    const state = await program.account.swapProposal.fetch(batchProposal);
+    // @ts-ignore
+    expect(!!state.status.deposited).to.be.true;
+    expect(state.offeredItems[0].amount.toNumber()).eq(50_000);
+  });
+
+  it("[token_2022] should: proposal owner deposits and withdraws a Token-2022 item one item at a time", async () => {
+    const id = Keypair.generate().publicKey.toBase58().slice(0, 10);
+    const itemId = Keypair.generate().publicKey.toBase58().slice(0, 10);
+    const [singleItemProposal] = await PublicKey.findProgramAddress(
+      [
+        anchor.utils.bytes.utf8.encode("SEED::SWAP::PROPOSAL_SEED"),
+        proposalOwner.publicKey.toBuffer(),
+        createHash("sha256").update(id).digest(),
+      ],
+      program.programId
+    );
+
+    await program.methods
+      .createProposal({
+        id,
+        swapOptions: [
+          {
+            id: Keypair.generate().publicKey.toBase58().slice(0, 10),
+            askingItems: [
+              {
+                id: Keypair.generate().publicKey.toBase58().slice(0, 10),
+                mintAccount,
+                amount: new BN(1_000),
+                itemType: { currency: {} },
+              },
+            ],
+          },
+        ],
+        offeredItems: [
+          {
+            id: itemId,
+            mintAccount,
+            amount: new BN(100_000),
+            itemType: { currency: {} },
+          },
+        ],
+        expiredAt: new BN(new Date().getTime() + 1000 * 60 * 60 * 24 * 7),
+      })
+      .accounts({
+        proposalOwner: proposalOwner.publicKey,
+        swapRegistry,
+        swapTreasury,
+        swapProposal: singleItemProposal,
+      })
+      .signers([proposalOwner])
+      .rpc({ commitment: "confirmed" });
+
+    const balanceBefore = Number(
+      (
+        await getAccount(
+          provider.connection,
+          ownerTokenAccount,
+          "confirmed",
+          TOKEN_2022_PROGRAM_ID
+        )
+      ).amount
+    );
+
+    await program.methods
+      // @ts-ignore
+      .transferAssetsToVault({
+        proposalId: id,
+        swapItemId: itemId,
+        swapTokenVaultBump,
+        actionType: { depositing: {} },
+        optionId: "",
+      })
+      .accounts({
+        signer: proposalOwner.publicKey,
+        signerTokenAccount: ownerTokenAccount,
+        swapProposal: singleItemProposal,
+        swapTokenVault,
+        swapRegistry,
+        swapTreasury,
+        mintAccount,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+      })
+      .signers([proposalOwner])
+      .rpc({ commitment: "confirmed" });
+
+    expect(
+      Number(
+        (
+          await getAccount(
+            provider.connection,
+            ownerTokenAccount,
+            "confirmed",
+            TOKEN_2022_PROGRAM_ID
+          )
+        ).amount
+      )
+    ).eq(balanceBefore - 100_000);
+
+    await program.methods
+      .cancelProposal({ id })
+      .accounts({
+        swapProposal: singleItemProposal,
+        signer: proposalOwner.publicKey,
+      })
+      .signers([proposalOwner])
+      .rpc({ commitment: "confirmed" });
+
+    await program.methods
+      // @ts-ignore
+      .transferAssetsFromVault({
+        proposalId: id,
+        swapItemId: itemId,
+        swapTokenVaultBump,
+        actionType: { withdrawing: {} },
+      })
+      .accounts({
+        signer: proposalOwner.publicKey,
+        signerTokenAccount: ownerTokenAccount,
+        swapProposal: singleItemProposal,
+        swapTokenVault,
+        swapRegistry,
+        mintAccount,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+      })
+      .signers([proposalOwner])
+      .rpc({ commitment: "confirmed" });
+
+    const state = await program.account.swapProposal.fetch(singleItemProposal);
+    // @ts-ignore
+    expect(!!state.status.withdrawn).to.be.true;
+    expect(
+      Number(
+        (
+          await getAccount(
+            provider.connection,
+            ownerTokenAccount,
+            "confirmed",
+            TOKEN_2022_PROGRAM_ID
+          )
+        ).amount
+      )
+    ).eq(balanceBefore);
+  });
+
+  it("[token_2022] should: deployer removes a Token-2022 mint with an empty vault", async () => {
+    const removedMintAccount = await createMint(
+      provider.connection,
+      deployer.payer,
+      deployer.publicKey,
+      deployer.publicKey,
+      6,
+      undefined,
+      undefined,
+      TOKEN_2022_PROGRAM_ID
+    );
+    const [removedTokenVault] = await PublicKey.findProgramAddress(
+      [
+        anchor.utils.bytes.utf8.encode("SEED::SWAP::TOKEN_VAULT_SEED"),
+        removedMintAccount.toBytes(),
+      ],
+      program.programId
+    );
+
+    await program.methods
+      .createTokenVault()
+      .accounts({
+        signer: deployer.publicKey,
+        mintAccount: removedMintAccount,
+        swapRegistry,
+        swapTokenVault: removedTokenVault,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+      })
+      .signers([deployer.payer])
+      .rpc({ commitment: "confirmed" });
+
+    await program.methods
+      .removeMint()
+      .accounts({
+        mintAccount: removedMintAccount,
+        swapRegistry,
+        swapTokenVault: removedTokenVault,
+        owner: deployer.publicKey,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+      })
+      .signers([deployer.payer])
+      .rpc({ commitment: "confirmed" });
+
+    const state = await program.account.swapPlatformRegistry.fetch(
+      swapRegistry
+    );
+    expect(
+      state.allowedMintAccounts.find((mintInfo) =>
+        mintInfo.mintAccount.equals(removedMintAccount)
+      )
+    ).to.be.undefined;
+    expect(await provider.connection.getAccountInfo(removedTokenVault)).to.be
+      .null;
+  });
+
+  it("[token_2022] should: record the amount that arrived for a transfer-fee mint", async () => {
+    feeProposalId = Keypair.generate().publicKey.toBase58().slice(0, 10);
+    feeOptionId = Keypair.generate().publicKey.toBase58().slice(0, 10);
+    feeOfferedItemId = Keypair.generate().publicKey.toBase58().slice(0, 10);
+    feeAskingItemId = Keypair.generate().publicKey.toBase58().slice(0, 10);
+    [feeProposal] = await PublicKey.findProgramAddress(
+      [
+        anchor.utils.bytes.utf8.encode("SEED::SWAP::PROPOSAL_SEED"),
+        proposalOwner.publicKey.toBuffer(),
+        createHash("sha256").update(feeProposalId).digest(),
+      ],
+      program.programId
+    );
+
+    await program.methods
+      .createProposal({
+        id: feeProposalId,
+        swapOptions: [
+          {
+            id: feeOptionId,
+            askingItems: [
+              {
+                id: feeAskingItemId,
+                mintAccount: feeMintAccount,
+                amount: new BN(10_000),
+                itemType: { currency: {} },
+              },
+            ],
+          },
+        ],
+        offeredItems: [
+          {
+            id: feeOfferedItemId,
+            mintAccount: feeMintAccount,
+            amount: new BN(100_000),
+            itemType: { currency: {} },
+          },
+        ],
+        expiredAt: new BN(new Date().getTime() + 1000 * 60 * 60 * 24 * 7),
+      })
+      .accounts({
+        proposalOwner: proposalOwner.publicKey,
+        swapRegistry,
+        swapTreasury,
+        swapProposal: feeProposal,
+      })
+      .signers([proposalOwner])
+      .rpc({ commitment: "confirmed" });
+
+    for (const [user, itemId, actionType, optionId] of [
+      [proposalOwner, feeOfferedItemId, { depositing: {} }, ""],
+      [participant, feeAskingItemId, { fulfilling: {} }, feeOptionId],
+    ] as [Keypair, string, object, string][]) {
+      await program.methods
+        // @ts-ignore
+        .transferAssetsToVault({
+          proposalId: feeProposalId,
+          swapItemId: itemId,
+          swapTokenVaultBump: feeTokenVaultBump,
+          actionType,
+          optionId,
+        })
+        .accounts({
+          signer: user.publicKey,
+          signerTokenAccount: feeTokenAccounts[user.publicKey.toBase58()],
+          swapProposal: feeProposal,
+          swapTokenVault: feeTokenVault,
+          swapRegistry,
+          swapTreasury,
+          mintAccount: feeMintAccount,
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+        })
+        .signers([user])
+        .rpc({ commitment: "confirmed" });
+    }
+
+    // the transfer fee is withheld on the way into the vault
+    const state = await program.account.swapProposal.fetch(feeProposal);
+    // @ts-ignore
+    expect(!!state.status.fulfilled).to.be.true;
+    expect(state.offeredItems[0].amount.toNumber()).lt(100_000);
+    expect(state.offeredItems[0].amount.toNumber()).eq(
+      100_000 - (100_000 * TRANSFER_FEE_BPS) / 10_000
+    );
+    expect(state.swapOptions[0].askingItems[0].amount.toNumber()).lt(10_000);
+  });
+
+  it("[token_2022] should: participant redeems the transfer-fee item and the platform fee is withdrawn", async () => {
+    const participantTokenAccount =
+      feeTokenAccounts[participant.publicKey.toBase58()];
+    const balanceBefore = await getFeeBalance(participantTokenAccount);
+    const accruedBefore = await findAccruedFee();
+
+    await program.methods
+      // @ts-ignore
+      .transferAssetsFromVault({
+        proposalId: feeProposalId,
+        swapItemId: feeOfferedItemId,
+        swapTokenVaultBump: feeTokenVaultBump,
+        actionType: { redeeming: {} },
+      })
+      .accounts({
+        signer: participant.publicKey,
+        signerTokenAccount: participantTokenAccount,
+        swapProposal: feeProposal,
+        swapTokenVault: feeTokenVault,
+        swapRegistry,
+        mintAccount: feeMintAccount,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+      })
+      .signers([participant])
+      .rpc({ commitment: "confirmed" });
+
+    const state = await program.account.swapProposal.fetch(feeProposal);
+    // @ts-ignore
+    expect(!!state.offeredItems[0].status.redeemed).to.be.true;
+
+    // the platform fee is kept in the shared vault, the rest arrives less the transfer fee
+    const storedAmount = state.offeredItems[0].amount.toNumber();
+    const platformFee = Math.floor((storedAmount * PLATFORM_FEE_BPS) / 10_000);
+    const received =
+      (await getFeeBalance(participantTokenAccount)) - balanceBefore;
+    expect(received).gt(0);
+    expect(received).lt(storedAmount - platformFee);
+    expect((await findAccruedFee()) - accruedBefore).eq(platformFee);
+
+    // the deployer is the fee recipient
+    const feeRecipientTokenAccount = (
+      await getOrCreateAssociatedTokenAccount(
+        provider.connection,
+        deployer.payer,
+        feeMintAccount,
+        deployer.publicKey,
+        false,
+        undefined,
+        undefined,
+        TOKEN_2022_PROGRAM_ID
+      )
+    ).address;
+    const accruedFee = await findAccruedFee();
+
+    await program.methods
+      .withdrawPlatformTokenFees({ amount: new BN(accruedFee) })
+      .accounts({
+        owner: deployer.publicKey,
+        swapRegistry,
+        mintAccount: feeMintAccount,
+        swapTokenVault: feeTokenVault,
+        feeRecipientTokenAccount,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+      })
+      .signers([deployer.payer])
+      .rpc({ commitment: "confirmed" });
+
+    expect(await findAccruedFee()).eq(0);
+    expect(await getFeeBalance(feeRecipientTokenAccount)).gt(0);
+  });
+
+  it("[token_2022] should: per-proposal vault accrues only the platform fee that arrived in the platform vault", async () => {
+    const id = Keypair.generate().publicKey.toBase58().slice(0, 10);
+    const optionId = Keypair.generate().publicKey.toBase58().slice(0, 10);
+    const offeredItemId = Keypair.generate().publicKey.toBase58().slice(0, 10);
+    const askingItemId = Keypair.generate().publicKey.toBase58().slice(0, 10);
+    const [perProposal] = await PublicKey.findProgramAddress(
+      [
+        anchor.utils.bytes.utf8.encode("SEED::SWAP::PROPOSAL_SEED"),
+        proposalOwner.publicKey.toBuffer(),
+        createHash("sha256").update(id).digest(),
+      ],
+      program.programId
+    );
+    const [proposalTokenVault, proposalTokenVaultBump] =
+      await PublicKey.findProgramAddress(
+        [
+          anchor.utils.bytes.utf8.encode("SEED::SWAP::PROPOSAL_VAULT_SEED"),
+          perProposal.toBytes(),
+          feeMintAccount.toBytes(),
+        ],
+        program.programId
+      );
+
+    await program.methods
+      .createProposal({
+        id,
+        swapOptions: [
+          {
+            id: optionId,
+            askingItems: [
+              {
+                id: askingItemId,
+                mintAccount: feeMintAccount,
+                amount: new BN(10_000),
+                itemType: { currency: {} },
+              },
+            ],
+          },
+        ],
+        offeredItems: [
+          {
+            id: offeredItemId,
+            mintAccount: feeMintAccount,
+            amount: new BN(100_000),
+            itemType: { currency: {} },
+          },
+        ],
+        expiredAt: new BN(new Date().getTime() + 1000 * 60 * 60 * 24 * 7),
+        vaultMode: { perProposal: {} },
+      })
+      .accounts({
+        proposalOwner: proposalOwner.publicKey,
+        swapRegistry,
+        swapTreasury,
+        swapProposal: perProposal,
+      })
+      .signers([proposalOwner])
+      .rpc({ commitment: "confirmed" });
+
+    await program.methods
+      .createProposalVault()
+      .accounts({
+        signer: proposalOwner.publicKey,
+        swapRegistry,
+        swapProposal: perProposal,
+        mintAccount: feeMintAccount,
+        proposalTokenVault,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+      })
+      .signers([proposalOwner])
+      .rpc({ commitment: "confirmed" });
+
+    for (const [user, itemId, actionType, optionIdParam] of [
+      [proposalOwner, offeredItemId, { depositing: {} }, ""],
+      [participant, askingItemId, { fulfilling: {} }, optionId],
+    ] as [Keypair, string, object, string][]) {
+      await program.methods
+        // @ts-ignore
+        .transferAssetsToVault({
+          proposalId: id,
+          swapItemId: itemId,
+          swapTokenVaultBump: proposalTokenVaultBump,
+          actionType,
+          optionId: optionIdParam,
+        })
+        .accounts({
+          signer: user.publicKey,
+          signerTokenAccount: feeTokenAccounts[user.publicKey.toBase58()],
+          swapProposal: perProposal,
+          swapTokenVault: proposalTokenVault,
+          swapRegistry,
+          swapTreasury,
+          mintAccount: feeMintAccount,
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+        })
+        .signers([user])
+        .rpc({ commitment: "confirmed" });
+    }
+
+    const accruedBefore = await findAccruedFee();
+    const platformVaultBefore = await getFeeBalance(feeTokenVault);
+
+    // the platform vault of the mint receives the fee of the per-proposal vault
+    await program.methods
+      // @ts-ignore
+      .transferAssetsFromVault({
+        proposalId: id,
+        swapItemId: offeredItemId,
+        swapTokenVaultBump: proposalTokenVaultBump,
+        actionType: { redeeming: {} },
+      })
+      .accounts({
+        signer: participant.publicKey,
+        signerTokenAccount: feeTokenAccounts[participant.publicKey.toBase58()],
+        swapProposal: perProposal,
+        swapTokenVault: proposalTokenVault,
+        swapRegistry,
+        mintAccount: feeMintAccount,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+      })
+      .remainingAccounts([
+        { pubkey: feeTokenVault, isSigner: false, isWritable: true },
+      ])
+      .signers([participant])
+      .rpc({ commitment: "confirmed" });
+
+    const state = await program.account.swapProposal.fetch(perProposal);
+    const platformFee = Math.floor(
+      (state.offeredItems[0].amount.toNumber() * PLATFORM_FEE_BPS) / 10_000
+    );
+    const receivedFee =
+      (await getFeeBalance(feeTokenVault)) - platformVaultBefore;
+
+    // the transfer fee is withheld from the platform fee on its way into the platform vault
+    expect(receivedFee).lt(platformFee);
+    expect((await findAccruedFee()) - accruedBefore).eq(receivedFee);
+  });
+});
@@ -0,0 +1,242 @@
+import * as anchor from "@project-serum/anchor";
+import {
+  AnchorError,
+  BN,
+  BorshCoder,
+  EventParser,
+  Program,
+  web3,
+} from "@project-serum/anchor";
+import { Keypair, PublicKey } from "@solana/web3.js";
+import { getAccount } from "@solana/spl-token";
+import { expect } from "chai";
+
+import { Swap } from "../target/types/swap";
+import { airdrop, createProposal, itemAccounts, setupMint } from "./fixtures";
+
+describe("transfer_all_assets", async () => {
+  // Configure the client to use the local cluster.
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.Swap as Program<Swap>;
+  const deployer = provider.wallet as anchor.Wallet;
+
+  // find the swap account
+  const [swapRegistry] = await PublicKey.findProgramAddress(
+    [anchor.utils.bytes.utf8.encode("SEED::SWAP::PLATFORM")],
+    program.programId
+  );
+  const [swapTreasury] = await PublicKey.findProgramAddress(
+    [anchor.utils.bytes.utf8.encode("SEED::SWAP::TREASURY_SEED")],
+    program.programId
+  );
+
+  const proposalOwner = Keypair.generate();
+  const participant = Keypair.generate();
+  const optionId = Keypair.generate().publicKey.toBase58().slice(0, 10);
+
+  let offeredMint;
+  let askingMint;
+
+  // create a proposal and deposit every offered item
+  const setupProposal = async () => {
+    const proposalId = Keypair.generate().publicKey.toBase58().slice(0, 10);
+    const swapProposal = await createProposal(proposalOwner, {
+      id: proposalId,
+      swapOptions: [
+        {
+          id: optionId,
+          askingItems: [
+            {
+              id: Keypair.generate().publicKey.toBase58().slice(0, 10),
+              mintAccount: askingMint.mintAccount,
+              amount: new BN(web3.LAMPORTS_PER_SOL * 2),
+              itemType: { currency: {} },
+            },
+          ],
+        },
+      ],
+      offeredItems: [0, 1].map(() => ({
+        id: Keypair.generate().publicKey.toBase58().slice(0, 10),
+        mintAccount: offeredMint.mintAccount,
+        amount: new BN(web3.LAMPORTS_PER_SOL),
+        itemType: { currency: {} },
+      })),
+    });
+
+    await program.methods
+      .depositAll({
+        proposalId,
+        swapTokenVaultBumps: Buffer.from([
+          offeredMint.swapTokenVaultBump,
+          offeredMint.swapTokenVaultBump,
+        ]),
+      })
+      .accounts({
+        signer: proposalOwner.publicKey,
+        swapProposal,
+        swapRegistry,
+      })
+      .remainingAccounts(
+        [0, 1].flatMap(() =>
+          itemAccounts(
+            offeredMint,
+            offeredMint.tokenAccounts[proposalOwner.publicKey.toBase58()]
+          )
+        )
+      )
+      .signers([proposalOwner])
+      .rpc({ commitment: "confirmed" });
+
+    return { proposalId, swapProposal };
+  };
+
+  before(async () => {
+    // funding the users
+    await airdrop([proposalOwner, participant]);
+
+    offeredMint = await setupMint([proposalOwner, participant]);
+    askingMint = await setupMint([proposalOwner, participant]);
+  });
+
+  it("[withdraw_all] should: proposal owner withdraws every offered item of a canceled proposal", async () => {
+    const { proposalId, swapProposal } = await setupProposal();
+
+    await program.methods
+      .cancelProposal({ id: proposalId })
+      .accounts({
+        swapProposal,
+        signer: proposalOwner.publicKey,
+      })
+      .signers([proposalOwner])
+      .rpc({ commitment: "confirmed" });
+
+    const tx = await program.methods
+      .withdrawAll({
+        proposalId,
+        swapTokenVaultBumps: Buffer.from([
+          offeredMint.swapTokenVaultBump,
+          offeredMint.swapTokenVaultBump,
+        ]),
+      })
+      .accounts({
+        signer: proposalOwner.publicKey,
+        swapRegistry,
+        swapProposal,
+      })
+      .remainingAccounts(
+        [0, 1].flatMap(() =>
+          itemAccounts(
+            offeredMint,
+            offeredMint.tokenAccounts[proposalOwner.publicKey.toBase58()]
+          )
+        )
+      )
+      .signers([proposalOwner])
+      .rpc({ commitment: "confirmed" });
+
+    const state = await program.account.swapProposal.fetch(swapProposal);
+    // @ts-ignore
+    expect(!!state.status.withdrawn).to.be.true;
+
+    const ownerAccount = await getAccount(
+      provider.connection,
+      offeredMint.tokenAccounts[proposalOwner.publicKey.toBase58()]
+    );
+    expect(Number(ownerAccount.amount)).eq(web3.LAMPORTS_PER_SOL * 10);
+
+    // one event per withdrawn item
+    const transaction = await provider.connection.getParsedTransaction(tx, {
+      commitment: "confirmed",
+    });
+    const eventParser = new EventParser(
+      program.programId,
+      new BorshCoder(program.idl)
+    );
+    const events = [
+      ...eventParser.parseLogs(transaction.meta.logMessages),
+    ].filter((event) => event.name === "ItemWithdrawn");
+    expect(events.length).eq(2);
+  });
+
+  it("[redeem_all] should: only participants redeem every item they are entitled to", async () => {
+    const { proposalId, swapProposal } = await setupProposal();
+
+    await program.methods
+      .acceptProposal({
+        proposalId,
+        optionId,
+        askingVaultBumps: Buffer.from([askingMint.swapTokenVaultBump]),
+        offeredVaultBumps: Buffer.from([
+          offeredMint.swapTokenVaultBump,
+          offeredMint.swapTokenVaultBump,
+        ]),
+        settleToOwner: false,
+      })
+      .accounts({
+        signer: participant.publicKey,
+        swapProposal,
+        swapRegistry,
+        swapTreasury,
+      })
+      .remainingAccounts([
+        ...itemAccounts(
+          askingMint,
+          askingMint.tokenAccounts[participant.publicKey.toBase58()]
+        ),
+        ...[0, 1].flatMap(() =>
+          itemAccounts(
+            offeredMint,
+            offeredMint.tokenAccounts[participant.publicKey.toBase58()]
+          )
+        ),
+      ])
+      .signers([participant])
+      .rpc({ commitment: "confirmed" });
+
+    try {
+      await program.methods
+        .redeemAll({
+          proposalId,
+          swapTokenVaultBumps: Buffer.from([]),
+        })
+        .accounts({
+          signer: deployer.publicKey,
+          swapRegistry,
+          swapProposal,
+        })
+        .signers([deployer.payer])
+        .rpc({ commitment: "confirmed" });
+
+      throw new Error("Should be failed");
+    } catch (e) {
+      expect(e instanceof AnchorError).to.be.true;
+      expect(e.error.errorCode.code).equals("OnlyParticipant");
+    }
+
+    // the proposal owner redeems the asking items left in the vault
+    await program.methods
+      .redeemAll({
+        proposalId,
+        swapTokenVaultBumps: Buffer.from([askingMint.swapTokenVaultBump]),
+      })
+      .accounts({
+        signer: proposalOwner.publicKey,
+        swapRegistry,
+        swapProposal,
+      })
+      .remainingAccounts(
+        itemAccounts(
+          askingMint,
+          askingMint.tokenAccounts[proposalOwner.publicKey.toBase58()]
+        )
+      )
+      .signers([proposalOwner])
+      .rpc({ commitment: "confirmed" });
+
+    const state = await program.account.swapProposal.fetch(swapProposal);
+    // @ts-ignore
+    expect(!!state.status.redeemed).to.be.true;
+  });
+});
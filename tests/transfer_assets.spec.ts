@@ -1,5 +1,5 @@
 import * as anchor from "@project-serum/anchor";
-import { BN, Program, web3 } from "@project-serum/anchor";
+import { AnchorError, BN, Program, web3 } from "@project-serum/anchor";
 import { Keypair, PublicKey } from "@solana/web3.js";
 import {
   createMint,
@@ -8,6 +8,7 @@ import {
   getAccount,
 } from "@solana/spl-token";
 import { expect } from "chai";
+import { createHash } from "crypto";
 
 import { Swap } from "../target/types/swap";
 
@@ -24,6 +25,10 @@ describe("transfer_assets", async () => {
     [anchor.utils.bytes.utf8.encode("SEED::SWAP::PLATFORM")],
     program.programId
   );
+  const [swapTreasury] = await PublicKey.findProgramAddress(
+    [anchor.utils.bytes.utf8.encode("SEED::SWAP::TREASURY_SEED")],
+    program.programId
+  );
 
   let mintNormalPublicKey;
   let swapTokenVault;
@@ -37,10 +42,41 @@ describe("transfer_assets", async () => {
   let participantTokenAccount;
   let expiredAt;
   let swapTokenVaultBump;
+  let otherMintPublicKey;
+  let participantOtherTokenAccount;
+  let poorParticipantTokenAccount;
 
   // Construct accounts for proposal creation
   proposalOwner = Keypair.generate();
   participant = Keypair.generate();
+  const poorParticipant = Keypair.generate();
+
+  // the participant fulfills the first asking item of the second option with the given accounts
+  const fulfillAskingItem = (
+    signer: Keypair,
+    signerTokenAccount: PublicKey,
+    mintAccount: PublicKey
+  ) =>
+    program.methods
+      // @ts-ignore
+      .transferAssetsToVault({
+        proposalId,
+        swapItemId: swapOptions[1].askingItems[0].id,
+        swapTokenVaultBump,
+        actionType: { fulfilling: {} },
+        optionId: swapOptions[1].id,
+      })
+      .accounts({
+        signerTokenAccount,
+        signer: signer.publicKey,
+        swapProposal,
+        swapTokenVault,
+        swapRegistry,
+        swapTreasury,
+        mintAccount,
+      })
+      .signers([signer])
+      .rpc({ commitment: "confirmed" });
 
   before(async () => {
     // funding proposal owner
@@ -125,6 +161,48 @@ describe("transfer_assets", async () => {
       participant.publicKey
     );
 
+    // another mint held by the participant, and a participant without balance
+    otherMintPublicKey = await createMint(
+      provider.connection,
+      deployer.payer,
+      deployer.publicKey,
+      deployer.publicKey,
+      9
+    );
+    participantOtherTokenAccount = await getOrCreateAssociatedTokenAccount(
+      provider.connection,
+      participant,
+      otherMintPublicKey,
+      participant.publicKey
+    );
+    await mintTo(
+      provider.connection,
+      deployer.payer,
+      otherMintPublicKey,
+      participantOtherTokenAccount.address,
+      deployer.publicKey,
+      web3.LAMPORTS_PER_SOL * 100
+    );
+
+    const airdropForPoorParticipantSignature =
+      await provider.connection.requestAirdrop(
+        poorParticipant.publicKey,
+        web3.LAMPORTS_PER_SOL
+      );
+    await provider.connection.confirmTransaction({
+      signature: airdropForPoorParticipantSignature,
+      blockhash: (await provider.connection.getLatestBlockhash()).blockhash,
+      lastValidBlockHeight: (
+        await provider.connection.getLatestBlockhash()
+      ).lastValidBlockHeight,
+    });
+    poorParticipantTokenAccount = await getOrCreateAssociatedTokenAccount(
+      provider.connection,
+      poorParticipant,
+      mintNormalPublicKey,
+      poorParticipant.publicKey
+    );
+
     [swapTokenVault, swapTokenVaultBump] = await PublicKey.findProgramAddress(
       [
         anchor.utils.bytes.utf8.encode("SEED::SWAP::TOKEN_VAULT_SEED"),
@@ -138,7 +216,8 @@ describe("transfer_assets", async () => {
     [swapProposal] = await PublicKey.findProgramAddress(
       [
         anchor.utils.bytes.utf8.encode("SEED::SWAP::PROPOSAL_SEED"),
-        anchor.utils.bytes.utf8.encode(proposalId),
+        proposalOwner.publicKey.toBuffer(),
+        createHash("sha256").update(proposalId).digest(),
       ],
       program.programId
     );
@@ -207,11 +286,12 @@ describe("transfer_assets", async () => {
       createVaultInstruction = await program.methods
         .createTokenVault()
         .accounts({
+          signer: deployer.publicKey,
           mintAccount: mintNormalPublicKey,
           swapRegistry,
           swapTokenVault,
         })
-        .signers([proposalOwner])
+        .signers([deployer.payer])
         .instruction();
     }
 
@@ -233,6 +313,8 @@ describe("transfer_assets", async () => {
               signerTokenAccount: proposalOwnerTokenAccount.address,
               swapProposal,
               swapTokenVault,
+              swapRegistry,
+              swapTreasury,
               mintAccount: mintNormalPublicKey,
             })
             .signers([proposalOwner])
@@ -256,6 +338,7 @@ describe("transfer_assets", async () => {
       .accounts({
         proposalOwner: proposalOwner.publicKey,
         swapRegistry,
+        swapTreasury,
         swapProposal: swapProposal,
       })
       .signers([proposalOwner])
@@ -293,6 +376,51 @@ describe("transfer_assets", async () => {
     );
   });
 
+  it("[fulfil_assets] should: participant fails to fulfill with a mint other than the asking item", async () => {
+    try {
+      await fulfillAskingItem(
+        participant,
+        participantOtherTokenAccount.address,
+        otherMintPublicKey
+      );
+
+      throw new Error("Should be failed");
+    } catch (e) {
+      expect(e instanceof AnchorError).to.be.true;
+      expect(e.error.errorCode.code).equals("MintAccountMismatch");
+    }
+  });
+
+  it("[fulfil_assets] should: participant fails to fulfill from a token account of another mint", async () => {
+    try {
+      await fulfillAskingItem(
+        participant,
+        participantOtherTokenAccount.address,
+        mintNormalPublicKey
+      );
+
+      throw new Error("Should be failed");
+    } catch (e) {
+      expect(e instanceof AnchorError).to.be.true;
+      expect(e.error.errorCode.code).equals("InvalidTokenAccountMint");
+    }
+  });
+
+  it("[fulfil_assets] should: participant fails to fulfill without enough balance", async () => {
+    try {
+      await fulfillAskingItem(
+        poorParticipant,
+        poorParticipantTokenAccount.address,
+        mintNormalPublicKey
+      );
+
+      throw new Error("Should be failed");
+    } catch (e) {
+      expect(e instanceof AnchorError).to.be.true;
+      expect(e.error.errorCode.code).equals("InsufficientBalance");
+    }
+  });
+
   it("[fulfil_assets] should: participant fulfill proposal successfully", async () => {
     expect(Number(participantTokenAccount.amount)).equals(
       web3.LAMPORTS_PER_SOL * 100
@@ -331,6 +459,8 @@ describe("transfer_assets", async () => {
               signer: participant.publicKey,
               swapProposal,
               swapTokenVault,
+              swapRegistry,
+              swapTreasury,
               mintAccount: mintNormalPublicKey,
             })
             .signers([participant])
@@ -387,6 +517,34 @@ describe("transfer_assets", async () => {
     );
   });
 
+  it("[redeem_assets] should: participant fails to redeem into a token account owned by someone else", async () => {
+    try {
+      await program.methods
+        // @ts-ignore
+        .transferAssetsFromVault({
+          proposalId,
+          swapItemId: offeredItems[0].id,
+          swapTokenVaultBump,
+          actionType: { redeeming: {} },
+        })
+        .accounts({
+          signerTokenAccount: proposalOwnerTokenAccount.address,
+          signer: participant.publicKey,
+          swapProposal,
+          swapTokenVault,
+          swapRegistry,
+          mintAccount: mintNormalPublicKey,
+        })
+        .signers([participant])
+        .rpc({ commitment: "confirmed" });
+
+      throw new Error("Should be failed");
+    } catch (e) {
+      expect(e instanceof AnchorError).to.be.true;
+      expect(e.error.errorCode.code).equals("InvalidTokenAccountOwner");
+    }
+  });
+
   it("[redeem_assets] should: proposal owner can redeem items once the proposal is fulfilled", async () => {
     const swapOption = swapOptions[1];
 
@@ -0,0 +1,139 @@
+import * as anchor from "@project-serum/anchor";
+import {
+  AnchorError,
+  BorshCoder,
+  EventParser,
+  Program,
+  web3,
+} from "@project-serum/anchor";
+import { Keypair, PublicKey } from "@solana/web3.js";
+import { expect } from "chai";
+
+import { Swap } from "../target/types/swap";
+
+describe("transfer_ownership", async () => {
+  // Configure the client to use the local cluster.
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.Swap as Program<Swap>;
+  const deployer = provider.wallet as anchor.Wallet;
+  const newOwner = Keypair.generate();
+  const otherUser = Keypair.generate();
+
+  // find the swap account
+  const [swapRegistry] = await PublicKey.findProgramAddress(
+    [anchor.utils.bytes.utf8.encode("SEED::SWAP::PLATFORM")],
+    program.programId
+  );
+
+  before(async () => {
+    // funding the new owner
+    const airdropSignature = await provider.connection.requestAirdrop(
+      newOwner.publicKey,
+      web3.LAMPORTS_PER_SOL
+    );
+    await provider.connection.confirmTransaction({
+      signature: airdropSignature,
+      blockhash: (await provider.connection.getLatestBlockhash()).blockhash,
+      lastValidBlockHeight: (
+        await provider.connection.getLatestBlockhash()
+      ).lastValidBlockHeight,
+    });
+  });
+
+  it("[transfer_ownership] should: deployer proposes a new owner successfully", async () => {
+    const tx = await program.methods
+      .proposeOwner({
+        newOwner: newOwner.publicKey,
+      })
+      .accounts({
+        swapRegistry,
+        owner: deployer.publicKey,
+      })
+      .signers([deployer.payer])
+      .rpc({ commitment: "confirmed" });
+
+    const state = await program.account.swapPlatformRegistry.fetch(
+      swapRegistry
+    );
+
+    // Expect conditions
+    expect(state.owner.equals(deployer.publicKey)).to.be.true;
+    expect(state.pendingOwner.equals(newOwner.publicKey)).to.be.true;
+
+    // expect eventLog
+    const transaction = await provider.connection.getParsedTransaction(tx, {
+      commitment: "confirmed",
+    });
+    const eventParser = new EventParser(
+      program.programId,
+      new BorshCoder(program.idl)
+    );
+    const [event] = eventParser.parseLogs(transaction.meta.logMessages);
+
+    // Expect emitted logs
+    expect(event.name).equals("OwnershipTransferProposed");
+    expect(event.data.pendingOwner.toString()).equals(
+      newOwner.publicKey.toString()
+    );
+  });
+
+  it("[transfer_ownership] should: non-pending owner fails to accept the ownership", async () => {
+    try {
+      await program.methods
+        .acceptOwnership()
+        .accounts({
+          swapRegistry,
+          newOwner: otherUser.publicKey,
+        })
+        .signers([otherUser])
+        .rpc({ commitment: "confirmed" });
+
+      throw new Error("Should be failed");
+    } catch (e) {
+      expect(e instanceof AnchorError).to.be.true;
+    }
+  });
+
+  it("[transfer_ownership] should: pending owner accepts the ownership successfully", async () => {
+    await program.methods
+      .acceptOwnership()
+      .accounts({
+        swapRegistry,
+        newOwner: newOwner.publicKey,
+      })
+      .signers([newOwner])
+      .rpc({ commitment: "confirmed" });
+
+    let state = await program.account.swapPlatformRegistry.fetch(swapRegistry);
+
+    // Expect conditions
+    expect(state.owner.equals(newOwner.publicKey)).to.be.true;
+    expect(state.pendingOwner.equals(PublicKey.default)).to.be.true;
+
+    // hand the ownership back to the deployer for the next specs
+    await program.methods
+      .proposeOwner({
+        newOwner: deployer.publicKey,
+      })
+      .accounts({
+        swapRegistry,
+        owner: newOwner.publicKey,
+      })
+      .signers([newOwner])
+      .rpc({ commitment: "confirmed" });
+
+    await program.methods
+      .acceptOwnership()
+      .accounts({
+        swapRegistry,
+        newOwner: deployer.publicKey,
+      })
+      .signers([deployer.payer])
+      .rpc({ commitment: "confirmed" });
+
+    state = await program.account.swapPlatformRegistry.fetch(swapRegistry);
+    expect(state.owner.equals(deployer.publicKey)).to.be.true;
+  });
+});
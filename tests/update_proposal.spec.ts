@@ -0,0 +1,166 @@
+import * as anchor from "@project-serum/anchor";
+import { AnchorError, BN, Program, web3 } from "@project-serum/anchor";
+import { Keypair, PublicKey } from "@solana/web3.js";
+import { expect } from "chai";
+
+import { Swap } from "../target/types/swap";
+import { airdrop, createProposal, itemAccounts, setupMint } from "./fixtures";
+
+describe("update_proposal", async () => {
+  // Configure the client to use the local cluster.
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.Swap as Program<Swap>;
+
+  // find the swap account
+  const [swapRegistry] = await PublicKey.findProgramAddress(
+    [anchor.utils.bytes.utf8.encode("SEED::SWAP::PLATFORM")],
+    program.programId
+  );
+
+  const proposalOwner = Keypair.generate();
+  const participant = Keypair.generate();
+  const proposalId = Keypair.generate().publicKey.toBase58().slice(0, 10);
+  const optionId = Keypair.generate().publicKey.toBase58().slice(0, 10);
+
+  let swapProposal;
+  let offeredMint;
+  let askingMint;
+
+  before(async () => {
+    // funding the users
+    await airdrop([proposalOwner, participant]);
+
+    offeredMint = await setupMint([proposalOwner, participant]);
+    askingMint = await setupMint([proposalOwner, participant]);
+
+    swapProposal = await createProposal(proposalOwner, {
+      id: proposalId,
+      swapOptions: [
+        {
+          id: optionId,
+          askingItems: [
+            {
+              id: Keypair.generate().publicKey.toBase58().slice(0, 10),
+              mintAccount: askingMint.mintAccount,
+              amount: new BN(web3.LAMPORTS_PER_SOL * 2),
+              itemType: { currency: {} },
+            },
+          ],
+        },
+      ],
+      offeredItems: [
+        {
+          id: Keypair.generate().publicKey.toBase58().slice(0, 10),
+          mintAccount: offeredMint.mintAccount,
+          amount: new BN(web3.LAMPORTS_PER_SOL),
+          itemType: { currency: {} },
+        },
+      ],
+    });
+  });
+
+  it("[update_proposal] should: fail when the signer is not the proposal owner", async () => {
+    try {
+      await program.methods
+        .updateProposal({
+          id: proposalId,
+          swapOptions: null,
+          expiredAt: new BN(new Date().getTime() + 1000 * 60 * 60 * 24 * 14),
+        })
+        .accounts({
+          proposalOwner: participant.publicKey,
+          swapProposal,
+          swapRegistry,
+        })
+        .signers([participant])
+        .rpc({ commitment: "confirmed" });
+
+      throw new Error("Should be failed");
+    } catch (e) {
+      expect(e instanceof AnchorError).to.be.true;
+      expect(e.error.errorCode.code).equals("OnlyOwner");
+    }
+  });
+
+  it("[update_proposal] should: fail to ask for un-allowed mint tokens", async () => {
+    try {
+      await program.methods
+        .updateProposal({
+          id: proposalId,
+          swapOptions: [
+            {
+              id: optionId,
+              askingItems: [
+                {
+                  id: Keypair.generate().publicKey.toBase58().slice(0, 10),
+                  mintAccount: Keypair.generate().publicKey,
+                  amount: new BN(web3.LAMPORTS_PER_SOL),
+                  itemType: { currency: {} },
+                },
+              ],
+            },
+          ],
+          expiredAt: null,
+        })
+        .accounts({
+          proposalOwner: proposalOwner.publicKey,
+          swapProposal,
+          swapRegistry,
+        })
+        .signers([proposalOwner])
+        .rpc({ commitment: "confirmed" });
+
+      throw new Error("Should be failed");
+    } catch (e) {
+      expect(e instanceof AnchorError).to.be.true;
+      expect(e.error.errorCode.code).equals("UnAllowedMintToken");
+    }
+  });
+
+  it("[update_proposal] should: proposal owner replaces the options and extends the expiry", async () => {
+    const expiredAt = new BN(new Date().getTime() + 1000 * 60 * 60 * 24 * 14);
+    const newOptionId = Keypair.generate().publicKey.toBase58().slice(0, 10);
+
+    await program.methods
+      .updateProposal({
+        id: proposalId,
+        swapOptions: [optionId, newOptionId].map((id) => ({
+          id,
+          askingItems: [
+            {
+              id: Keypair.generate().publicKey.toBase58().slice(0, 10),
+              mintAccount: askingMint.mintAccount,
+              amount: new BN(web3.LAMPORTS_PER_SOL),
+              itemType: { currency: {} },
+            },
+            {
+              id: Keypair.generate().publicKey.toBase58().slice(0, 10),
+              mintAccount: offeredMint.mintAccount,
+              amount: new BN(web3.LAMPORTS_PER_SOL),
+              itemType: { currency: {} },
+            },
+          ],
+        })),
+        expiredAt,
+      })
+      .accounts({
+        proposalOwner: proposalOwner.publicKey,
+        swapProposal,
+        swapRegistry,
+      })
+      .signers([proposalOwner])
+      .rpc({ commitment: "confirmed" });
+
+    const state = await program.account.swapProposal.fetch(swapProposal);
+    expect(state.expiredAt.eq(expiredAt)).to.be.true;
+    expect(state.swapOptions.map((option) => option.id)).to.deep.eq([
+      optionId,
+      newOptionId,
+    ]);
+    state.swapOptions.forEach((option) => {
+      expect(option.askingItems.length).eq(2);
+    });
+  });
+});
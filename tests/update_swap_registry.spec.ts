@@ -6,7 +6,7 @@ import {
   EventParser,
   Program,
 } from "@project-serum/anchor";
-import { PublicKey, Keypair, SendTransactionError } from "@solana/web3.js";
+import { PublicKey, Keypair } from "@solana/web3.js";
 import { expect } from "chai";
 
 import { Swap } from "../target/types/swap";
@@ -104,7 +104,8 @@ describe("update_swap_program", async () => {
 
       throw new Error("Should be failed");
     } catch (e) {
-      expect(e instanceof SendTransactionError).to.be.true;
+      expect(e instanceof AnchorError).to.be.true;
+      expect(e.error.errorCode.code).equals("InvalidValue");
     }
   });
 });
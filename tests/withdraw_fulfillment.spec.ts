@@ -0,0 +1,171 @@
+import * as anchor from "@project-serum/anchor";
+import { AnchorError, BN, Program, web3 } from "@project-serum/anchor";
+import { Keypair, PublicKey } from "@solana/web3.js";
+import { getAccount } from "@solana/spl-token";
+import { expect } from "chai";
+
+import { Swap } from "../target/types/swap";
+import { airdrop, createProposal, itemAccounts, setupMint } from "./fixtures";
+
+describe("withdraw_fulfillment", async () => {
+  // Configure the client to use the local cluster.
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.Swap as Program<Swap>;
+
+  // find the swap account
+  const [swapRegistry] = await PublicKey.findProgramAddress(
+    [anchor.utils.bytes.utf8.encode("SEED::SWAP::PLATFORM")],
+    program.programId
+  );
+  const [swapTreasury] = await PublicKey.findProgramAddress(
+    [anchor.utils.bytes.utf8.encode("SEED::SWAP::TREASURY_SEED")],
+    program.programId
+  );
+
+  const proposalOwner = Keypair.generate();
+  const participant = Keypair.generate();
+  const proposalId = Keypair.generate().publicKey.toBase58().slice(0, 10);
+  const optionId = Keypair.generate().publicKey.toBase58().slice(0, 10);
+
+  let swapProposal;
+  let offeredMint;
+  let askingMint;
+  let askingItemIds;
+
+  before(async () => {
+    // funding the users
+    await airdrop([proposalOwner, participant]);
+
+    offeredMint = await setupMint([proposalOwner, participant]);
+    askingMint = await setupMint([proposalOwner, participant]);
+
+    askingItemIds = [0, 1].map(() =>
+      Keypair.generate().publicKey.toBase58().slice(0, 10)
+    );
+    swapProposal = await createProposal(proposalOwner, {
+      id: proposalId,
+      swapOptions: [
+        {
+          id: optionId,
+          askingItems: askingItemIds.map((id) => ({
+            id,
+            mintAccount: askingMint.mintAccount,
+            amount: new BN(web3.LAMPORTS_PER_SOL),
+            itemType: { currency: {} },
+          })),
+        },
+      ],
+      offeredItems: [
+        {
+          id: Keypair.generate().publicKey.toBase58().slice(0, 10),
+          mintAccount: offeredMint.mintAccount,
+          amount: new BN(web3.LAMPORTS_PER_SOL),
+          itemType: { currency: {} },
+        },
+      ],
+    });
+
+    await program.methods
+      .depositAll({
+        proposalId,
+        swapTokenVaultBumps: Buffer.from([offeredMint.swapTokenVaultBump]),
+      })
+      .accounts({
+        signer: proposalOwner.publicKey,
+        swapProposal,
+        swapRegistry,
+      })
+      .remainingAccounts(
+        itemAccounts(
+          offeredMint,
+          offeredMint.tokenAccounts[proposalOwner.publicKey.toBase58()]
+        )
+      )
+      .signers([proposalOwner])
+      .rpc({ commitment: "confirmed" });
+
+    // the participant reserves the proposal with a single asking item
+    await program.methods
+      // @ts-ignore
+      .transferAssetsToVault({
+        proposalId,
+        swapItemId: askingItemIds[0],
+        swapTokenVaultBump: askingMint.swapTokenVaultBump,
+        actionType: { fulfilling: {} },
+        optionId,
+      })
+      .accounts({
+        signer: participant.publicKey,
+        signerTokenAccount:
+          askingMint.tokenAccounts[participant.publicKey.toBase58()],
+        swapProposal,
+        swapTokenVault: askingMint.swapTokenVault,
+        swapRegistry,
+        swapTreasury,
+        mintAccount: askingMint.mintAccount,
+      })
+      .signers([participant])
+      .rpc({ commitment: "confirmed" });
+  });
+
+  it("[withdraw_fulfillment] should: only the reserving participant withdraws its fulfillment", async () => {
+    try {
+      await program.methods
+        .withdrawFulfillment({
+          proposalId,
+          swapTokenVaultBumps: Buffer.from([askingMint.swapTokenVaultBump]),
+        })
+        .accounts({
+          signer: proposalOwner.publicKey,
+          swapRegistry,
+          swapProposal,
+        })
+        .signers([proposalOwner])
+        .rpc({ commitment: "confirmed" });
+
+      throw new Error("Should be failed");
+    } catch (e) {
+      expect(e instanceof AnchorError).to.be.true;
+      expect(e.error.errorCode.code).equals("OnlyParticipant");
+    }
+  });
+
+  it("[withdraw_fulfillment] should: participant takes back its items and the proposal reopens", async () => {
+    await program.methods
+      .withdrawFulfillment({
+        proposalId,
+        swapTokenVaultBumps: Buffer.from([askingMint.swapTokenVaultBump]),
+      })
+      .accounts({
+        signer: participant.publicKey,
+        swapRegistry,
+        swapProposal,
+      })
+      .remainingAccounts(
+        itemAccounts(
+          askingMint,
+          askingMint.tokenAccounts[participant.publicKey.toBase58()]
+        )
+      )
+      .signers([participant])
+      .rpc({ commitment: "confirmed" });
+
+    const state = await program.account.swapProposal.fetch(swapProposal);
+    // @ts-ignore
+    expect(!!state.status.deposited).to.be.true;
+    expect(state.fulfilledBy.equals(PublicKey.default)).to.be.true;
+    expect(state.fulfilledWithOptionId).eq("");
+    state.swapOptions[0].askingItems.forEach((item) => {
+      // @ts-ignore
+      expect(!!item.status.created).to.be.true;
+    });
+
+    const participantAccount = await getAccount(
+      provider.connection,
+      askingMint.tokenAccounts[participant.publicKey.toBase58()]
+    );
+    expect(Number(participantAccount.amount)).eq(web3.LAMPORTS_PER_SOL * 10);
+  });
+});